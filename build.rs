@@ -5,12 +5,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "proto/google/cloud/kms/v1/service.proto",
         "proto/google/pubsub/v1/pubsub.proto",
         "proto/google/pubsub/v1/schema.proto",
+        "proto/google/rpc/status.proto",
+        "proto/google/rpc/error_details.proto",
     ];
     let output = "src/proto";
 
     tonic_build::configure()
         .out_dir(output)
-        .build_server(false)
+        // Servers are only needed by the fakes; keep them out of the public API otherwise.
+        .build_server(true)
+        .server_mod_attribute(
+            "google.pubsub.v1",
+            r#"#[cfg(any(test, feature = "pubsub-fake"))]"#,
+        )
+        .server_mod_attribute("google.cloud.kms.v1", "#[cfg(test)]")
+        .server_mod_attribute("google.iam.v1", "#[cfg(test)]")
+        .server_mod_attribute("google.storage.v2", "#[cfg(test)]")
         .build_client(true)
         .compile(&protos, &["proto"])?;
     Ok(())
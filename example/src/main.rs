@@ -129,7 +129,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     match opts.subcmd {
         SubCommands::Storage(opts) => match opts.subcmd {
             StorageCommands::Get(opts) => {
-                let mut client = Client::new().await?;
+                let client = Client::new().await?;
                 let data = client.object(&opts.bucket, &opts.object_id).await?;
 
                 if let Some(output_path) = opts.output {
@@ -139,7 +139,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             StorageCommands::Upload(opts) => {
-                let mut client = Client::new().await?;
+                let client = Client::new().await?;
                 let data = fs::read_to_string(opts.input)?;
 
                 client
@@ -154,15 +154,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
         SubCommands::Kms(opts) => match opts.subcmd {
             KmsCommands::ListKeyRings(opts) => {
-                let mut client = KmsClient::new().await?;
+                let client = KmsClient::new().await?;
                 dbg!(client.list_key_rings(&opts.parent).await?);
             }
             KmsCommands::ListCryptoKeys(opts) => {
-                let mut client = KmsClient::new().await?;
+                let client = KmsClient::new().await?;
                 dbg!(client.list_crypto_keys(&opts.parent).await?);
             }
             KmsCommands::Encrypt(opts) => {
-                let mut client = KmsClient::new().await?;
+                let client = KmsClient::new().await?;
                 let res = client.encrypt(&opts.key, opts.data.as_bytes()).await?;
 
                 println!("encrypt success.");
@@ -175,12 +175,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
         SubCommands::Pubsub(opts) => match opts.subcmd {
             PubsubSubCommands::Publish(opts) => {
-                let mut client = PubSubClient::new().await?;
+                let client = PubSubClient::new().await?;
                 let res = client.publish(&opts.topic, opts.data.as_bytes()).await?;
                 dbg!(res);
             }
             PubsubSubCommands::Pull(opts) => {
-                let mut client = PubSubClient::new().await?;
+                let client = PubSubClient::new().await?;
                 let res = dbg!(client.pull(&opts.subscription).await?);
                 for message in res.received_messages.into_iter() {
                     if let Some(mes) = message.message {
@@ -202,7 +202,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", path);
             }
 
-            let mut client = drive::Client::new().await?;
+            let client = drive::Client::new().await?;
             let data = std::fs::read(opts.input)?;
             dbg!(
                 client
@@ -1,53 +1,316 @@
-use std::path::Path;
+mod service_account;
 
-use gcp_auth::{AuthenticationManager, Token};
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use gcp_auth::AuthenticationManager;
+use serde::Deserialize;
+use tokio::sync::{Mutex, RwLock};
 
 use crate::error::AuthError;
 
-pub struct TokenManager {
+pub use service_account::ServiceAccount;
+
+/// The `type` of credential files holding a service account key.
+const SERVICE_ACCOUNT_TYPE: &str = "service_account";
+
+/// Tokens are refreshed this long before they actually expire, so that a request
+/// never goes out with a token that expires while it is in flight.
+const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+
+/// An OAuth2 access token, sent as `authorization: Bearer {token}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessToken {
+    token: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl AccessToken {
+    /// # Arguments
+    /// * `token`      - the bearer token.
+    /// * `expires_at` - `None` if the token never expires.
+    pub fn new(token: impl Into<String>, expires_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            token: token.into(),
+            expires_at,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.token
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    fn needs_refresh(&self) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at - Duration::seconds(REFRESH_MARGIN_SECS) <= Utc::now())
+            .unwrap_or(false)
+    }
+}
+
+impl From<gcp_auth::Token> for AccessToken {
+    fn from(token: gcp_auth::Token) -> Self {
+        Self::new(token.as_str(), token.expires_at())
+    }
+}
+
+/// A source of access tokens.
+///
+/// Every client accepts a provider through its builder's `with_token_provider`.
+/// Providers are not expected to cache; [`TokenManager`] caches on their behalf.
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    /// Fetches a fresh token for `scopes`.
+    ///
+    /// Returns `None` when requests should be sent without an `authorization` header.
+    async fn token(&self, scopes: &[&str]) -> Result<Option<AccessToken>, AuthError>;
+}
+
+#[async_trait]
+impl<T: TokenProvider + ?Sized> TokenProvider for Arc<T> {
+    async fn token(&self, scopes: &[&str]) -> Result<Option<AccessToken>, AuthError> {
+        (**self).token(scopes).await
+    }
+}
+
+/// Credentials found by `gcp_auth`'s default chain: `GOOGLE_APPLICATION_CREDENTIALS`,
+/// `gcloud`, the metadata server and application default credentials, in that order.
+///
+/// [`TokenManager::from_credential_file`] also uses it for credential files other
+/// than service account keys.
+pub struct DefaultCredentials {
     auth_manager: AuthenticationManager,
-    token: Option<Token>,
-    scopes: Vec<&'static str>,
 }
 
-impl TokenManager {
-    pub async fn new(scopes: &[&'static str]) -> Result<Self, AuthError> {
+impl DefaultCredentials {
+    pub async fn new() -> Result<Self, AuthError> {
         Ok(Self {
             auth_manager: gcp_auth::init().await?,
-            token: None,
-            scopes: scopes.to_owned(),
         })
     }
+}
+
+#[async_trait]
+impl TokenProvider for DefaultCredentials {
+    async fn token(&self, scopes: &[&str]) -> Result<Option<AccessToken>, AuthError> {
+        Ok(Some(self.auth_manager.get_token(scopes).await?.into()))
+    }
+}
+
+/// A fixed bearer token, e.g. one issued by a secret store.
+pub struct StaticToken {
+    token: AccessToken,
+}
+
+impl StaticToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: AccessToken::new(token, None),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for StaticToken {
+    async fn token(&self, _scopes: &[&str]) -> Result<Option<AccessToken>, AuthError> {
+        Ok(Some(self.token.clone()))
+    }
+}
+
+/// Sends requests without credentials, for emulators and local fakes.
+pub struct NoAuth;
+
+#[async_trait]
+impl TokenProvider for NoAuth {
+    async fn token(&self, _scopes: &[&str]) -> Result<Option<AccessToken>, AuthError> {
+        Ok(None)
+    }
+}
+
+/// The `type` field of a credential file, if it is JSON and has one.
+fn credential_type(json: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Credential {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    }
+    serde_json::from_str::<Credential>(json).ok()?.kind
+}
+
+/// Caches access tokens and refreshes them before they expire.
+///
+/// `TokenManager` is cheap to clone and can be shared between tasks; all clones
+/// share the same cached token. When the token needs to be refreshed, only one
+/// caller performs the refresh while the others wait for its result.
+#[derive(Clone)]
+pub struct TokenManager {
+    inner: Arc<Inner>,
+}
 
+struct Inner {
+    provider: Arc<dyn TokenProvider>,
+    scopes: Vec<&'static str>,
+    token: RwLock<Option<AccessToken>>,
+    refresh_lock: Mutex<()>,
+}
+
+impl TokenManager {
+    pub async fn new(scopes: &[&'static str]) -> Result<Self, AuthError> {
+        Ok(Self::with_provider(
+            Arc::new(DefaultCredentials::new().await?),
+            scopes,
+        ))
+    }
+
+    /// Service account keys are used through [`ServiceAccount`]; other credential
+    /// types, e.g. the `authorized_user` written by
+    /// `gcloud auth application-default login`, are handed to `gcp_auth`.
     pub async fn from_credential_file<T: AsRef<Path>>(
         path: T,
         scopes: &[&'static str],
     ) -> Result<Self, AuthError> {
-        Ok(Self {
-            auth_manager: gcp_auth::from_credentials_file(path).await?,
-            token: None,
-            scopes: scopes.to_owned(),
+        let json = tokio::fs::read_to_string(path.as_ref()).await?;
+        let provider: Arc<dyn TokenProvider> = match credential_type(&json).as_deref() {
+            Some(SERVICE_ACCOUNT_TYPE) => Arc::new(ServiceAccount::from_json(&json)?),
+            _ => Arc::new(DefaultCredentials {
+                auth_manager: gcp_auth::from_credentials_file(path).await?,
+            }),
+        };
+        Ok(Self::with_provider(provider, scopes))
+    }
+
+    pub fn with_provider(provider: Arc<dyn TokenProvider>, scopes: &[&'static str]) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                provider,
+                scopes: scopes.to_owned(),
+                token: RwLock::new(None),
+                refresh_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Returns `None` if the provider does not authenticate requests.
+    pub async fn get_token(&self) -> Result<Option<AccessToken>, AuthError> {
+        if let Some(token) = self.cached_token().await {
+            return Ok(Some(token));
+        }
+
+        // Only one caller refreshes; the others wait here and pick up its result.
+        let _guard = self.inner.refresh_lock.lock().await;
+        if let Some(token) = self.cached_token().await {
+            return Ok(Some(token));
+        }
+
+        let token = self._get_token().await?;
+        *self.inner.token.write().await = token.clone();
+        Ok(token)
+    }
+
+    async fn cached_token(&self) -> Option<AccessToken> {
+        self.inner
+            .token
+            .read()
+            .await
+            .as_ref()
+            .filter(|token| !token.needs_refresh())
+            .cloned()
+    }
+
+    async fn _get_token(&self) -> Result<Option<AccessToken>, AuthError> {
+        self.inner.provider.token(&self.inner.scopes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    struct CountingProvider {
+        calls: AtomicUsize,
+        lifetime: Duration,
+    }
+
+    #[async_trait]
+    impl TokenProvider for CountingProvider {
+        async fn token(&self, _scopes: &[&str]) -> Result<Option<AccessToken>, AuthError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            Ok(Some(AccessToken::new(
+                format!("token-{}", n),
+                Some(Utc::now() + self.lifetime),
+            )))
+        }
+    }
+
+    fn counting_provider(lifetime: Duration) -> Arc<CountingProvider> {
+        Arc::new(CountingProvider {
+            calls: AtomicUsize::new(0),
+            lifetime,
         })
     }
 
-    pub async fn get_token(&mut self) -> Result<&Token, AuthError> {
-        match &self.token {
-            None => {
-                let token = self._get_token().await?;
-                self.token = Some(token);
-            }
-            Some(current_token) => {
-                if current_token.has_expired() {
-                    let token = self._get_token().await?;
-                    self.token = Some(token);
-                }
-            }
+    #[tokio::test]
+    async fn test_get_token_is_cached() -> anyhow::Result<()> {
+        let provider = counting_provider(Duration::hours(1));
+        let manager = TokenManager::with_provider(provider.clone(), &["scope"]);
+
+        let tasks = (0..10)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move { manager.get_token().await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            assert_eq!(task.await??.unwrap().as_str(), "token-0");
         }
 
-        Ok(self.token.as_ref().unwrap())
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_token_refreshes_before_expiry() -> anyhow::Result<()> {
+        let provider = counting_provider(Duration::seconds(REFRESH_MARGIN_SECS - 1));
+        let manager = TokenManager::with_provider(provider.clone(), &["scope"]);
+
+        assert_eq!(manager.get_token().await?.unwrap().as_str(), "token-0");
+        assert_eq!(manager.get_token().await?.unwrap().as_str(), "token-1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_static_token_and_no_auth() -> anyhow::Result<()> {
+        let manager = TokenManager::with_provider(Arc::new(StaticToken::new("static")), &[]);
+        assert_eq!(
+            manager.get_token().await?,
+            Some(AccessToken::new("static", None))
+        );
+
+        let manager = TokenManager::with_provider(Arc::new(NoAuth), &[]);
+        assert_eq!(manager.get_token().await?, None);
+        Ok(())
     }
 
-    async fn _get_token(&mut self) -> Result<Token, AuthError> {
-        Ok(self.auth_manager.get_token(&self.scopes).await?)
+    #[test]
+    fn test_credential_type() {
+        assert_eq!(
+            credential_type(r#"{"type": "service_account", "client_email": "a@b"}"#).as_deref(),
+            Some(SERVICE_ACCOUNT_TYPE)
+        );
+        assert_eq!(
+            credential_type(r#"{"type": "authorized_user", "refresh_token": "t"}"#).as_deref(),
+            Some("authorized_user")
+        );
+        assert_eq!(credential_type("{}"), None);
+        assert_eq!(credential_type("not json"), None);
     }
 }
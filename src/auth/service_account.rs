@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{AccessToken, TokenProvider},
+    error::AuthError,
+};
+
+const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+const ASSERTION_LIFETIME_SECS: i64 = 60 * 60;
+
+/// A service account key, as downloaded from the IAM console.
+///
+/// Tokens are obtained by exchanging a signed JWT at the key's `token_uri`.
+pub struct ServiceAccount {
+    key: ServiceAccountKey,
+    encoding_key: EncodingKey,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    private_key_id: Option<String>,
+    token_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<i64>,
+}
+
+impl ServiceAccount {
+    pub async fn from_file<T: AsRef<Path>>(path: T) -> Result<Self, AuthError> {
+        let json = tokio::fs::read_to_string(path).await?;
+        Self::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, AuthError> {
+        let key: ServiceAccountKey = serde_json::from_str(json)?;
+        let encoding_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())?;
+        Ok(Self {
+            key,
+            encoding_key,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    fn assertion(&self, scopes: &[&str]) -> Result<String, AuthError> {
+        let now = Utc::now().timestamp();
+        let scope = scopes.join(" ");
+        let claims = Claims {
+            iss: &self.key.client_email,
+            scope: &scope,
+            aud: &self.key.token_uri,
+            iat: now,
+            exp: now + ASSERTION_LIFETIME_SECS,
+        };
+        let header = Header {
+            kid: self.key.private_key_id.clone(),
+            ..Header::new(Algorithm::RS256)
+        };
+
+        Ok(jsonwebtoken::encode(&header, &claims, &self.encoding_key)?)
+    }
+}
+
+#[async_trait]
+impl TokenProvider for ServiceAccount {
+    async fn token(&self, scopes: &[&str]) -> Result<Option<AccessToken>, AuthError> {
+        let assertion = self.assertion(scopes)?;
+        let res = self
+            .http
+            .post(&self.key.token_uri)
+            .form(&[("grant_type", GRANT_TYPE), ("assertion", &assertion)])
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(AuthError::TokenExchange {
+                status: res.status(),
+                response: res.text().await?,
+            });
+        }
+
+        let token: TokenResponse = res.json().await?;
+        let expires_at = token
+            .expires_in
+            .map(|seconds| Utc::now() + Duration::seconds(seconds));
+        Ok(Some(AccessToken::new(token.access_token, expires_at)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_rejects_invalid_key() {
+        let json = serde_json::json!({
+            "type": "service_account",
+            "client_email": "test@example.iam.gserviceaccount.com",
+            "private_key": "not a pem",
+            "token_uri": "https://oauth2.googleapis.com/token",
+        })
+        .to_string();
+
+        assert!(matches!(
+            ServiceAccount::from_json(&json),
+            Err(AuthError::Jwt(_))
+        ));
+        assert!(matches!(
+            ServiceAccount::from_json("{}"),
+            Err(AuthError::Json(_))
+        ));
+    }
+}
@@ -1,25 +1,26 @@
-use once_cell::sync::Lazy;
 use reqwest::{header::HeaderMap, StatusCode, Url};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{path::Path, sync::Arc};
 
 use crate::{
-    auth::TokenManager,
-    error::{AuthError, Error},
+    auth::{DefaultCredentials, ServiceAccount, TokenManager, TokenProvider},
+    endpoint::Endpoint,
+    error::{ApiError, Error},
+    options::CallOptions,
+    pagination::{ListOptions, Page, PageStream},
+    retry::RetryPolicy,
 };
 
-use super::handler::upload_file::upload_file;
+use super::handler::{list_files::list_files, upload_file::upload_file};
 
-const DRIVE_API_ENDPOINT: &str = "https://www.googleapis.com";
-#[cfg(not(test))]
-pub const ENDPOINT: Lazy<String> = Lazy::new(|| DRIVE_API_ENDPOINT.to_owned());
-
-#[cfg(test)]
-pub const ENDPOINT: Lazy<String> = Lazy::new(|| mockito::server_url());
+pub const ENDPOINT: &str = "https://www.googleapis.com";
 
+#[derive(Clone)]
 pub struct Client {
     token_manager: TokenManager,
     http: reqwest::Client,
+    endpoint: Url,
+    retry_policy: Option<RetryPolicy>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -31,9 +32,22 @@ pub enum GoogleDriveError {
     UnexpectedResponse {
         status: StatusCode,
         response: String,
+        /// `response` parsed as a JSON error body, if it is one. Boxed to keep
+        /// [`Error`] small.
+        error: Option<Box<ApiError>>,
     },
 }
 
+impl GoogleDriveError {
+    pub(crate) fn unexpected_response(status: StatusCode, response: String) -> Self {
+        Self::UnexpectedResponse {
+            status,
+            error: ApiError::parse(&response).map(Box::new),
+            response,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash)]
 pub enum Scope {
     /// See, edit, create, and delete all of your Google Drive files
@@ -93,56 +107,170 @@ pub struct File {
     pub writers_can_share: Option<String>,
 }
 
+#[derive(Default)]
+pub struct ClientBuilder {
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    endpoint: Option<Endpoint>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl ClientBuilder {
+    pub fn with_token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.token_provider = Some(Arc::new(token_provider));
+        self
+    }
+
+    /// Defaults to [`ENDPOINT`].
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Replaces the per-method defaults. By default listing is retried on transient
+    /// errors and uploads, which are not idempotent, are not.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub async fn build(self) -> Result<Client, Error> {
+        let token_provider = match self.token_provider {
+            Some(token_provider) => token_provider,
+            None => Arc::new(DefaultCredentials::new().await?),
+        };
+        let endpoint = match self.endpoint {
+            Some(endpoint) => endpoint,
+            None => Endpoint::new(ENDPOINT)?,
+        };
+
+        Ok(Client {
+            token_manager: TokenManager::with_provider(token_provider, &[Scope::Full.as_ref()]),
+            http: endpoint.http_client()?,
+            endpoint: endpoint.url().clone(),
+            retry_policy: self.retry_policy,
+        })
+    }
+}
+
 impl Client {
-    pub async fn new() -> Result<Self, AuthError> {
-        Ok(Self::_new(
-            TokenManager::new(&[Scope::Full.as_ref()]).await?,
-        ))
+    pub async fn new() -> Result<Self, Error> {
+        Self::builder().build().await
     }
 
-    pub async fn from_credential_file<T: AsRef<Path>>(path: T) -> Result<Self, AuthError> {
-        Ok(Self::_new(
-            TokenManager::from_credential_file(path, &[Scope::Full.as_ref()]).await?,
-        ))
+    pub async fn from_credential_file<T: AsRef<Path>>(path: T) -> Result<Self, Error> {
+        Self::builder()
+            .with_token_provider(ServiceAccount::from_file(path).await?)
+            .build()
+            .await
     }
 
-    fn _new(token_manager: TokenManager) -> Self {
-        Self {
-            token_manager,
-            http: reqwest::Client::new(),
-        }
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    pub async fn upload(&self, data: impl Into<Vec<u8>>, metadata: File) -> Result<File, Error> {
+        self.upload_with_options(data, metadata, &CallOptions::default())
+            .await
     }
 
-    pub async fn upload(
-        &mut self,
+    pub async fn upload_with_options(
+        &self,
         data: impl Into<Vec<u8>>,
         metadata: File,
+        options: &CallOptions,
     ) -> Result<File, Error> {
-        let headers = self.headers().await?;
+        let data = data.into();
 
-        upload_file(&self.http, headers, data, metadata).await
+        self.retry_policy(options, RetryPolicy::none)
+            .run(|| {
+                options.attempt(async {
+                    let headers = self.headers(options).await?;
+                    upload_file(
+                        &self.http,
+                        &self.endpoint,
+                        headers,
+                        data.clone(),
+                        metadata.clone(),
+                    )
+                    .await
+                })
+            })
+            .await
     }
 
-    pub(crate) fn build_drive_uri(path: &str, params: &[(&str, &str)]) -> Result<Url, Error> {
-        Self::build_uri(&ENDPOINT, path, params)
+    /// Streams all files matching `list_options`, fetching further pages as needed.
+    ///
+    /// The filter is sent as the `q` search query, e.g. `name contains 'report'`.
+    pub fn stream_files(&self, list_options: &ListOptions) -> PageStream<'_, File> {
+        self.stream_files_with_options(list_options, &CallOptions::default())
     }
 
-    pub(crate) fn build_uri(base: &str, path: &str, params: &[(&str, &str)]) -> Result<Url, Error> {
-        let mut uri = Url::parse(base)?.join(path)?;
+    pub fn stream_files_with_options(
+        &self,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, File> {
+        let list_options = list_options.clone();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let list_options = list_options.clone();
+            let options = options.clone();
+            async move {
+                let page_size = list_options.page_size().map(|n| n.to_string());
+                let params = [
+                    ("pageSize", page_size.as_deref()),
+                    ("pageToken", page_token.as_deref()),
+                    ("q", list_options.filter()),
+                    ("orderBy", list_options.order_by()),
+                ]
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, value?)))
+                .collect::<Vec<_>>();
+
+                let list = self
+                    .retry_policy(&options, RetryPolicy::default)
+                    .run(|| {
+                        options.attempt(async {
+                            let headers = self.headers(&options).await?;
+                            list_files(&self.http, &self.endpoint, headers, &params).await
+                        })
+                    })
+                    .await?;
+                Ok(Page::new(list.files, list.next_page_token))
+            }
+        })
+    }
+
+    /// The retry policy in `options`, the client's retry policy or `default`, in that order.
+    fn retry_policy(
+        &self,
+        options: &CallOptions,
+        default: impl FnOnce() -> RetryPolicy,
+    ) -> RetryPolicy {
+        options
+            .retry_policy()
+            .or(self.retry_policy.as_ref())
+            .cloned()
+            .unwrap_or_else(default)
+    }
+
+    pub(crate) fn build_uri(base: &Url, path: &str, params: &[(&str, &str)]) -> Result<Url, Error> {
+        let mut uri = base.join(path)?;
         for (key, value) in params {
             uri.query_pairs_mut().append_pair(key, value);
         }
         Ok(uri)
     }
 
-    async fn headers(&mut self) -> Result<HeaderMap, AuthError> {
+    async fn headers(&self, options: &CallOptions) -> Result<HeaderMap, Error> {
         let mut header = HeaderMap::new();
-        header.insert(
-            reqwest::header::AUTHORIZATION,
-            format!("Bearer {}", self.token_manager.get_token().await?.as_str())
-                .parse()
-                .unwrap(),
-        );
+        options.apply_to_headers(&mut header)?;
+        if let Some(token) = self.token_manager.get_token().await? {
+            header.insert(
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {}", token.as_str()).parse().unwrap(),
+            );
+        }
         Ok(header)
     }
 }
@@ -156,18 +284,18 @@ mod tests {
     #[test]
     fn test_build_uri() -> anyhow::Result<()> {
         assert_eq!(
-            Client::build_uri(DRIVE_API_ENDPOINT, "hoge/goo.txt", &[])?,
+            Client::build_uri(&Url::parse(ENDPOINT)?, "hoge/goo.txt", &[])?,
             Url::parse("https://www.googleapis.com/hoge/goo.txt")?
         );
 
         assert_eq!(
-            Client::build_uri(DRIVE_API_ENDPOINT, "/hoge/goo.txt", &[])?,
+            Client::build_uri(&Url::parse(ENDPOINT)?, "/hoge/goo.txt", &[])?,
             Url::parse("https://www.googleapis.com/hoge/goo.txt")?
         );
 
         assert_eq!(
             dbg!(Client::build_uri(
-                DRIVE_API_ENDPOINT,
+                &Url::parse(ENDPOINT)?,
                 "/hoge/goo.txt",
                 &[("hoge", "foo")]
             )?),
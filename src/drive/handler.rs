@@ -1 +1,2 @@
+pub mod list_files;
 pub mod upload_file;
@@ -0,0 +1,74 @@
+use reqwest::{header::HeaderMap, Url};
+use serde::Deserialize;
+
+use crate::{
+    drive::{
+        client::{File, GoogleDriveError},
+        Client,
+    },
+    error::Error,
+};
+
+const FILES_PATH: &str = "drive/v3/files";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileList {
+    #[serde(default)]
+    pub files: Vec<File>,
+    pub next_page_token: Option<String>,
+}
+
+/// cf. https://developers.google.com/drive/api/v3/reference/files/list
+pub async fn list_files(
+    http: &reqwest::Client,
+    endpoint: &Url,
+    headers: HeaderMap,
+    params: &[(&str, &str)],
+) -> Result<FileList, Error> {
+    let url = Client::build_uri(endpoint, FILES_PATH, params)?;
+    let res = http.get(url).headers(headers).send().await?;
+    if res.status().is_success() {
+        Ok(res.json().await?)
+    } else {
+        Err(GoogleDriveError::unexpected_response(res.status(), res.text().await?).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{mock, Matcher};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_files() -> anyhow::Result<()> {
+        let _m = mock("GET", "/drive/v3/files")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("q".into(), "name = 'hoge'".into()),
+                Matcher::UrlEncoded("pageToken".into(), "token".into()),
+            ]))
+            .with_body(
+                serde_json::json!({
+                    "kind": "drive#fileList",
+                    "files": [{"kind": "drive#file", "id": "1", "name": "hoge", "mimeType": "text/plain"}]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let list = list_files(
+            &reqwest::Client::new(),
+            &Url::parse(&mockito::server_url())?,
+            HeaderMap::new(),
+            &[("q", "name = 'hoge'"), ("pageToken", "token")],
+        )
+        .await?;
+
+        assert_eq!(list.files.len(), 1);
+        assert_eq!(list.files[0].name, "hoge");
+        assert_eq!(list.next_page_token, None);
+        Ok(())
+    }
+}
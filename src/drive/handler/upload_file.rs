@@ -15,12 +15,13 @@ const HEADER_UPLOAD_CONTENT_LENGTH: &str = "X-Upload-Content-Length";
 
 pub async fn upload_file(
     http: &reqwest::Client,
+    endpoint: &Url,
     headers: HeaderMap,
     data: impl Into<Vec<u8>>,
     metadata: File,
 ) -> Result<File, Error> {
     let data = data.into();
-    let url = resume_url(http, &data, headers.clone(), &metadata).await?;
+    let url = resume_url(http, endpoint, &data, headers.clone(), &metadata).await?;
     upload_request(http, url, data, headers, &metadata).await
 }
 
@@ -45,36 +46,34 @@ async fn upload_request(
 
 async fn resume_url(
     http: &reqwest::Client,
+    endpoint: &Url,
     data: &[u8],
     headers: HeaderMap,
     metadata: &File,
 ) -> Result<Url, Error> {
-    let res = resume_request(http, data, headers, metadata).await?;
+    let res = resume_request(http, endpoint, data, headers, metadata).await?;
     match res.status() {
         StatusCode::OK => {
             if let Some(x) = res.headers().get(reqwest::header::LOCATION) {
                 return Ok(x.to_str()?.parse()?);
             }
-            return Err(GoogleDriveError::ResumeUrlNotFound {
+            Err(GoogleDriveError::ResumeUrlNotFound {
                 response: res.text().await?,
             }
-            .into());
+            .into())
         }
-        _ => Err(GoogleDriveError::UnexpectedResponse {
-            status: res.status(),
-            response: res.text().await?,
-        }
-        .into()),
+        status => Err(GoogleDriveError::unexpected_response(status, res.text().await?).into()),
     }
 }
 
 async fn resume_request(
     http: &reqwest::Client,
+    endpoint: &Url,
     data: &[u8],
     headers: HeaderMap,
     metadata: &File,
 ) -> Result<reqwest::Response, Error> {
-    let resume_url = Client::build_drive_uri(UPLOAD_PATH, &[("uploadType", "resumable")])?;
+    let resume_url = Client::build_uri(endpoint, UPLOAD_PATH, &[("uploadType", "resumable")])?;
 
     Ok(http
         .post(resume_url)
@@ -128,7 +127,8 @@ mod tests {
             .with_header(reqwest::header::LOCATION.as_str(), url)
             .create();
 
-        let resumable_url = resume_url(&client, &data, headers, &metadata).await?;
+        let endpoint = Url::parse(&mockito::server_url())?;
+        let resumable_url = resume_url(&client, &endpoint, &data, headers, &metadata).await?;
 
         _mock.assert();
         assert_eq!(resumable_url, Url::parse(url)?);
@@ -172,7 +172,8 @@ mod tests {
             )
             .create();
 
-        let file = upload_file(&client, headers, data, metadata.clone()).await?;
+        let endpoint = Url::parse(&mockito::server_url())?;
+        let file = upload_file(&client, &endpoint, headers, data, metadata.clone()).await?;
 
         resume_mock.assert();
         upload_mock.assert();
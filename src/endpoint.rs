@@ -0,0 +1,161 @@
+use std::env;
+
+use reqwest::Url;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+
+use crate::{error::Error, proto::TLS_CERT};
+
+/// Where a client sends its requests.
+///
+/// TLS is used when the URL scheme is `https`. For gRPC clients the bundled Google
+/// root certificates are trusted unless a custom CA is given; HTTP clients trust the
+/// system roots plus the custom CA, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    url: Url,
+    ca_certificate: Option<Vec<u8>>,
+    domain_name: Option<String>,
+}
+
+impl Endpoint {
+    /// Fails with [`Error::InvalidEndpoint`] unless the scheme is `http` or `https`.
+    ///
+    /// # Arguments
+    /// * `url` - base URL including the scheme, e.g. `https://pubsub.googleapis.com`
+    pub fn new(url: &str) -> Result<Self, Error> {
+        let url = Url::parse(url)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::InvalidEndpoint(format!(
+                "{} must start with http:// or https://",
+                url
+            )));
+        }
+        Ok(Self {
+            url,
+            ca_certificate: None,
+            domain_name: None,
+        })
+    }
+
+    /// Switches between `https` and plaintext `http`.
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        let scheme = if tls { "https" } else { "http" };
+        // `new` only accepts `http` and `https`, between which switching cannot fail.
+        self.url.set_scheme(scheme).unwrap();
+        self
+    }
+
+    /// Trusts the given PEM encoded CA certificate.
+    pub fn with_ca_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.ca_certificate = Some(pem.into());
+        self
+    }
+
+    /// Overrides the name the server certificate is verified against.
+    /// Defaults to the host of the URL.
+    pub fn with_domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.domain_name = Some(domain_name.into());
+        self
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn is_tls(&self) -> bool {
+        self.url.scheme() == "https"
+    }
+
+    pub(crate) async fn connect(&self) -> Result<Channel, Error> {
+        let mut channel = Channel::from_shared(self.url.to_string())?;
+
+        if self.is_tls() {
+            let domain_name = self
+                .domain_name
+                .as_deref()
+                .or_else(|| self.url.host_str())
+                .unwrap_or_default();
+            let tls_config = ClientTlsConfig::new()
+                .ca_certificate(Certificate::from_pem(
+                    self.ca_certificate.as_deref().unwrap_or(TLS_CERT),
+                ))
+                .domain_name(domain_name);
+            channel = channel.tls_config(tls_config)?;
+        }
+
+        Ok(channel.connect().await?)
+    }
+
+    pub(crate) fn http_client(&self) -> Result<reqwest::Client, Error> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(pem) = &self.ca_certificate {
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Returns the endpoint of a local emulator when the environment variable `var`
+/// (e.g. `PUBSUB_EMULATOR_HOST`) is set.
+///
+/// The value is either `host:port` or a full URL; emulators are always plaintext.
+pub fn emulator_endpoint(var: &str) -> Result<Option<Endpoint>, Error> {
+    match env::var(var) {
+        Ok(host) if !host.is_empty() => Ok(Some(parse_emulator_host(&host)?)),
+        _ => Ok(None),
+    }
+}
+
+fn parse_emulator_host(host: &str) -> Result<Endpoint, Error> {
+    if host.contains("://") {
+        Endpoint::new(host)
+    } else {
+        Ok(Endpoint::new(&format!("http://{}", host))?.with_tls(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_with_tls() -> anyhow::Result<()> {
+        let endpoint = Endpoint::new("https://pubsub.googleapis.com")?;
+        assert!(endpoint.is_tls());
+
+        let endpoint = endpoint.with_tls(false);
+        assert!(!endpoint.is_tls());
+        assert_eq!(endpoint.url().as_str(), "http://pubsub.googleapis.com/");
+
+        let endpoint = Endpoint::new("http://localhost:8085")?.with_tls(true);
+        assert_eq!(endpoint.url().as_str(), "https://localhost:8085/");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_rejects_other_schemes() {
+        for url in ["localhost:8085", "grpc://localhost:8085", "file:///tmp"] {
+            assert!(
+                matches!(Endpoint::new(url), Err(Error::InvalidEndpoint(_))),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_emulator_host() -> anyhow::Result<()> {
+        assert_eq!(
+            parse_emulator_host("localhost:8085")?,
+            Endpoint::new("http://localhost:8085")?
+        );
+        assert_eq!(
+            parse_emulator_host("http://0.0.0.0:4443")?,
+            Endpoint::new("http://0.0.0.0:4443")?
+        );
+        Ok(())
+    }
+}
@@ -1,23 +1,48 @@
+mod api_error;
+mod status;
+
 use std::env;
 use std::io;
+use std::time::Duration;
 
-use reqwest::header::ToStrError;
+use reqwest::{
+    header::{InvalidHeaderName, InvalidHeaderValue, ToStrError},
+    StatusCode,
+};
+use tonic::Code;
 
 use crate::drive::client::GoogleDriveError;
+use crate::kms::client::KmsError;
+use crate::pubsub::client::PubSubError;
 use crate::storage::client::CloudStorageError;
 
+pub use api_error::{ApiError, ApiErrorItem};
+pub use status::{ErrorDetails, GrpcStatus};
+
 /// The main error-handling type.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     /// An unexpected status code was received.
     #[error("unexpected status from GCP: {0}")]
-    Status(#[from] tonic::Status),
+    Status(Box<GrpcStatus>),
     /// An error with the gRPC transport channel.
     #[error("transport error: {0}")]
     Transport(#[from] tonic::transport::Error),
     /// An error with the gRPC metadata value.
     #[error("metadata parse error: {0}")]
     InvalidMetadata(#[from] tonic::metadata::errors::InvalidMetadataValue),
+    /// An error with the gRPC metadata key.
+    #[error("metadata key parse error: {0}")]
+    InvalidMetadataKey(#[from] tonic::metadata::errors::InvalidMetadataKey),
+    /// An error with an HTTP header name.
+    #[error("header name parse error: {0}")]
+    InvalidHeaderName(#[from] InvalidHeaderName),
+    /// An error with an HTTP header value.
+    #[error("header value parse error: {0}")]
+    InvalidHeaderValue(#[from] InvalidHeaderValue),
+    /// An attempt did not complete within the timeout set in `CallOptions`.
+    #[error("call timed out after {0:?}")]
+    Timeout(Duration),
     /// An IO error.
     #[error("IO error: {0}")]
     IO(#[from] io::Error),
@@ -36,9 +61,24 @@ pub enum Error {
     /// url error.
     #[error("url error: {0}")]
     Url(#[from] url::ParseError),
+    /// An endpoint URL whose scheme is neither `http` nor `https`.
+    #[error("invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+    /// uri error (gRPC endpoints).
+    #[error("uri error: {0}")]
+    InvalidUri(#[from] tonic::codegen::http::uri::InvalidUri),
     /// cloud storage API error.
     #[error("cloud storage api error: {0}")]
     CloudStorage(#[from] CloudStorageError),
+    /// Cloud KMS error detected on the client side.
+    #[error("kms error: {0}")]
+    Kms(#[from] KmsError),
+    /// Pub/Sub error detected on the client side.
+    #[error("pubsub error: {0}")]
+    PubSub(#[from] PubSubError),
+    /// A checksum did not match, meaning that data was corrupted in transit.
+    #[error("integrity check failed: {0}")]
+    Integrity(String),
     /// Google Drive API error.
     #[error("google drive api error: {0}")]
     GooleDrive(#[from] GoogleDriveError),
@@ -47,6 +87,94 @@ pub enum Error {
     HeaderValueIsNotString(#[from] ToStrError),
 }
 
+impl From<tonic::Status> for Error {
+    fn from(status: tonic::Status) -> Self {
+        Self::Status(Box::new(status.into()))
+    }
+}
+
+impl Error {
+    /// Whether the requested resource does not exist (gRPC `NOT_FOUND` or HTTP 404).
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::Status(status) => status.code() == Code::NotFound,
+            _ => self.http_status() == Some(StatusCode::NOT_FOUND),
+        }
+    }
+
+    /// Whether the call is worth retrying, if it is idempotent.
+    ///
+    /// gRPC: `UNAVAILABLE`, `DEADLINE_EXCEEDED`, `RESOURCE_EXHAUSTED` and `ABORTED`.
+    /// HTTP: 408, 429 and 5xx responses, connection failures and timeouts.
+    /// Both: attempts exceeding the timeout in `CallOptions` and checksum mismatches.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status(status) => matches!(
+                status.code(),
+                Code::Unavailable
+                    | Code::DeadlineExceeded
+                    | Code::ResourceExhausted
+                    | Code::Aborted
+            ),
+            _ => match self.http_status() {
+                Some(status) => {
+                    status == StatusCode::REQUEST_TIMEOUT
+                        || status == StatusCode::TOO_MANY_REQUESTS
+                        || status.is_server_error()
+                }
+                None => self.is_transport_failure(),
+            },
+        }
+    }
+
+    /// The delay the server asked for before retrying, from `google.rpc.RetryInfo`.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        match self {
+            Self::Status(status) => status.details().retry_delay(),
+            _ => self.api_error()?.retry_delay(),
+        }
+    }
+
+    /// The machine readable reason of the error, e.g. `RATE_LIMIT_EXCEEDED`.
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Self::Status(status) => status.details().reason(),
+            _ => self.api_error()?.reason(),
+        }
+    }
+
+    /// The status of an unsuccessful HTTP response.
+    pub fn http_status(&self) -> Option<StatusCode> {
+        match self {
+            Self::CloudStorage(CloudStorageError::ErrorResponse { status, .. })
+            | Self::GooleDrive(GoogleDriveError::UnexpectedResponse { status, .. }) => {
+                Some(*status)
+            }
+            _ => None,
+        }
+    }
+
+    /// The parsed body of an unsuccessful HTTP response.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::CloudStorage(CloudStorageError::ErrorResponse { error, .. })
+            | Self::GooleDrive(GoogleDriveError::UnexpectedResponse { error, .. }) => {
+                error.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Connection failures, timeouts and data corrupted in transit, retryable whatever the API.
+    pub(crate) fn is_transport_failure(&self) -> bool {
+        match self {
+            Self::Transport(_) | Self::Timeout(_) | Self::Integrity(_) => true,
+            Self::Reqwest(error) => error.is_connect() || error.is_timeout(),
+            _ => false,
+        }
+    }
+}
+
 /// The error type for value conversions.
 #[derive(Debug, thiserror::Error)]
 pub enum ConvertError {
@@ -69,4 +197,60 @@ pub enum AuthError {
     /// A JWT-related error.
     #[error("GcpAuth error: {0}")]
     GcpAuth(#[from] gcp_auth::Error),
+    /// The credential file could not be read.
+    #[error("IO error: {0}")]
+    IO(#[from] io::Error),
+    /// The credential JSON could not be parsed.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The service account key could not be used to sign a JWT.
+    #[error("JWT error: {0}")]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+    /// The token endpoint could not be reached.
+    #[error("HTTP error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    /// The token endpoint rejected the JWT assertion.
+    #[error("token exchange failed. status: {status} response: {response}")]
+    TokenExchange {
+        status: StatusCode,
+        response: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_is_not_found() {
+        let error: Error = tonic::Status::not_found("no such key").into();
+        assert!(error.is_not_found());
+        assert!(!error.is_retryable());
+
+        let error: Error = CloudStorageError::error_response(
+            StatusCode::NOT_FOUND,
+            r#"{"error":{"code":404,"message":"No such object","errors":[{"reason":"notFound"}]}}"#
+                .into(),
+        )
+        .into();
+        assert!(error.is_not_found());
+        assert_eq!(error.reason(), Some("notFound"));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let error: Error = tonic::Status::unavailable("try again").into();
+        assert!(error.is_retryable());
+
+        let error: Error =
+            GoogleDriveError::unexpected_response(StatusCode::TOO_MANY_REQUESTS, "".into()).into();
+        assert!(error.is_retryable());
+        assert_eq!(error.api_error(), None);
+
+        let error: Error =
+            GoogleDriveError::unexpected_response(StatusCode::FORBIDDEN, "".into()).into();
+        assert!(!error.is_retryable());
+    }
 }
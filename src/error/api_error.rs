@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+const RETRY_INFO_TYPE: &str = "type.googleapis.com/google.rpc.RetryInfo";
+const ERROR_INFO_TYPE: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+
+/// The JSON error body returned by Google's REST APIs.
+///
+/// cf. https://cloud.google.com/apis/design/errors#http_mapping
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiError {
+    pub code: u16,
+    pub message: String,
+    /// Canonical status name, e.g. `NOT_FOUND`. Not sent by every API.
+    pub status: Option<String>,
+    /// Legacy per-error entries, still the only detail sent by Cloud Storage and Drive.
+    #[serde(default)]
+    pub errors: Vec<ApiErrorItem>,
+    /// `google.rpc` error details in their JSON form, keyed by `@type`.
+    #[serde(default)]
+    pub details: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiErrorItem {
+    pub domain: Option<String>,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub location: Option<String>,
+    #[serde(rename = "locationType")]
+    pub location_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    error: ApiError,
+}
+
+impl ApiError {
+    /// Parses a `{"error": {...}}` response body. Returns `None` for any other body.
+    pub fn parse(body: &str) -> Option<Self> {
+        serde_json::from_str::<Envelope>(body)
+            .ok()
+            .map(|envelope| envelope.error)
+    }
+
+    /// The machine readable reason, e.g. `notFound` or `RATE_LIMIT_EXCEEDED`.
+    pub fn reason(&self) -> Option<&str> {
+        self.detail(ERROR_INFO_TYPE)
+            .and_then(|info| info.get("reason")?.as_str())
+            .or_else(|| self.errors.iter().find_map(|item| item.reason.as_deref()))
+    }
+
+    /// How long the server asked the client to wait before retrying.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        let delay = self.detail(RETRY_INFO_TYPE)?.get("retryDelay")?.as_str()?;
+        // Durations are encoded as decimal seconds with an `s` suffix, e.g. `1.5s`.
+        let seconds: f64 = delay.strip_suffix('s')?.parse().ok()?;
+        Duration::try_from_secs_f64(seconds).ok()
+    }
+
+    fn detail(&self, type_url: &str) -> Option<&serde_json::Value> {
+        self.details
+            .iter()
+            .find(|detail| detail.get("@type").and_then(|t| t.as_str()) == Some(type_url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_storage_error() {
+        let body = serde_json::json!({
+            "error": {
+                "code": 404,
+                "message": "No such object: bucket/name",
+                "errors": [{
+                    "message": "No such object: bucket/name",
+                    "domain": "global",
+                    "reason": "notFound"
+                }]
+            }
+        })
+        .to_string();
+
+        let error = ApiError::parse(&body).unwrap();
+        assert_eq!(error.code, 404);
+        assert_eq!(error.reason(), Some("notFound"));
+        assert_eq!(error.retry_delay(), None);
+    }
+
+    #[test]
+    fn test_parse_error_details() {
+        let body = serde_json::json!({
+            "error": {
+                "code": 429,
+                "message": "Quota exceeded",
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [
+                    {
+                        "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                        "reason": "RATE_LIMIT_EXCEEDED",
+                        "domain": "googleapis.com"
+                    },
+                    {
+                        "@type": "type.googleapis.com/google.rpc.RetryInfo",
+                        "retryDelay": "1.5s"
+                    }
+                ]
+            }
+        })
+        .to_string();
+
+        let error = ApiError::parse(&body).unwrap();
+        assert_eq!(error.status.as_deref(), Some("RESOURCE_EXHAUSTED"));
+        assert_eq!(error.reason(), Some("RATE_LIMIT_EXCEEDED"));
+        assert_eq!(error.retry_delay(), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn test_retry_delay_out_of_range() {
+        for delay in ["1e30s", "-1s", "NaNs"] {
+            let body = serde_json::json!({
+                "error": {
+                    "code": 503,
+                    "message": "Unavailable",
+                    "details": [{
+                        "@type": "type.googleapis.com/google.rpc.RetryInfo",
+                        "retryDelay": delay
+                    }]
+                }
+            })
+            .to_string();
+            assert_eq!(ApiError::parse(&body).unwrap().retry_delay(), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_non_json() {
+        assert_eq!(ApiError::parse("<html>Bad Gateway</html>"), None);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{fmt, time::Duration};
+
+use prost::Message;
+use tonic::Code;
+
+use crate::proto::google::rpc::{
+    BadRequest, DebugInfo, ErrorInfo, Help, LocalizedMessage, PreconditionFailure, QuotaFailure,
+    RequestInfo, ResourceInfo, RetryInfo, Status,
+};
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/";
+
+/// A gRPC status together with the `google.rpc` error details sent in its
+/// `grpc-status-details-bin` trailer.
+#[derive(Debug)]
+pub struct GrpcStatus {
+    status: tonic::Status,
+    details: ErrorDetails,
+}
+
+impl GrpcStatus {
+    pub fn code(&self) -> Code {
+        self.status.code()
+    }
+
+    pub fn message(&self) -> &str {
+        self.status.message()
+    }
+
+    pub fn details(&self) -> &ErrorDetails {
+        &self.details
+    }
+
+    pub fn as_status(&self) -> &tonic::Status {
+        &self.status
+    }
+
+    pub fn into_status(self) -> tonic::Status {
+        self.status
+    }
+}
+
+impl From<tonic::Status> for GrpcStatus {
+    fn from(status: tonic::Status) -> Self {
+        // Details are best effort; a malformed trailer must not hide the status itself.
+        let details = ErrorDetails::decode(status.details()).unwrap_or_default();
+        Self { status, details }
+    }
+}
+
+impl fmt::Display for GrpcStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.status.fmt(f)
+    }
+}
+
+/// The standard error payloads of `google/rpc/error_details.proto`.
+///
+/// cf. https://cloud.google.com/apis/design/errors#error_details
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorDetails {
+    pub error_info: Option<ErrorInfo>,
+    pub retry_info: Option<RetryInfo>,
+    pub debug_info: Option<DebugInfo>,
+    pub quota_failure: Option<QuotaFailure>,
+    pub precondition_failure: Option<PreconditionFailure>,
+    pub bad_request: Option<BadRequest>,
+    pub request_info: Option<RequestInfo>,
+    pub resource_info: Option<ResourceInfo>,
+    pub help: Option<Help>,
+    pub localized_message: Option<LocalizedMessage>,
+}
+
+impl ErrorDetails {
+    /// Decodes a serialized `google.rpc.Status`. Unknown detail types are skipped.
+    pub fn decode(bytes: &[u8]) -> Result<Self, prost::DecodeError> {
+        let mut details = Self::default();
+        if bytes.is_empty() {
+            return Ok(details);
+        }
+
+        for any in Status::decode(bytes)?.details {
+            let value = any.value.as_slice();
+            match any.type_url.trim_start_matches(TYPE_URL_PREFIX) {
+                "google.rpc.ErrorInfo" => details.error_info = Some(Message::decode(value)?),
+                "google.rpc.RetryInfo" => details.retry_info = Some(Message::decode(value)?),
+                "google.rpc.DebugInfo" => details.debug_info = Some(Message::decode(value)?),
+                "google.rpc.QuotaFailure" => details.quota_failure = Some(Message::decode(value)?),
+                "google.rpc.PreconditionFailure" => {
+                    details.precondition_failure = Some(Message::decode(value)?)
+                }
+                "google.rpc.BadRequest" => details.bad_request = Some(Message::decode(value)?),
+                "google.rpc.RequestInfo" => details.request_info = Some(Message::decode(value)?),
+                "google.rpc.ResourceInfo" => details.resource_info = Some(Message::decode(value)?),
+                "google.rpc.Help" => details.help = Some(Message::decode(value)?),
+                "google.rpc.LocalizedMessage" => {
+                    details.localized_message = Some(Message::decode(value)?)
+                }
+                _ => {}
+            }
+        }
+        Ok(details)
+    }
+
+    /// How long the server asked the client to wait before retrying.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        let delay = self.retry_info.as_ref()?.retry_delay.as_ref()?;
+        Some(Duration::new(
+            delay.seconds.max(0) as u64,
+            delay.nanos.max(0) as u32,
+        ))
+    }
+
+    /// The machine readable reason of the `ErrorInfo`, e.g. `RATE_LIMIT_EXCEEDED`.
+    pub fn reason(&self) -> Option<&str> {
+        self.error_info.as_ref().map(|info| info.reason.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn any(type_name: &str, message: &impl Message) -> prost_types::Any {
+        prost_types::Any {
+            type_url: format!("{}{}", TYPE_URL_PREFIX, type_name),
+            value: message.encode_to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_from_tonic_status() {
+        let error_info = ErrorInfo {
+            reason: "RATE_LIMIT_EXCEEDED".into(),
+            domain: "pubsub.googleapis.com".into(),
+            metadata: Default::default(),
+        };
+        let retry_info = RetryInfo {
+            retry_delay: Some(prost_types::Duration {
+                seconds: 2,
+                nanos: 500_000_000,
+            }),
+        };
+        let status = Status {
+            code: Code::ResourceExhausted as i32,
+            message: "slow down".into(),
+            details: vec![
+                any("google.rpc.ErrorInfo", &error_info),
+                any("google.rpc.RetryInfo", &retry_info),
+                any("google.example.Unknown", &error_info),
+            ],
+        };
+
+        let status: GrpcStatus = tonic::Status::with_details(
+            Code::ResourceExhausted,
+            "slow down",
+            status.encode_to_vec().into(),
+        )
+        .into();
+
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert_eq!(status.details().error_info, Some(error_info));
+        assert_eq!(status.details().reason(), Some("RATE_LIMIT_EXCEEDED"));
+        assert_eq!(
+            status.details().retry_delay(),
+            Some(Duration::from_millis(2500))
+        );
+    }
+
+    #[test]
+    fn test_from_tonic_status_without_details() {
+        let status: GrpcStatus = tonic::Status::not_found("no such topic").into();
+        assert_eq!(status.details(), &ErrorDetails::default());
+
+        let status: GrpcStatus =
+            tonic::Status::with_details(Code::Internal, "oops", vec![0xff, 0xff].into()).into();
+        assert_eq!(status.details(), &ErrorDetails::default());
+    }
+}
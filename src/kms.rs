@@ -1,3 +1,13 @@
 pub mod client;
+pub mod crypto_key;
+pub mod envelope;
+#[cfg(test)]
+pub(crate) mod fake;
+pub mod import_job;
+mod integrity;
+pub mod public_key;
 
 pub use client::KmsClient;
+pub use crypto_key::CryptoKeyBuilder;
+pub use envelope::EnvelopeCipher;
+pub use public_key::PublicKey;
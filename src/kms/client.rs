@@ -1,158 +1,691 @@
-use tonic::{
-    transport::{Certificate, Channel, ClientTlsConfig},
-    IntoRequest, Request,
+mod asymmetric;
+mod import;
+mod lifecycle;
+mod mac;
+mod random;
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
 };
 
+use tonic::{transport::Channel, Code, IntoRequest, Request, Response, Status};
+
 use crate::{
-    auth::TokenManager,
+    auth::{DefaultCredentials, TokenManager, TokenProvider},
+    endpoint::Endpoint,
     error::Error,
-    proto::{
-        google::cloud::kms::v1::{
-            key_management_service_client::KeyManagementServiceClient, DecryptRequest,
-            DecryptResponse, EncryptRequest, EncryptResponse, ListCryptoKeysRequest,
-            ListCryptoKeysResponse, ListKeyRingsRequest, ListKeyRingsResponse,
-        },
-        TLS_CERT,
+    kms::{
+        crypto_key::CryptoKeyVersionAlgorithm,
+        integrity::{crc32c, ensure_verified, ensure_version_of, verify_crc32c},
+    },
+    options::CallOptions,
+    pagination::{ListOptions, Page, PageStream},
+    proto::google::cloud::kms::v1::{
+        key_management_service_client::KeyManagementServiceClient, CryptoKey, DecryptRequest,
+        DecryptResponse, EncryptRequest, EncryptResponse, KeyRing, ListCryptoKeysRequest,
+        ListCryptoKeysResponse, ListKeyRingsRequest, ListKeyRingsResponse,
     },
+    retry::RetryPolicy,
     util::construct_request,
 };
 
-pub const DOMAIN_NAME: &str = "cloudkms.googleapis.com";
 pub const ENDPOINT: &str = "https://cloudkms.googleapis.com";
 pub const SCOPES: [&str; 2] = [
     "https://www.googleapis.com/auth/cloud-platform",
     "https://www.googleapis.com/auth/cloudkms",
 ];
 
+// cf. https://github.com/googleapis/googleapis/blob/master/google/cloud/kms/v1/cloudkms_grpc_service_config.json
+const RETRY_CODES: &[Code] = &[Code::Unavailable, Code::DeadlineExceeded];
+/// Creates are not idempotent: one that ran out of time may still have been applied.
+const CREATE_RETRY_CODES: &[Code] = &[Code::Unavailable];
+
+const MAX_PLAINTEXT_SIZE: usize = 64 * 1024;
+const MAX_AAD_SIZE: usize = 64 * 1024;
+
+#[derive(thiserror::Error, Debug)]
+pub enum KmsError {
+    #[error("{0:?} is not supported by this operation")]
+    UnsupportedAlgorithm(CryptoKeyVersionAlgorithm),
+
+    #[error("invalid public key: {0}")]
+    InvalidPublicKey(String),
+
+    #[error("signature verification failed")]
+    InvalidSignature,
+
+    #[error("MAC verification failed")]
+    InvalidMac,
+
+    #[error("RSA error: {0}")]
+    Rsa(String),
+
+    #[error("{field} is {size} bytes, more than the {limit} allowed")]
+    TooLarge {
+        field: &'static str,
+        size: usize,
+        limit: usize,
+    },
+
+    #[error("import failed: {0}")]
+    InvalidImportJob(String),
+
+    #[error("invalid envelope ciphertext: {0}")]
+    InvalidEnvelope(String),
+
+    #[error("envelope ciphertext was modified or truncated")]
+    EnvelopeDecryption,
+}
+
+#[derive(Clone)]
 pub struct KmsClient {
     token_manager: TokenManager,
     client: KeyManagementServiceClient<Channel>,
+    retry_policy: Option<RetryPolicy>,
+    /// Algorithms of key versions, which never change once created.
+    algorithms: Arc<Mutex<HashMap<String, CryptoKeyVersionAlgorithm>>>,
 }
 
-impl KmsClient {
-    pub async fn new() -> Result<Self, Error> {
-        Ok(Self {
-            token_manager: TokenManager::new(&SCOPES).await?,
-            client: Self::kms_client().await?,
-        })
+#[derive(Default)]
+pub struct KmsClientBuilder {
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    endpoint: Option<Endpoint>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl KmsClientBuilder {
+    pub fn with_token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.token_provider = Some(Arc::new(token_provider));
+        self
     }
 
-    async fn kms_client() -> Result<KeyManagementServiceClient<Channel>, Error> {
-        let tls_config = ClientTlsConfig::new()
-            .ca_certificate(Certificate::from_pem(TLS_CERT))
-            .domain_name(DOMAIN_NAME);
+    /// Defaults to [`ENDPOINT`].
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
 
-        let channel = Channel::from_static(ENDPOINT)
-            .tls_config(tls_config)?
-            .connect()
-            .await?;
+    /// Defaults to retrying `UNAVAILABLE` and `DEADLINE_EXCEEDED`, as Google's
+    /// client libraries do, except for creates which are only retried on
+    /// `UNAVAILABLE`. A policy set here applies to every method.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub async fn build(self) -> Result<KmsClient, Error> {
+        let token_provider = match self.token_provider {
+            Some(token_provider) => token_provider,
+            None => Arc::new(DefaultCredentials::new().await?),
+        };
+        let endpoint = match self.endpoint {
+            Some(endpoint) => endpoint,
+            None => Endpoint::new(ENDPOINT)?,
+        };
+        let channel = endpoint.connect().await?;
+
+        Ok(KmsClient {
+            token_manager: TokenManager::with_provider(token_provider, &SCOPES),
+            client: KeyManagementServiceClient::new(channel),
+            retry_policy: self.retry_policy,
+            algorithms: Default::default(),
+        })
+    }
+}
 
-        Ok(KeyManagementServiceClient::new(channel))
+impl KmsClient {
+    pub async fn new() -> Result<Self, Error> {
+        Self::builder().build().await
     }
+
+    pub fn builder() -> KmsClientBuilder {
+        KmsClientBuilder::default()
+    }
+
     pub(crate) async fn construct_request<T: IntoRequest<T>>(
-        &mut self,
+        &self,
         request: T,
         headers: Vec<(&str, &str)>,
+        options: &CallOptions,
     ) -> Result<Request<T>, Error> {
         construct_request(
             request,
-            self.token_manager.get_token().await?.as_str(),
+            self.token_manager
+                .get_token()
+                .await?
+                .as_ref()
+                .map(|token| token.as_str()),
             headers,
+            options,
         )
         .await
     }
 
+    /// Sends `request` through `method`, retrying according to `options` or the
+    /// client's retry policy.
+    async fn call<T, U, F, Fut>(
+        &self,
+        request: T,
+        headers: Vec<(&str, &str)>,
+        options: &CallOptions,
+        method: F,
+    ) -> Result<U, Error>
+    where
+        T: Clone,
+        F: Fn(KeyManagementServiceClient<Channel>, Request<T>) -> Fut,
+        Fut: Future<Output = Result<Response<U>, Status>>,
+    {
+        self.call_retrying(request, headers, options, RETRY_CODES, method)
+            .await
+    }
+
+    /// Like [`KmsClient::call`] for methods that create resources, which by default
+    /// are only retried when they cannot have been applied.
+    async fn call_create<T, U, F, Fut>(
+        &self,
+        request: T,
+        headers: Vec<(&str, &str)>,
+        options: &CallOptions,
+        method: F,
+    ) -> Result<U, Error>
+    where
+        T: Clone,
+        F: Fn(KeyManagementServiceClient<Channel>, Request<T>) -> Fut,
+        Fut: Future<Output = Result<Response<U>, Status>>,
+    {
+        self.call_retrying(request, headers, options, CREATE_RETRY_CODES, method)
+            .await
+    }
+
+    /// Retries on `retry_codes` unless `options` or the client has a retry policy.
+    async fn call_retrying<T, U, F, Fut>(
+        &self,
+        request: T,
+        headers: Vec<(&str, &str)>,
+        options: &CallOptions,
+        retry_codes: &'static [Code],
+        method: F,
+    ) -> Result<U, Error>
+    where
+        T: Clone,
+        F: Fn(KeyManagementServiceClient<Channel>, Request<T>) -> Fut,
+        Fut: Future<Output = Result<Response<U>, Status>>,
+    {
+        let request = &request;
+        let headers = &headers;
+        let method = &method;
+        options
+            .retry_policy()
+            .or(self.retry_policy.as_ref())
+            .cloned()
+            .unwrap_or_else(|| RetryPolicy::default().with_retryable_codes(retry_codes))
+            .run(|| {
+                options.attempt(async move {
+                    let request = self
+                        .construct_request(request.clone(), headers.clone(), options)
+                        .await?;
+                    Ok(method(self.client.clone(), request).await?.into_inner())
+                })
+            })
+            .await
+    }
+
+    /// Returns the first 100 key rings only; see [`KmsClient::stream_key_rings`].
+    ///
     /// # Arguments
     /// * `parent` - in the format `projects/*/locations/*`
-    pub async fn list_key_rings(&mut self, parent: &str) -> Result<ListKeyRingsResponse, Error> {
-        let request = self
-            .construct_request(
-                ListKeyRingsRequest {
-                    parent: parent.to_owned(),
-                    page_size: 100,
-                    page_token: Default::default(),
-                    filter: Default::default(),
-                    order_by: Default::default(),
-                },
-                vec![("parent", parent)],
-            )
-            .await?;
+    pub async fn list_key_rings(&self, parent: &str) -> Result<ListKeyRingsResponse, Error> {
+        self.list_key_rings_with_options(parent, &CallOptions::default())
+            .await
+    }
 
-        let response = self.client.list_key_rings(request).await?;
-        Ok(response.into_inner())
+    pub async fn list_key_rings_with_options(
+        &self,
+        parent: &str,
+        options: &CallOptions,
+    ) -> Result<ListKeyRingsResponse, Error> {
+        self.call(
+            ListKeyRingsRequest {
+                parent: parent.to_owned(),
+                page_size: 100,
+                page_token: Default::default(),
+                filter: Default::default(),
+                order_by: Default::default(),
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.list_key_rings(request).await },
+        )
+        .await
     }
 
+    /// Returns the first 100 crypto keys only; see [`KmsClient::stream_crypto_keys`].
+    ///
     /// # Arguments
     /// * `parent` - in the format `projects/*/locations/*/keyRings/*`
-    pub async fn list_crypto_keys(
-        &mut self,
+    pub async fn list_crypto_keys(&self, parent: &str) -> Result<ListCryptoKeysResponse, Error> {
+        self.list_crypto_keys_with_options(parent, &CallOptions::default())
+            .await
+    }
+
+    pub async fn list_crypto_keys_with_options(
+        &self,
         parent: &str,
+        options: &CallOptions,
     ) -> Result<ListCryptoKeysResponse, Error> {
-        let request = self
-            .construct_request(
-                ListCryptoKeysRequest {
-                    parent: parent.to_owned(),
-                    page_size: 100,
-                    page_token: Default::default(),
-                    filter: Default::default(),
-                    order_by: Default::default(),
-                    version_view: 0,
-                },
-                vec![("parent", parent)],
-            )
-            .await?;
+        self.call(
+            ListCryptoKeysRequest {
+                parent: parent.to_owned(),
+                page_size: 100,
+                page_token: Default::default(),
+                filter: Default::default(),
+                order_by: Default::default(),
+                version_view: 0,
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.list_crypto_keys(request).await },
+        )
+        .await
+    }
+
+    /// Streams all key rings, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `parent` - in the format `projects/*/locations/*`
+    pub fn stream_key_rings(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, KeyRing> {
+        self.stream_key_rings_with_options(parent, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_key_rings_with_options(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, KeyRing> {
+        let parent = parent.to_owned();
+        let list_options = list_options.clone();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListKeyRingsRequest {
+                parent: parent.clone(),
+                page_size: list_options.grpc_page_size(),
+                page_token: page_token.unwrap_or_default(),
+                filter: list_options.filter().unwrap_or_default().to_owned(),
+                order_by: list_options.order_by().unwrap_or_default().to_owned(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        request.clone(),
+                        vec![("parent", &request.parent)],
+                        &options,
+                        |mut client, request| async move { client.list_key_rings(request).await },
+                    )
+                    .await?;
+                Ok(Page::new(response.key_rings, response.next_page_token))
+            }
+        })
+    }
+
+    /// Streams all crypto keys, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `parent` - in the format `projects/*/locations/*/keyRings/*`
+    pub fn stream_crypto_keys(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, CryptoKey> {
+        self.stream_crypto_keys_with_options(parent, list_options, &CallOptions::default())
+    }
 
-        let response = self.client.list_crypto_keys(request).await?;
-        Ok(response.into_inner())
+    pub fn stream_crypto_keys_with_options(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, CryptoKey> {
+        let parent = parent.to_owned();
+        let list_options = list_options.clone();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListCryptoKeysRequest {
+                parent: parent.clone(),
+                page_size: list_options.grpc_page_size(),
+                page_token: page_token.unwrap_or_default(),
+                filter: list_options.filter().unwrap_or_default().to_owned(),
+                order_by: list_options.order_by().unwrap_or_default().to_owned(),
+                version_view: 0,
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        request.clone(),
+                        vec![("parent", &request.parent)],
+                        &options,
+                        |mut client, request| async move { client.list_crypto_keys(request).await },
+                    )
+                    .await?;
+                Ok(Page::new(response.crypto_keys, response.next_page_token))
+            }
+        })
     }
 
+    /// Checksums of the plaintext and ciphertext are verified on both ends, failing
+    /// with [`Error::Integrity`] if either was corrupted in transit.
+    ///
     /// # Arguments
     /// * `key_name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
-    /// * `data`     - to be encrypted.
+    /// * `data`     - to be encrypted, at most 64 KiB.
     pub async fn encrypt(
-        &mut self,
+        &self,
+        key_name: &str,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<EncryptResponse, Error> {
+        self.encrypt_with_options(key_name, data, &CallOptions::default())
+            .await
+    }
+
+    pub async fn encrypt_with_options(
+        &self,
+        key_name: &str,
+        data: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<EncryptResponse, Error> {
+        self.encrypt_with_aad_with_options(key_name, data, vec![], options)
+            .await
+    }
+
+    /// Like [`KmsClient::encrypt`], binding the ciphertext to `aad`: decrypting it
+    /// requires the same AAD.
+    ///
+    /// # Arguments
+    /// * `key_name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    /// * `data`     - to be encrypted, at most 64 KiB.
+    /// * `aad`      - additional authenticated data, e.g. a record id, at most 64 KiB.
+    pub async fn encrypt_with_aad(
+        &self,
         key_name: &str,
         data: impl Into<Vec<u8>>,
+        aad: impl Into<Vec<u8>>,
     ) -> Result<EncryptResponse, Error> {
-        let request = self
-            .construct_request(
+        self.encrypt_with_aad_with_options(key_name, data, aad, &CallOptions::default())
+            .await
+    }
+
+    pub async fn encrypt_with_aad_with_options(
+        &self,
+        key_name: &str,
+        data: impl Into<Vec<u8>>,
+        aad: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<EncryptResponse, Error> {
+        let plaintext = data.into();
+        let aad = aad.into();
+        ensure_size("plaintext", &plaintext, MAX_PLAINTEXT_SIZE)?;
+        ensure_size("additional authenticated data", &aad, MAX_AAD_SIZE)?;
+
+        let has_aad = !aad.is_empty();
+        let response = self
+            .call(
                 EncryptRequest {
                     name: key_name.to_owned(),
-                    plaintext: data.into(),
-                    additional_authenticated_data: vec![],
-                    plaintext_crc32c: None,
-                    additional_authenticated_data_crc32c: None,
+                    plaintext_crc32c: crc32c(&plaintext),
+                    plaintext,
+                    additional_authenticated_data_crc32c: crc32c(&aad).filter(|_| has_aad),
+                    additional_authenticated_data: aad,
                 },
                 vec![("name", key_name)],
+                options,
+                |mut client, request| async move { client.encrypt(request).await },
             )
             .await?;
-
-        let response = self.client.encrypt(request).await?;
-        Ok(response.into_inner())
+        ensure_version_of(key_name, &response.name)?;
+        ensure_verified("plaintext", response.verified_plaintext_crc32c)?;
+        if has_aad {
+            ensure_verified(
+                "additional authenticated data",
+                response.verified_additional_authenticated_data_crc32c,
+            )?;
+        }
+        verify_crc32c(
+            "ciphertext",
+            &response.ciphertext,
+            response.ciphertext_crc32c,
+        )?;
+        Ok(response)
     }
 
+    /// Checksums of the ciphertext and plaintext are verified on both ends, failing
+    /// with [`Error::Integrity`] if either was corrupted in transit.
+    ///
     /// # Arguments
     /// * `key_name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
     /// * `data`     - to be decrypted.
     pub async fn decrypt(
-        &mut self,
+        &self,
+        key_name: &str,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<DecryptResponse, Error> {
+        self.decrypt_with_options(key_name, data, &CallOptions::default())
+            .await
+    }
+
+    pub async fn decrypt_with_options(
+        &self,
         key_name: &str,
         data: impl Into<Vec<u8>>,
+        options: &CallOptions,
     ) -> Result<DecryptResponse, Error> {
-        let request = self
-            .construct_request(
+        self.decrypt_with_aad_with_options(key_name, data, vec![], options)
+            .await
+    }
+
+    /// Decrypts a ciphertext returned by [`KmsClient::encrypt_with_aad`]. Fails with
+    /// `INVALID_ARGUMENT` when `aad` differs from the one used to encrypt.
+    ///
+    /// # Arguments
+    /// * `key_name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    /// * `data`     - to be decrypted.
+    /// * `aad`      - additional authenticated data given to encrypt, at most 64 KiB.
+    pub async fn decrypt_with_aad(
+        &self,
+        key_name: &str,
+        data: impl Into<Vec<u8>>,
+        aad: impl Into<Vec<u8>>,
+    ) -> Result<DecryptResponse, Error> {
+        self.decrypt_with_aad_with_options(key_name, data, aad, &CallOptions::default())
+            .await
+    }
+
+    pub async fn decrypt_with_aad_with_options(
+        &self,
+        key_name: &str,
+        data: impl Into<Vec<u8>>,
+        aad: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<DecryptResponse, Error> {
+        let ciphertext = data.into();
+        let aad = aad.into();
+        ensure_size("additional authenticated data", &aad, MAX_AAD_SIZE)?;
+
+        let has_aad = !aad.is_empty();
+        let response = self
+            .call(
                 DecryptRequest {
                     name: key_name.to_owned(),
-                    ciphertext: data.into(),
-                    additional_authenticated_data: vec![],
-                    ciphertext_crc32c: None,
-                    additional_authenticated_data_crc32c: None,
+                    ciphertext_crc32c: crc32c(&ciphertext),
+                    ciphertext,
+                    additional_authenticated_data_crc32c: crc32c(&aad).filter(|_| has_aad),
+                    additional_authenticated_data: aad,
                 },
                 vec![("name", key_name)],
+                options,
+                |mut client, request| async move { client.decrypt(request).await },
             )
             .await?;
+        verify_crc32c("plaintext", &response.plaintext, response.plaintext_crc32c)?;
+        Ok(response)
+    }
+}
+
+fn ensure_size(field: &'static str, data: &[u8], limit: usize) -> Result<(), KmsError> {
+    if data.len() > limit {
+        return Err(KmsError::TooLarge {
+            field,
+            size: data.len(),
+            limit,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::kms::{crypto_key::CryptoKeyPurpose, fake, CryptoKeyBuilder};
+
+    #[tokio::test]
+    async fn test_stream_key_rings() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let location = "projects/test/locations/global";
+        for id in ["a", "b", "c"] {
+            client.create_key_ring(location, id).await?;
+        }
+
+        let pages: Vec<_> = client
+            .stream_key_rings(location, &ListOptions::new().with_page_size(2))
+            .map_ok(|page| page.items.len())
+            .try_collect()
+            .await?;
+        assert_eq!(pages, vec![2, 1]);
+
+        let names: Vec<_> = client
+            .stream_key_rings(location, &ListOptions::new())
+            .items()
+            .map_ok(|key_ring| key_ring.name)
+            .try_collect()
+            .await?;
+        assert_eq!(
+            names,
+            ["a", "b", "c"]
+                .iter()
+                .map(|id| format!("{}/keyRings/{}", location, id))
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_integrity() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        let key_ring = client
+            .create_key_ring("projects/test/locations/global", "ring")
+            .await?;
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "key",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt).build(),
+            )
+            .await?;
+
+        let response = client.encrypt(&crypto_key.name, "secret").await?;
+        assert!(response.verified_plaintext_crc32c);
+        let ciphertext = response.ciphertext;
+        assert_eq!(
+            client
+                .decrypt(&crypto_key.name, ciphertext.clone())
+                .await?
+                .plaintext,
+            b"secret"
+        );
+
+        fake.set_corrupt(true);
+        let options = CallOptions::new().with_retry_policy(RetryPolicy::none());
+        assert!(matches!(
+            client
+                .encrypt_with_options(&crypto_key.name, "secret", &options)
+                .await,
+            Err(Error::Integrity(_))
+        ));
+        assert!(matches!(
+            client
+                .decrypt_with_options(&crypto_key.name, ciphertext, &options)
+                .await,
+            Err(Error::Integrity(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_with_aad() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let key_ring = client
+            .create_key_ring("projects/test/locations/global", "ring")
+            .await?;
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "key",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt).build(),
+            )
+            .await?;
+
+        let response = client
+            .encrypt_with_aad(&crypto_key.name, "secret", "record-1")
+            .await?;
+        assert!(response.verified_additional_authenticated_data_crc32c);
+        let ciphertext = response.ciphertext;
+        assert_eq!(
+            client
+                .decrypt_with_aad(&crypto_key.name, ciphertext.clone(), "record-1")
+                .await?
+                .plaintext,
+            b"secret"
+        );
+
+        let options = CallOptions::new().with_retry_policy(RetryPolicy::none());
+        for aad in ["record-2", ""] {
+            let error = client
+                .decrypt_with_aad_with_options(&crypto_key.name, ciphertext.clone(), aad, &options)
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(error, Error::Status(status) if status.code() == Code::InvalidArgument)
+            );
+        }
 
-        let response = self.client.decrypt(request).await?;
-        Ok(response.into_inner())
+        assert!(matches!(
+            client
+                .encrypt_with_aad(&crypto_key.name, "secret", vec![0; MAX_AAD_SIZE + 1])
+                .await,
+            Err(Error::Kms(KmsError::TooLarge {
+                limit: MAX_AAD_SIZE,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            client
+                .encrypt(&crypto_key.name, vec![0; MAX_PLAINTEXT_SIZE + 1])
+                .await,
+            Err(Error::Kms(KmsError::TooLarge {
+                field: "plaintext",
+                ..
+            }))
+        ));
+        Ok(())
     }
 }
@@ -0,0 +1,282 @@
+use super::KmsClient;
+use crate::{
+    error::Error,
+    kms::{
+        crypto_key::CryptoKeyVersionAlgorithm,
+        integrity::{crc32c, ensure_name, ensure_verified, verify_crc32c},
+        public_key::{PublicKey, Scheme},
+    },
+    options::CallOptions,
+    proto::google::cloud::kms::v1::{
+        AsymmetricDecryptRequest, AsymmetricDecryptResponse, AsymmetricSignRequest,
+        AsymmetricSignResponse, GetPublicKeyRequest,
+    },
+};
+
+impl KmsClient {
+    /// Fetches and parses the public key of an asymmetric key version.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    pub async fn get_public_key(&self, name: &str) -> Result<PublicKey, Error> {
+        self.get_public_key_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_public_key_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<PublicKey, Error> {
+        let public_key = self
+            .call(
+                GetPublicKeyRequest {
+                    name: name.to_owned(),
+                },
+                vec![("name", name)],
+                options,
+                |mut client, request| async move { client.get_public_key(request).await },
+            )
+            .await?;
+        ensure_name(name, &public_key.name)?;
+
+        let public_key = PublicKey::try_from(public_key)?;
+        self.algorithms
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), public_key.algorithm());
+        Ok(public_key)
+    }
+
+    /// Signs `data` with an asymmetric key version.
+    ///
+    /// `data` is hashed locally with the hash function of the version's algorithm, so
+    /// only the digest is sent. The algorithm is looked up once per version.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    /// * `data` - to be signed. For raw PKCS#1 keys, sent and signed as is.
+    pub async fn asymmetric_sign(
+        &self,
+        name: &str,
+        data: impl AsRef<[u8]>,
+    ) -> Result<AsymmetricSignResponse, Error> {
+        self.asymmetric_sign_with_options(name, data, &CallOptions::default())
+            .await
+    }
+
+    pub async fn asymmetric_sign_with_options(
+        &self,
+        name: &str,
+        data: impl AsRef<[u8]>,
+        options: &CallOptions,
+    ) -> Result<AsymmetricSignResponse, Error> {
+        let data = data.as_ref();
+        let algorithm = self.algorithm(name, options).await?;
+        let mut request = AsymmetricSignRequest {
+            name: name.to_owned(),
+            ..Default::default()
+        };
+        match Scheme::of(algorithm)?.hash() {
+            Some(hash) => {
+                let digest = hash.digest(data);
+                request.digest_crc32c = crc32c(&digest);
+                request.digest = hash.to_proto(digest);
+            }
+            None => {
+                request.data_crc32c = crc32c(data);
+                request.data = data.to_vec();
+            }
+        }
+        let sent_digest = request.digest.is_some();
+
+        let response = self
+            .call(
+                request,
+                vec![("name", name)],
+                options,
+                |mut client, request| async move { client.asymmetric_sign(request).await },
+            )
+            .await?;
+        ensure_name(name, &response.name)?;
+        if sent_digest {
+            ensure_verified("digest", response.verified_digest_crc32c)?;
+        } else {
+            ensure_verified("data", response.verified_data_crc32c)?;
+        }
+        verify_crc32c("signature", &response.signature, response.signature_crc32c)?;
+        Ok(response)
+    }
+
+    /// Decrypts data encrypted with the public key of an asymmetric key version,
+    /// e.g. by [`PublicKey::encrypt`].
+    ///
+    /// # Arguments
+    /// * `name`       - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    /// * `ciphertext` - to be decrypted.
+    pub async fn asymmetric_decrypt(
+        &self,
+        name: &str,
+        ciphertext: impl Into<Vec<u8>>,
+    ) -> Result<AsymmetricDecryptResponse, Error> {
+        self.asymmetric_decrypt_with_options(name, ciphertext, &CallOptions::default())
+            .await
+    }
+
+    pub async fn asymmetric_decrypt_with_options(
+        &self,
+        name: &str,
+        ciphertext: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<AsymmetricDecryptResponse, Error> {
+        let ciphertext = ciphertext.into();
+        let response = self
+            .call(
+                AsymmetricDecryptRequest {
+                    name: name.to_owned(),
+                    ciphertext_crc32c: crc32c(&ciphertext),
+                    ciphertext,
+                },
+                vec![("name", name)],
+                options,
+                |mut client, request| async move { client.asymmetric_decrypt(request).await },
+            )
+            .await?;
+        ensure_verified("ciphertext", response.verified_ciphertext_crc32c)?;
+        verify_crc32c("plaintext", &response.plaintext, response.plaintext_crc32c)?;
+        Ok(response)
+    }
+
+    /// The algorithm of a key version, fetched once and cached.
+    async fn algorithm(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersionAlgorithm, Error> {
+        if let Some(algorithm) = self.algorithms.lock().unwrap().get(name) {
+            return Ok(*algorithm);
+        }
+        let algorithm = self
+            .get_crypto_key_version_with_options(name, options)
+            .await?
+            .algorithm();
+        self.algorithms
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), algorithm);
+        Ok(algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        kms::{client::KmsError, crypto_key::CryptoKeyPurpose, fake, CryptoKeyBuilder},
+        retry::RetryPolicy,
+    };
+
+    const KEY_RING: &str = "projects/test/locations/global/keyRings/ring";
+
+    /// Creates a key with a single version and returns the version's name.
+    async fn create_key(
+        client: &KmsClient,
+        purpose: CryptoKeyPurpose,
+        algorithm: CryptoKeyVersionAlgorithm,
+    ) -> anyhow::Result<String> {
+        client
+            .create_key_ring("projects/test/locations/global", "ring")
+            .await?;
+        let crypto_key = client
+            .create_crypto_key(
+                KEY_RING,
+                "key",
+                CryptoKeyBuilder::new(purpose)
+                    .with_algorithm(algorithm)
+                    .build(),
+            )
+            .await?;
+        Ok(format!("{}/cryptoKeyVersions/1", crypto_key.name))
+    }
+
+    #[rstest]
+    #[case(CryptoKeyVersionAlgorithm::EcSignP256Sha256)]
+    #[case(CryptoKeyVersionAlgorithm::EcSignP384Sha384)]
+    #[case(CryptoKeyVersionAlgorithm::RsaSignPss2048Sha256)]
+    #[case(CryptoKeyVersionAlgorithm::RsaSignPkcs12048Sha256)]
+    #[case(CryptoKeyVersionAlgorithm::RsaSignRawPkcs12048)]
+    #[tokio::test]
+    async fn test_sign_and_verify(
+        #[case] algorithm: CryptoKeyVersionAlgorithm,
+    ) -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let name = create_key(&client, CryptoKeyPurpose::AsymmetricSign, algorithm).await?;
+
+        let public_key = client.get_public_key(&name).await?;
+        assert_eq!(public_key.name(), name);
+        assert_eq!(public_key.algorithm(), algorithm);
+
+        let signature = client
+            .asymmetric_sign(&name, "release.tar.gz")
+            .await?
+            .signature;
+        public_key.verify(b"release.tar.gz", &signature)?;
+        assert!(matches!(
+            public_key.verify(b"release.tar.bz2", &signature),
+            Err(Error::Kms(KmsError::InvalidSignature))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_asymmetric_decrypt() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let name = create_key(
+            &client,
+            CryptoKeyPurpose::AsymmetricDecrypt,
+            CryptoKeyVersionAlgorithm::RsaDecryptOaep2048Sha256,
+        )
+        .await?;
+
+        let ciphertext = client.get_public_key(&name).await?.encrypt(b"secret")?;
+        let response = client.asymmetric_decrypt(&name, ciphertext).await?;
+        assert_eq!(response.plaintext, b"secret");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_responses() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        let name = create_key(
+            &client,
+            CryptoKeyPurpose::AsymmetricSign,
+            CryptoKeyVersionAlgorithm::EcSignP256Sha256,
+        )
+        .await?;
+        fake.set_corrupt(true);
+
+        let options = CallOptions::new().with_retry_policy(RetryPolicy::none());
+        assert!(matches!(
+            client.get_public_key_with_options(&name, &options).await,
+            Err(Error::Integrity(_))
+        ));
+        assert!(matches!(
+            client
+                .asymmetric_sign_with_options(&name, "data", &options)
+                .await,
+            Err(Error::Integrity(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_algorithm() {
+        assert!(matches!(
+            PublicKey::from_pem("", CryptoKeyVersionAlgorithm::GoogleSymmetricEncryption),
+            Err(Error::Kms(KmsError::UnsupportedAlgorithm(_)))
+        ));
+    }
+}
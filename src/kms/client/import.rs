@@ -0,0 +1,332 @@
+use std::time::{Duration, Instant};
+
+use tonic::Code;
+
+use super::{KmsClient, KmsError};
+use crate::{
+    error::Error,
+    kms::{
+        crypto_key::{CryptoKeyVersionAlgorithm, ProtectionLevel},
+        import_job::{wrap_key_material, ImportJobState, ImportMethod},
+    },
+    options::CallOptions,
+    proto::google::cloud::kms::v1::{
+        import_crypto_key_version_request::WrappedKeyMaterial, CreateImportJobRequest,
+        CryptoKeyVersion, GetImportJobRequest, ImportCryptoKeyVersionRequest, ImportJob,
+    },
+    retry::RetryPolicy,
+};
+
+/// How long [`KmsClient::import_key`] waits for a new job's wrapping key.
+const IMPORT_JOB_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+impl KmsClient {
+    /// Creates an import job. Its wrapping key is generated asynchronously, see
+    /// [`KmsClient::wait_for_import_job`].
+    ///
+    /// # Arguments
+    /// * `parent`           - in the format `projects/*/locations/*/keyRings/*`
+    /// * `import_job_id`    - unique within `parent`, matching `[a-zA-Z0-9_-]{1,63}`
+    /// * `import_method`    - how key material is wrapped.
+    /// * `protection_level` - must match that of the crypto keys imported into.
+    pub async fn create_import_job(
+        &self,
+        parent: &str,
+        import_job_id: &str,
+        import_method: ImportMethod,
+        protection_level: ProtectionLevel,
+    ) -> Result<ImportJob, Error> {
+        self.create_import_job_with_options(
+            parent,
+            import_job_id,
+            import_method,
+            protection_level,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn create_import_job_with_options(
+        &self,
+        parent: &str,
+        import_job_id: &str,
+        import_method: ImportMethod,
+        protection_level: ProtectionLevel,
+        options: &CallOptions,
+    ) -> Result<ImportJob, Error> {
+        self.call_create(
+            CreateImportJobRequest {
+                parent: parent.to_owned(),
+                import_job_id: import_job_id.to_owned(),
+                import_job: Some(ImportJob {
+                    import_method: import_method as i32,
+                    protection_level: protection_level as i32,
+                    ..Default::default()
+                }),
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.create_import_job(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/importJobs/*`
+    pub async fn get_import_job(&self, name: &str) -> Result<ImportJob, Error> {
+        self.get_import_job_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_import_job_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<ImportJob, Error> {
+        self.call(
+            GetImportJobRequest {
+                name: name.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move { client.get_import_job(request).await },
+        )
+        .await
+    }
+
+    /// Polls an import job until it is `Active`, failing with
+    /// [`KmsError::InvalidImportJob`] if it expires and [`Error::Timeout`] if it is
+    /// still pending after `timeout`.
+    ///
+    /// # Arguments
+    /// * `name`    - in the format `projects/*/locations/*/keyRings/*/importJobs/*`
+    /// * `timeout` - how long to wait in total.
+    pub async fn wait_for_import_job(
+        &self,
+        name: &str,
+        timeout: Duration,
+    ) -> Result<ImportJob, Error> {
+        self.wait_for_import_job_with_options(name, timeout, &CallOptions::default())
+            .await
+    }
+
+    pub async fn wait_for_import_job_with_options(
+        &self,
+        name: &str,
+        timeout: Duration,
+        options: &CallOptions,
+    ) -> Result<ImportJob, Error> {
+        let polling = RetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(500))
+            .with_max_backoff(Duration::from_secs(10))
+            .with_multiplier(1.5);
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let import_job = self.get_import_job_with_options(name, options).await?;
+            match import_job.state() {
+                ImportJobState::Active => return Ok(import_job),
+                ImportJobState::Expired => {
+                    return Err(KmsError::InvalidImportJob(format!("{} has expired", name)).into())
+                }
+                _ => {}
+            }
+
+            let delay = polling.backoff(attempt);
+            if started.elapsed() + delay > timeout {
+                return Err(Error::Timeout(timeout));
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Imports key material already wrapped by [`wrap_key_material`] as a new version.
+    ///
+    /// # Arguments
+    /// * `parent`      - the crypto key, in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    /// * `algorithm`   - of the key material, compatible with the crypto key's purpose.
+    /// * `import_job`  - in the format `projects/*/locations/*/keyRings/*/importJobs/*`
+    /// * `wrapped_key` - see [`wrap_key_material`].
+    pub async fn import_crypto_key_version(
+        &self,
+        parent: &str,
+        algorithm: CryptoKeyVersionAlgorithm,
+        import_job: &str,
+        wrapped_key: Vec<u8>,
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.import_crypto_key_version_with_options(
+            parent,
+            algorithm,
+            import_job,
+            wrapped_key,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn import_crypto_key_version_with_options(
+        &self,
+        parent: &str,
+        algorithm: CryptoKeyVersionAlgorithm,
+        import_job: &str,
+        wrapped_key: Vec<u8>,
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.call_create(
+            ImportCryptoKeyVersionRequest {
+                parent: parent.to_owned(),
+                crypto_key_version: String::new(),
+                algorithm: algorithm as i32,
+                import_job: import_job.to_owned(),
+                wrapped_key_material: Some(WrappedKeyMaterial::RsaAesWrappedKey(wrapped_key)),
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.import_crypto_key_version(request).await },
+        )
+        .await
+    }
+
+    /// Imports local key material as a new version of a crypto key, typically one
+    /// created with [`CryptoKeyBuilder::with_import_only`](crate::kms::CryptoKeyBuilder::with_import_only).
+    ///
+    /// The import job `import_job_id` in the key's key ring is reused if it exists,
+    /// otherwise created with `RSA_OAEP_3072_SHA1_AES_256` and the key's protection
+    /// level. The version gets the algorithm of the key's version template.
+    ///
+    /// # Arguments
+    /// * `crypto_key`    - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    /// * `import_job_id` - unique within the key ring, matching `[a-zA-Z0-9_-]{1,63}`
+    /// * `key_material`  - raw bytes for symmetric keys, PKCS#8 DER for asymmetric ones.
+    pub async fn import_key(
+        &self,
+        crypto_key: &str,
+        import_job_id: &str,
+        key_material: &[u8],
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.import_key_with_options(
+            crypto_key,
+            import_job_id,
+            key_material,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn import_key_with_options(
+        &self,
+        crypto_key: &str,
+        import_job_id: &str,
+        key_material: &[u8],
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        let key_ring = crypto_key
+            .split("/cryptoKeys/")
+            .next()
+            .filter(|key_ring| *key_ring != crypto_key)
+            .ok_or_else(|| {
+                KmsError::InvalidImportJob(format!("{} is not a crypto key", crypto_key))
+            })?;
+        let template = self
+            .get_crypto_key_with_options(crypto_key, options)
+            .await?
+            .version_template
+            .unwrap_or_default();
+
+        let name = format!("{}/importJobs/{}", key_ring, import_job_id);
+        let import_job = match self
+            .create_import_job_with_options(
+                key_ring,
+                import_job_id,
+                ImportMethod::RsaOaep3072Sha1Aes256,
+                template.protection_level(),
+                options,
+            )
+            .await
+        {
+            Err(Error::Status(status)) if status.code() == Code::AlreadyExists => {
+                self.get_import_job_with_options(&name, options).await?
+            }
+            result => result?,
+        };
+        if import_job.protection_level != template.protection_level {
+            return Err(KmsError::InvalidImportJob(format!(
+                "{} has a different protection level than {}",
+                name, crypto_key
+            ))
+            .into());
+        }
+
+        let import_job = match import_job.state() {
+            ImportJobState::Active => import_job,
+            _ => {
+                self.wait_for_import_job_with_options(&name, IMPORT_JOB_TIMEOUT, options)
+                    .await?
+            }
+        };
+        let public_key = import_job.public_key.as_ref().ok_or_else(|| {
+            KmsError::InvalidImportJob(format!("{} has no public key", import_job.name))
+        })?;
+        let wrapped_key =
+            wrap_key_material(&public_key.pem, import_job.import_method(), key_material)?;
+
+        self.import_crypto_key_version_with_options(
+            crypto_key,
+            template.algorithm(),
+            &import_job.name,
+            wrapped_key,
+            options,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hmac::{Hmac, Mac};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::kms::{crypto_key::CryptoKeyPurpose, fake, CryptoKeyBuilder};
+
+    #[tokio::test]
+    async fn test_import_key() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let key_ring = client
+            .create_key_ring("projects/test/locations/global", "ring")
+            .await?;
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "imported",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::Mac)
+                    .with_algorithm(CryptoKeyVersionAlgorithm::HmacSha256)
+                    .with_import_only(true)
+                    .build(),
+            )
+            .await?;
+
+        let secret = [42; 32];
+        let version = client.import_key(&crypto_key.name, "job", &secret).await?;
+        assert_eq!(version.algorithm(), CryptoKeyVersionAlgorithm::HmacSha256);
+        assert_eq!(
+            version.import_job,
+            format!("{}/importJobs/job", key_ring.name)
+        );
+
+        // The imported secret is the one used by Cloud KMS.
+        let mut expected = Hmac::<sha2::Sha256>::new_from_slice(&secret)?;
+        expected.update(b"payload");
+        assert_eq!(
+            client.mac_sign(&version.name, "payload").await?.mac,
+            expected.finalize().into_bytes().to_vec()
+        );
+
+        // The job is reused for the next version.
+        let second = client.import_key(&crypto_key.name, "job", &[7; 32]).await?;
+        assert_eq!(second.import_job, version.import_job);
+        assert_ne!(second.name, version.name);
+        Ok(())
+    }
+}
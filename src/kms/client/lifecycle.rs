@@ -0,0 +1,583 @@
+use super::KmsClient;
+use crate::{
+    error::Error,
+    options::CallOptions,
+    pagination::{ListOptions, Page, PageStream},
+    proto::google::cloud::kms::v1::{
+        CreateCryptoKeyRequest, CreateCryptoKeyVersionRequest, CreateKeyRingRequest, CryptoKey,
+        CryptoKeyVersion, DestroyCryptoKeyVersionRequest, GetCryptoKeyRequest,
+        GetCryptoKeyVersionRequest, GetKeyRingRequest, KeyRing, ListCryptoKeyVersionsRequest,
+        RestoreCryptoKeyVersionRequest, UpdateCryptoKeyPrimaryVersionRequest,
+        UpdateCryptoKeyRequest, UpdateCryptoKeyVersionRequest,
+    },
+    util::field_mask,
+};
+
+impl KmsClient {
+    /// # Arguments
+    /// * `parent`      - in the format `projects/*/locations/*`
+    /// * `key_ring_id` - unique within `parent`, matching `[a-zA-Z0-9_-]{1,63}`
+    pub async fn create_key_ring(&self, parent: &str, key_ring_id: &str) -> Result<KeyRing, Error> {
+        self.create_key_ring_with_options(parent, key_ring_id, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_key_ring_with_options(
+        &self,
+        parent: &str,
+        key_ring_id: &str,
+        options: &CallOptions,
+    ) -> Result<KeyRing, Error> {
+        self.call_create(
+            CreateKeyRingRequest {
+                parent: parent.to_owned(),
+                key_ring_id: key_ring_id.to_owned(),
+                key_ring: Some(KeyRing::default()),
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.create_key_ring(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*`
+    pub async fn get_key_ring(&self, name: &str) -> Result<KeyRing, Error> {
+        self.get_key_ring_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_key_ring_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<KeyRing, Error> {
+        self.call(
+            GetKeyRingRequest {
+                name: name.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move { client.get_key_ring(request).await },
+        )
+        .await
+    }
+
+    /// Creates a crypto key and, unless it is import only, its first version.
+    ///
+    /// # Arguments
+    /// * `parent`        - in the format `projects/*/locations/*/keyRings/*`
+    /// * `crypto_key_id` - unique within `parent`, matching `[a-zA-Z0-9_-]{1,63}`
+    /// * `crypto_key`    - see [`CryptoKeyBuilder`](crate::kms::CryptoKeyBuilder)
+    pub async fn create_crypto_key(
+        &self,
+        parent: &str,
+        crypto_key_id: &str,
+        crypto_key: CryptoKey,
+    ) -> Result<CryptoKey, Error> {
+        self.create_crypto_key_with_options(
+            parent,
+            crypto_key_id,
+            crypto_key,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn create_crypto_key_with_options(
+        &self,
+        parent: &str,
+        crypto_key_id: &str,
+        crypto_key: CryptoKey,
+        options: &CallOptions,
+    ) -> Result<CryptoKey, Error> {
+        self.call_create(
+            CreateCryptoKeyRequest {
+                parent: parent.to_owned(),
+                crypto_key_id: crypto_key_id.to_owned(),
+                skip_initial_version_creation: crypto_key.import_only,
+                crypto_key: Some(crypto_key),
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.create_crypto_key(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    pub async fn get_crypto_key(&self, name: &str) -> Result<CryptoKey, Error> {
+        self.get_crypto_key_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_crypto_key_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKey, Error> {
+        self.call(
+            GetCryptoKeyRequest {
+                name: name.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move { client.get_crypto_key(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `crypto_key`  - the key to update, identified by its `name`.
+    /// * `update_mask` - the fields to update, e.g. `["labels", "rotation_period"]`.
+    pub async fn update_crypto_key(
+        &self,
+        crypto_key: CryptoKey,
+        update_mask: &[&str],
+    ) -> Result<CryptoKey, Error> {
+        self.update_crypto_key_with_options(crypto_key, update_mask, &CallOptions::default())
+            .await
+    }
+
+    pub async fn update_crypto_key_with_options(
+        &self,
+        crypto_key: CryptoKey,
+        update_mask: &[&str],
+        options: &CallOptions,
+    ) -> Result<CryptoKey, Error> {
+        let name = crypto_key.name.clone();
+        self.call(
+            UpdateCryptoKeyRequest {
+                crypto_key: Some(crypto_key),
+                update_mask: Some(field_mask(update_mask)),
+            },
+            vec![("crypto_key.name", &name)],
+            options,
+            |mut client, request| async move { client.update_crypto_key(request).await },
+        )
+        .await
+    }
+
+    /// Makes `crypto_key_version_id` the version used by `encrypt`.
+    ///
+    /// # Arguments
+    /// * `name`                  - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    /// * `crypto_key_version_id` - the last segment of the version's name, e.g. `2`
+    pub async fn update_crypto_key_primary_version(
+        &self,
+        name: &str,
+        crypto_key_version_id: &str,
+    ) -> Result<CryptoKey, Error> {
+        self.update_crypto_key_primary_version_with_options(
+            name,
+            crypto_key_version_id,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn update_crypto_key_primary_version_with_options(
+        &self,
+        name: &str,
+        crypto_key_version_id: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKey, Error> {
+        self.call(
+            UpdateCryptoKeyPrimaryVersionRequest {
+                name: name.to_owned(),
+                crypto_key_version_id: crypto_key_version_id.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move {
+                client.update_crypto_key_primary_version(request).await
+            },
+        )
+        .await
+    }
+
+    /// Creates a new version and makes it primary.
+    ///
+    /// The two steps are separate calls; if the second fails, the new version exists
+    /// but is not primary.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    pub async fn rotate_crypto_key(&self, name: &str) -> Result<CryptoKey, Error> {
+        self.rotate_crypto_key_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn rotate_crypto_key_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKey, Error> {
+        let version = self
+            .create_crypto_key_version_with_options(name, options)
+            .await?;
+        let version_id = version.name.rsplit('/').next().unwrap_or_default();
+        self.update_crypto_key_primary_version_with_options(name, version_id, options)
+            .await
+    }
+
+    /// # Arguments
+    /// * `parent` - the crypto key, in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    pub async fn create_crypto_key_version(&self, parent: &str) -> Result<CryptoKeyVersion, Error> {
+        self.create_crypto_key_version_with_options(parent, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_crypto_key_version_with_options(
+        &self,
+        parent: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.call_create(
+            CreateCryptoKeyVersionRequest {
+                parent: parent.to_owned(),
+                crypto_key_version: Some(CryptoKeyVersion::default()),
+            },
+            vec![("parent", parent)],
+            options,
+            |mut client, request| async move { client.create_crypto_key_version(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    pub async fn get_crypto_key_version(&self, name: &str) -> Result<CryptoKeyVersion, Error> {
+        self.get_crypto_key_version_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_crypto_key_version_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.call(
+            GetCryptoKeyVersionRequest {
+                name: name.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move { client.get_crypto_key_version(request).await },
+        )
+        .await
+    }
+
+    /// Updates the state of a version, to enable or disable it.
+    ///
+    /// # Arguments
+    /// * `crypto_key_version` - the version to update, identified by its `name`.
+    /// * `update_mask`        - the fields to update, e.g. `["state"]`.
+    pub async fn update_crypto_key_version(
+        &self,
+        crypto_key_version: CryptoKeyVersion,
+        update_mask: &[&str],
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.update_crypto_key_version_with_options(
+            crypto_key_version,
+            update_mask,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn update_crypto_key_version_with_options(
+        &self,
+        crypto_key_version: CryptoKeyVersion,
+        update_mask: &[&str],
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        let name = crypto_key_version.name.clone();
+        self.call(
+            UpdateCryptoKeyVersionRequest {
+                crypto_key_version: Some(crypto_key_version),
+                update_mask: Some(field_mask(update_mask)),
+            },
+            vec![("crypto_key_version.name", &name)],
+            options,
+            |mut client, request| async move { client.update_crypto_key_version(request).await },
+        )
+        .await
+    }
+
+    /// Schedules a version for destruction. It can be restored until its
+    /// `destroy_time` has passed.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    pub async fn destroy_crypto_key_version(&self, name: &str) -> Result<CryptoKeyVersion, Error> {
+        self.destroy_crypto_key_version_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn destroy_crypto_key_version_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.call(
+            DestroyCryptoKeyVersionRequest {
+                name: name.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move { client.destroy_crypto_key_version(request).await },
+        )
+        .await
+    }
+
+    /// Cancels a scheduled destruction. The version is restored in the `Disabled` state.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    pub async fn restore_crypto_key_version(&self, name: &str) -> Result<CryptoKeyVersion, Error> {
+        self.restore_crypto_key_version_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn restore_crypto_key_version_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<CryptoKeyVersion, Error> {
+        self.call(
+            RestoreCryptoKeyVersionRequest {
+                name: name.to_owned(),
+            },
+            vec![("name", name)],
+            options,
+            |mut client, request| async move { client.restore_crypto_key_version(request).await },
+        )
+        .await
+    }
+
+    /// Streams all versions of a crypto key, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `parent` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    pub fn stream_crypto_key_versions(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, CryptoKeyVersion> {
+        self.stream_crypto_key_versions_with_options(parent, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_crypto_key_versions_with_options(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, CryptoKeyVersion> {
+        let parent = parent.to_owned();
+        let list_options = list_options.clone();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListCryptoKeyVersionsRequest {
+                parent: parent.clone(),
+                page_size: list_options.grpc_page_size(),
+                page_token: page_token.unwrap_or_default(),
+                view: 0,
+                filter: list_options.filter().unwrap_or_default().to_owned(),
+                order_by: list_options.order_by().unwrap_or_default().to_owned(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        request.clone(),
+                        vec![("parent", &request.parent)],
+                        &options,
+                        |mut client, request| async move {
+                            client.list_crypto_key_versions(request).await
+                        },
+                    )
+                    .await?;
+                Ok(Page::new(
+                    response.crypto_key_versions,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use tonic::Code;
+
+    use super::*;
+    use crate::kms::{
+        crypto_key::{CryptoKeyPurpose, CryptoKeyVersionState, ProtectionLevel},
+        fake, CryptoKeyBuilder,
+    };
+
+    const LOCATION: &str = "projects/test/locations/global";
+
+    #[tokio::test]
+    async fn test_key_lifecycle() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+
+        let key_ring = client.create_key_ring(LOCATION, "ring").await?;
+        assert_eq!(client.get_key_ring(&key_ring.name).await?, key_ring);
+
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "key",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt)
+                    .with_protection_level(ProtectionLevel::Hsm)
+                    .with_label("team", "payments")
+                    .build(),
+            )
+            .await?;
+        let first = crypto_key.primary.clone().unwrap();
+        assert_eq!(
+            first.name,
+            format!("{}/cryptoKeyVersions/1", crypto_key.name)
+        );
+        assert_eq!(first.protection_level(), ProtectionLevel::Hsm);
+
+        let ciphertext = client.encrypt(&crypto_key.name, "secret").await?.ciphertext;
+
+        let rotated = client.rotate_crypto_key(&crypto_key.name).await?;
+        let second = rotated.primary.unwrap();
+        assert_eq!(
+            second.name,
+            format!("{}/cryptoKeyVersions/2", crypto_key.name)
+        );
+
+        let decrypted = client.decrypt(&crypto_key.name, ciphertext.clone()).await?;
+        assert_eq!(decrypted.plaintext, b"secret");
+        assert!(!decrypted.used_primary);
+
+        let destroyed = client.destroy_crypto_key_version(&first.name).await?;
+        assert_eq!(destroyed.state(), CryptoKeyVersionState::DestroyScheduled);
+        assert!(destroyed.destroy_time.is_some());
+        match client.decrypt(&crypto_key.name, ciphertext.clone()).await {
+            Err(crate::error::Error::Status(status)) => {
+                assert_eq!(status.code(), Code::FailedPrecondition)
+            }
+            result => panic!("unexpected result: {:?}", result.map(|res| res.plaintext)),
+        }
+
+        let restored = client.restore_crypto_key_version(&first.name).await?;
+        assert_eq!(restored.state(), CryptoKeyVersionState::Disabled);
+        let enabled = client
+            .update_crypto_key_version(
+                CryptoKeyVersion {
+                    state: CryptoKeyVersionState::Enabled as i32,
+                    ..restored
+                },
+                &["state"],
+            )
+            .await?;
+        assert_eq!(enabled.state(), CryptoKeyVersionState::Enabled);
+        assert_eq!(
+            client
+                .decrypt(&crypto_key.name, ciphertext)
+                .await?
+                .plaintext,
+            b"secret"
+        );
+
+        let primary = client
+            .update_crypto_key_primary_version(&crypto_key.name, "1")
+            .await?;
+        assert_eq!(primary.primary.unwrap().name, first.name);
+
+        let versions: Vec<_> = client
+            .stream_crypto_key_versions(&crypto_key.name, &ListOptions::new().with_page_size(1))
+            .items()
+            .map_ok(|version| version.name)
+            .try_collect()
+            .await?;
+        assert_eq!(versions, vec![first.name, second.name]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_crypto_key() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let key_ring = client.create_key_ring(LOCATION, "ring").await?;
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "key",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt)
+                    .with_label("team", "payments")
+                    .build(),
+            )
+            .await?;
+
+        let mut update = crypto_key.clone();
+        update.labels.insert("env".into(), "prod".into());
+        let updated = client.update_crypto_key(update, &["labels"]).await?;
+        assert_eq!(updated.labels.len(), 2);
+        assert_eq!(client.get_crypto_key(&crypto_key.name).await?, updated);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_errors() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        client.create_key_ring(LOCATION, "ring").await?;
+
+        let error = client.create_key_ring(LOCATION, "ring").await.unwrap_err();
+        assert!(
+            matches!(error, crate::error::Error::Status(status) if status.code() == Code::AlreadyExists)
+        );
+
+        let error = client
+            .get_crypto_key(&format!("{}/keyRings/ring/cryptoKeys/missing", LOCATION))
+            .await
+            .unwrap_err();
+        assert!(error.is_not_found());
+
+        let import_only = client
+            .create_crypto_key(
+                &format!("{}/keyRings/ring", LOCATION),
+                "imported",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt)
+                    .with_import_only(true)
+                    .build(),
+            )
+            .await?;
+        assert_eq!(import_only.primary, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_retries() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+
+        // The create may have been applied, so it is not retried.
+        fake.fail_key_ring_requests(Code::DeadlineExceeded, 1);
+        let error = client.create_key_ring(LOCATION, "ring").await.unwrap_err();
+        assert!(
+            matches!(error, crate::error::Error::Status(status) if status.code() == Code::DeadlineExceeded)
+        );
+        assert_eq!(fake.request_count("CreateKeyRing"), 1);
+
+        fake.fail_key_ring_requests(Code::Unavailable, 1);
+        let key_ring = client.create_key_ring(LOCATION, "ring").await?;
+        assert_eq!(fake.request_count("CreateKeyRing"), 3);
+
+        fake.fail_key_ring_requests(Code::DeadlineExceeded, 1);
+        client.get_key_ring(&key_ring.name).await?;
+        assert_eq!(fake.request_count("GetKeyRing"), 2);
+        Ok(())
+    }
+}
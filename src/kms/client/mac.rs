@@ -0,0 +1,165 @@
+use super::{ensure_size, KmsClient, KmsError, MAX_PLAINTEXT_SIZE};
+use crate::{
+    error::Error,
+    kms::integrity::{crc32c, ensure_name, ensure_verified, verify_crc32c},
+    options::CallOptions,
+    proto::google::cloud::kms::v1::{MacSignRequest, MacSignResponse, MacVerifyRequest},
+};
+
+impl KmsClient {
+    /// Computes the MAC of `data` with an HMAC key version. The secret never leaves
+    /// Cloud KMS.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    /// * `data` - to be signed, at most 64 KiB.
+    pub async fn mac_sign(
+        &self,
+        name: &str,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<MacSignResponse, Error> {
+        self.mac_sign_with_options(name, data, &CallOptions::default())
+            .await
+    }
+
+    pub async fn mac_sign_with_options(
+        &self,
+        name: &str,
+        data: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<MacSignResponse, Error> {
+        let data = data.into();
+        ensure_size("data", &data, MAX_PLAINTEXT_SIZE)?;
+        let response = self
+            .call(
+                MacSignRequest {
+                    name: name.to_owned(),
+                    data_crc32c: crc32c(&data),
+                    data,
+                },
+                vec![("name", name)],
+                options,
+                |mut client, request| async move { client.mac_sign(request).await },
+            )
+            .await?;
+        ensure_name(name, &response.name)?;
+        ensure_verified("data", response.verified_data_crc32c)?;
+        verify_crc32c("mac", &response.mac, response.mac_crc32c)?;
+        Ok(response)
+    }
+
+    /// Checks `mac` against `data` with an HMAC key version, failing with
+    /// [`KmsError::InvalidMac`] if it does not match.
+    ///
+    /// The comparison runs in Cloud KMS in constant time. The outcome is only trusted
+    /// when the server confirms that it arrived intact, and is returned as an error
+    /// rather than a `bool` so that it cannot be ignored by accident.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    /// * `data` - that was signed, at most 64 KiB.
+    /// * `mac`  - to be verified.
+    pub async fn mac_verify(
+        &self,
+        name: &str,
+        data: impl Into<Vec<u8>>,
+        mac: impl Into<Vec<u8>>,
+    ) -> Result<(), Error> {
+        self.mac_verify_with_options(name, data, mac, &CallOptions::default())
+            .await
+    }
+
+    pub async fn mac_verify_with_options(
+        &self,
+        name: &str,
+        data: impl Into<Vec<u8>>,
+        mac: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        let data = data.into();
+        ensure_size("data", &data, MAX_PLAINTEXT_SIZE)?;
+        let mac = mac.into();
+        let response = self
+            .call(
+                MacVerifyRequest {
+                    name: name.to_owned(),
+                    data_crc32c: crc32c(&data),
+                    data,
+                    mac_crc32c: crc32c(&mac),
+                    mac,
+                },
+                vec![("name", name)],
+                options,
+                |mut client, request| async move { client.mac_verify(request).await },
+            )
+            .await?;
+        ensure_name(name, &response.name)?;
+        ensure_verified("data", response.verified_data_crc32c)?;
+        ensure_verified("mac", response.verified_mac_crc32c)?;
+        ensure_verified("success", response.verified_success_integrity)?;
+        if response.success {
+            Ok(())
+        } else {
+            Err(KmsError::InvalidMac.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        kms::{
+            crypto_key::{CryptoKeyPurpose, CryptoKeyVersionAlgorithm},
+            fake, CryptoKeyBuilder,
+        },
+        retry::RetryPolicy,
+    };
+
+    #[tokio::test]
+    async fn test_mac_sign_verify() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        let key_ring = client
+            .create_key_ring("projects/test/locations/global", "ring")
+            .await?;
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "hmac",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::Mac)
+                    .with_algorithm(CryptoKeyVersionAlgorithm::HmacSha256)
+                    .build(),
+            )
+            .await?;
+        let name = format!("{}/cryptoKeyVersions/1", crypto_key.name);
+
+        let mac = client.mac_sign(&name, "payload").await?.mac;
+        assert_eq!(mac.len(), 32);
+        client.mac_verify(&name, "payload", mac.clone()).await?;
+        assert!(matches!(
+            client.mac_verify(&name, "tampered", mac.clone()).await,
+            Err(Error::Kms(KmsError::InvalidMac))
+        ));
+        let oversized = vec![0; MAX_PLAINTEXT_SIZE + 1];
+        assert!(matches!(
+            client.mac_sign(&name, oversized.clone()).await,
+            Err(Error::Kms(KmsError::TooLarge { field: "data", .. }))
+        ));
+        assert!(matches!(
+            client.mac_verify(&name, oversized, mac.clone()).await,
+            Err(Error::Kms(KmsError::TooLarge { field: "data", .. }))
+        ));
+
+        fake.set_corrupt(true);
+        let options = CallOptions::new().with_retry_policy(RetryPolicy::none());
+        assert!(matches!(
+            client
+                .mac_sign_with_options(&name, "payload", &options)
+                .await,
+            Err(Error::Integrity(_))
+        ));
+        Ok(())
+    }
+}
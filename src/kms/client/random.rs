@@ -0,0 +1,102 @@
+use super::{KmsClient, KmsError};
+use crate::{
+    error::Error,
+    kms::{crypto_key::ProtectionLevel, integrity::verify_crc32c},
+    options::CallOptions,
+    proto::google::cloud::kms::v1::GenerateRandomBytesRequest,
+};
+
+const MAX_RANDOM_BYTES: usize = 1024;
+
+impl KmsClient {
+    /// Generates random bytes, e.g. for tokens or key material.
+    ///
+    /// # Arguments
+    /// * `location`         - in the format `projects/*/locations/*`
+    /// * `length`           - number of bytes, at most 1024.
+    /// * `protection_level` - only `Hsm` is supported by Cloud KMS.
+    pub async fn generate_random_bytes(
+        &self,
+        location: &str,
+        length: usize,
+        protection_level: ProtectionLevel,
+    ) -> Result<Vec<u8>, Error> {
+        self.generate_random_bytes_with_options(
+            location,
+            length,
+            protection_level,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn generate_random_bytes_with_options(
+        &self,
+        location: &str,
+        length: usize,
+        protection_level: ProtectionLevel,
+        options: &CallOptions,
+    ) -> Result<Vec<u8>, Error> {
+        if length > MAX_RANDOM_BYTES {
+            return Err(KmsError::TooLarge {
+                field: "random bytes",
+                size: length,
+                limit: MAX_RANDOM_BYTES,
+            }
+            .into());
+        }
+
+        let response = self
+            .call(
+                GenerateRandomBytesRequest {
+                    location: location.to_owned(),
+                    length_bytes: length as i32,
+                    protection_level: protection_level as i32,
+                },
+                vec![("location", location)],
+                options,
+                |mut client, request| async move { client.generate_random_bytes(request).await },
+            )
+            .await?;
+        verify_crc32c("random bytes", &response.data, response.data_crc32c)?;
+        if response.data.len() != length {
+            return Err(Error::Integrity(format!(
+                "requested {} random bytes but got {}",
+                length,
+                response.data.len()
+            )));
+        }
+        Ok(response.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::kms::fake;
+
+    #[tokio::test]
+    async fn test_generate_random_bytes() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let location = "projects/test/locations/global";
+
+        let first = client
+            .generate_random_bytes(location, 32, ProtectionLevel::Hsm)
+            .await?;
+        let second = client
+            .generate_random_bytes(location, 32, ProtectionLevel::Hsm)
+            .await?;
+        assert_eq!(first.len(), 32);
+        assert_ne!(first, second);
+
+        assert!(matches!(
+            client
+                .generate_random_bytes(location, 1025, ProtectionLevel::Hsm)
+                .await,
+            Err(Error::Kms(KmsError::TooLarge { .. }))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+pub use crate::proto::google::cloud::kms::v1::{
+    crypto_key::CryptoKeyPurpose,
+    crypto_key_version::{CryptoKeyVersionAlgorithm, CryptoKeyVersionState},
+    ProtectionLevel,
+};
+use crate::proto::google::cloud::kms::v1::{
+    crypto_key::RotationSchedule, CryptoKey, CryptoKeyVersionTemplate,
+};
+
+/// Builds the [`CryptoKey`] passed to [`KmsClient::create_crypto_key`](super::KmsClient::create_crypto_key).
+#[derive(Debug, Clone)]
+pub struct CryptoKeyBuilder {
+    purpose: CryptoKeyPurpose,
+    algorithm: Option<CryptoKeyVersionAlgorithm>,
+    protection_level: Option<ProtectionLevel>,
+    rotation: Option<(Duration, SystemTime)>,
+    labels: HashMap<String, String>,
+    import_only: bool,
+    destroy_scheduled_duration: Option<Duration>,
+}
+
+impl CryptoKeyBuilder {
+    /// Keys with purpose `EncryptDecrypt` default to `GoogleSymmetricEncryption`;
+    /// every other purpose needs an explicit algorithm.
+    pub fn new(purpose: CryptoKeyPurpose) -> Self {
+        Self {
+            purpose,
+            algorithm: None,
+            protection_level: None,
+            rotation: None,
+            labels: HashMap::new(),
+            import_only: false,
+            destroy_scheduled_duration: None,
+        }
+    }
+
+    pub fn with_algorithm(mut self, algorithm: CryptoKeyVersionAlgorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+
+    /// Defaults to `Software`.
+    pub fn with_protection_level(mut self, protection_level: ProtectionLevel) -> Self {
+        self.protection_level = Some(protection_level);
+        self
+    }
+
+    /// Rotates the key automatically every `period`, starting at `next_rotation_time`.
+    /// Only supported for `EncryptDecrypt` keys; `period` must be at least 24 hours.
+    pub fn with_rotation(mut self, period: Duration, next_rotation_time: SystemTime) -> Self {
+        self.rotation = Some((period, next_rotation_time));
+        self
+    }
+
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Versions can only be added by importing key material.
+    pub fn with_import_only(mut self, import_only: bool) -> Self {
+        self.import_only = import_only;
+        self
+    }
+
+    /// How long versions stay in `DestroyScheduled` before they are destroyed.
+    /// Defaults to 24 hours.
+    pub fn with_destroy_scheduled_duration(mut self, duration: Duration) -> Self {
+        self.destroy_scheduled_duration = Some(duration);
+        self
+    }
+
+    pub fn build(self) -> CryptoKey {
+        let algorithm = self.algorithm.or(match self.purpose {
+            CryptoKeyPurpose::EncryptDecrypt => {
+                Some(CryptoKeyVersionAlgorithm::GoogleSymmetricEncryption)
+            }
+            _ => None,
+        });
+        let version_template = match (algorithm, self.protection_level) {
+            (None, None) => None,
+            (algorithm, protection_level) => Some(CryptoKeyVersionTemplate {
+                algorithm: algorithm.unwrap_or(CryptoKeyVersionAlgorithm::Unspecified) as i32,
+                protection_level: protection_level.unwrap_or(ProtectionLevel::Unspecified) as i32,
+            }),
+        };
+
+        CryptoKey {
+            purpose: self.purpose as i32,
+            version_template,
+            labels: self.labels,
+            import_only: self.import_only,
+            next_rotation_time: self.rotation.map(|(_, time)| time.into()),
+            rotation_schedule: self
+                .rotation
+                .map(|(period, _)| RotationSchedule::RotationPeriod(period.into())),
+            destroy_scheduled_duration: self.destroy_scheduled_duration.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let now = SystemTime::now();
+        let key = CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt)
+            .with_protection_level(ProtectionLevel::Hsm)
+            .with_rotation(Duration::from_secs(86400), now)
+            .with_label("team", "payments")
+            .build();
+
+        assert_eq!(key.purpose(), CryptoKeyPurpose::EncryptDecrypt);
+        assert_eq!(
+            key.version_template,
+            Some(CryptoKeyVersionTemplate {
+                protection_level: ProtectionLevel::Hsm as i32,
+                algorithm: CryptoKeyVersionAlgorithm::GoogleSymmetricEncryption as i32,
+            })
+        );
+        assert_eq!(
+            key.rotation_schedule,
+            Some(RotationSchedule::RotationPeriod(prost_types::Duration {
+                seconds: 86400,
+                nanos: 0
+            }))
+        );
+        assert_eq!(key.next_rotation_time, Some(now.into()));
+        assert_eq!(key.labels["team"], "payments");
+    }
+
+    #[test]
+    fn test_build_without_template() {
+        let key = CryptoKeyBuilder::new(CryptoKeyPurpose::AsymmetricSign).build();
+        assert_eq!(key.version_template, None);
+        assert_eq!(key.rotation_schedule, None);
+    }
+}
@@ -0,0 +1,643 @@
+//! Envelope encryption for payloads larger than Cloud KMS accepts.
+//!
+//! Each payload is encrypted locally with a random AES-256-GCM data encryption key
+//! (DEK), and only the DEK is sent to Cloud KMS to be wrapped by a crypto key. The
+//! output is self-describing, so [`EnvelopeCipher::decrypt`] needs nothing else:
+//!
+//! ```text
+//! magic         "KMSE"
+//! version       u8, currently 1
+//! key name      u16 length, UTF-8 crypto key name that wrapped the DEK
+//! wrapped DEK   u32 length, ciphertext returned by Cloud KMS
+//! segment size  u32, plaintext bytes per segment
+//! nonce prefix  7 bytes
+//! segments      AES-256-GCM STREAM (big-endian 32-bit counter) segments
+//! ```
+//!
+//! Every segment but the last holds exactly `segment size` bytes of plaintext plus a
+//! 16 byte tag, and the header is authenticated as associated data of every segment.
+//! Reordered, modified or truncated ciphertexts fail to decrypt.
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use aes_gcm::{
+    aead::{
+        stream::{DecryptorBE32, EncryptorBE32},
+        KeyInit, Payload,
+    },
+    Aes256Gcm, Key,
+};
+use rand::Rng;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    error::Error,
+    kms::{client::KmsError, KmsClient},
+    options::CallOptions,
+};
+
+const MAGIC: &[u8; 4] = b"KMSE";
+const VERSION: u8 = 1;
+const NONCE_PREFIX_LEN: usize = 7;
+const TAG_LEN: usize = 16;
+const DEK_LEN: usize = 32;
+const MAX_WRAPPED_DEK_LEN: usize = 64 * 1024;
+const MAX_SEGMENT_SIZE: usize = 16 * 1024 * 1024;
+const DEFAULT_SEGMENT_SIZE: usize = 64 * 1024;
+const UNWRAPPED_CACHE_CAPACITY: usize = 128;
+/// Payloads per cached DEK. Nonce prefixes are random 56-bit values, so this keeps
+/// the chance of two payloads sharing a nonce below 2^-16.
+const MAX_DEK_USES: u64 = 1 << 20;
+
+/// Encrypts and decrypts payloads of any size with a DEK wrapped by a Cloud KMS key.
+///
+/// Clones share the DEK cache.
+#[derive(Clone)]
+pub struct EnvelopeCipher {
+    client: KmsClient,
+    key_name: String,
+    segment_size: usize,
+    cache: Option<Arc<Mutex<DekCache>>>,
+}
+
+impl EnvelopeCipher {
+    /// # Arguments
+    /// * `client`   - used to wrap and unwrap DEKs.
+    /// * `key_name` - in the format `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    pub fn new(client: KmsClient, key_name: impl Into<String>) -> Self {
+        Self {
+            client,
+            key_name: key_name.into(),
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            cache: None,
+        }
+    }
+
+    /// Plaintext bytes per segment, 64 KiB by default. Streaming keeps about two
+    /// segments in memory. Clamped to `1..=16 MiB`.
+    pub fn with_segment_size(mut self, segment_size: usize) -> Self {
+        self.segment_size = segment_size.clamp(1, MAX_SEGMENT_SIZE);
+        self
+    }
+
+    /// Reuses a DEK for up to `max_uses` payloads or `max_age`, whichever comes first,
+    /// and keeps recently unwrapped DEKs, so that most calls skip Cloud KMS.
+    /// `max_uses` is clamped to `1..=2^20` to keep random nonces from repeating.
+    ///
+    /// Without a cache every payload gets a fresh DEK.
+    pub fn with_dek_cache(mut self, max_age: Duration, max_uses: u64) -> Self {
+        let max_uses = max_uses.clamp(1, MAX_DEK_USES);
+        self.cache = Some(Arc::new(Mutex::new(DekCache::new(max_age, max_uses))));
+        self
+    }
+
+    pub fn key_name(&self) -> &str {
+        &self.key_name
+    }
+
+    pub async fn encrypt(&self, plaintext: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+        self.encrypt_with_options(plaintext, &CallOptions::default())
+            .await
+    }
+
+    pub async fn encrypt_with_options(
+        &self,
+        plaintext: impl AsRef<[u8]>,
+        options: &CallOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let mut ciphertext = Vec::new();
+        self.encrypt_stream_with_options(plaintext.as_ref(), &mut ciphertext, options)
+            .await?;
+        Ok(ciphertext)
+    }
+
+    pub async fn decrypt(&self, ciphertext: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+        self.decrypt_with_options(ciphertext, &CallOptions::default())
+            .await
+    }
+
+    pub async fn decrypt_with_options(
+        &self,
+        ciphertext: impl AsRef<[u8]>,
+        options: &CallOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let mut plaintext = Vec::new();
+        self.decrypt_stream_with_options(ciphertext.as_ref(), &mut plaintext, options)
+            .await?;
+        Ok(plaintext)
+    }
+
+    /// Encrypts everything `reader` yields into `writer`.
+    pub async fn encrypt_stream<R, W>(&self, reader: R, writer: W) -> Result<(), Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        self.encrypt_stream_with_options(reader, writer, &CallOptions::default())
+            .await
+    }
+
+    pub async fn encrypt_stream_with_options<R, W>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        options: &CallOptions,
+    ) -> Result<(), Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let (dek, wrapped_dek) = self.data_key(options).await?;
+        let header = Header {
+            key_name: self.key_name.clone(),
+            wrapped_dek,
+            segment_size: self.segment_size,
+            nonce_prefix: rand::thread_rng().gen(),
+        };
+        let aad = header.encode()?;
+        writer.write_all(&aad).await?;
+
+        let mut encryptor =
+            EncryptorBE32::from_aead(Aes256Gcm::new(&dek), (&header.nonce_prefix).into());
+        let too_large = || KmsError::InvalidEnvelope("too many segments".to_owned());
+        let mut current = Vec::with_capacity(header.segment_size);
+        let mut next = Vec::with_capacity(header.segment_size);
+        read_segment(&mut reader, &mut current, header.segment_size).await?;
+        loop {
+            read_segment(&mut reader, &mut next, header.segment_size).await?;
+            let payload = Payload {
+                msg: &current,
+                aad: &aad,
+            };
+            if next.is_empty() {
+                let segment = encryptor.encrypt_last(payload).map_err(|_| too_large())?;
+                writer.write_all(&segment).await?;
+                break;
+            }
+            let segment = encryptor.encrypt_next(payload).map_err(|_| too_large())?;
+            writer.write_all(&segment).await?;
+            std::mem::swap(&mut current, &mut next);
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Decrypts a ciphertext read from `reader` into `writer`.
+    ///
+    /// Segments are written as soon as they are authenticated, so when this fails
+    /// everything written so far must be discarded.
+    pub async fn decrypt_stream<R, W>(&self, reader: R, writer: W) -> Result<(), Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        self.decrypt_stream_with_options(reader, writer, &CallOptions::default())
+            .await
+    }
+
+    /// Fails without calling Cloud KMS if the ciphertext names a crypto key other
+    /// than [`EnvelopeCipher::key_name`], so ciphertexts cannot pick the key that
+    /// this cipher's credentials decrypt with.
+    pub async fn decrypt_stream_with_options<R, W>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        options: &CallOptions,
+    ) -> Result<(), Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let header = Header::read(&mut reader).await?;
+        if header.key_name != self.key_name {
+            return Err(KmsError::InvalidEnvelope(format!(
+                "DEK wrapped by {}, expected {}",
+                header.key_name, self.key_name
+            ))
+            .into());
+        }
+        let aad = header.encode()?;
+        let dek = self.unwrap_key(&header.wrapped_dek, options).await?;
+
+        let mut decryptor =
+            DecryptorBE32::from_aead(Aes256Gcm::new(&dek), (&header.nonce_prefix).into());
+        let segment_len = header.segment_size + TAG_LEN;
+        let mut current = Vec::with_capacity(segment_len);
+        let mut next = Vec::with_capacity(segment_len);
+        read_segment(&mut reader, &mut current, segment_len).await?;
+        loop {
+            read_segment(&mut reader, &mut next, segment_len).await?;
+            let payload = Payload {
+                msg: &current,
+                aad: &aad,
+            };
+            if next.is_empty() {
+                let segment = decryptor
+                    .decrypt_last(payload)
+                    .map_err(|_| KmsError::EnvelopeDecryption)?;
+                writer.write_all(&segment).await?;
+                break;
+            }
+            let segment = decryptor
+                .decrypt_next(payload)
+                .map_err(|_| KmsError::EnvelopeDecryption)?;
+            writer.write_all(&segment).await?;
+            std::mem::swap(&mut current, &mut next);
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// A DEK and its wrapped form, from the cache or freshly wrapped by Cloud KMS.
+    async fn data_key(&self, options: &CallOptions) -> Result<(Key<Aes256Gcm>, Vec<u8>), Error> {
+        if let Some(cache) = &self.cache {
+            if let Some(data_key) = cache.lock().unwrap().acquire() {
+                return Ok(data_key);
+            }
+        }
+
+        let dek = Key::<Aes256Gcm>::from(rand::thread_rng().gen::<[u8; DEK_LEN]>());
+        let wrapped_dek = self
+            .client
+            .encrypt_with_options(&self.key_name, dek.to_vec(), options)
+            .await?
+            .ciphertext;
+
+        if let Some(cache) = &self.cache {
+            cache
+                .lock()
+                .unwrap()
+                .insert(&self.key_name, dek, wrapped_dek.clone());
+        }
+        Ok((dek, wrapped_dek))
+    }
+
+    async fn unwrap_key(
+        &self,
+        wrapped_dek: &[u8],
+        options: &CallOptions,
+    ) -> Result<Key<Aes256Gcm>, Error> {
+        if let Some(cache) = &self.cache {
+            if let Some(dek) = cache.lock().unwrap().get(&self.key_name, wrapped_dek) {
+                return Ok(dek);
+            }
+        }
+
+        let plaintext = self
+            .client
+            .decrypt_with_options(&self.key_name, wrapped_dek, options)
+            .await?
+            .plaintext;
+        let dek = <[u8; DEK_LEN]>::try_from(plaintext.as_slice()).map_err(|_| {
+            KmsError::InvalidEnvelope(format!("unwrapped DEK has {} bytes", plaintext.len()))
+        })?;
+        let dek = Key::<Aes256Gcm>::from(dek);
+
+        if let Some(cache) = &self.cache {
+            cache
+                .lock()
+                .unwrap()
+                .remember(&self.key_name, wrapped_dek.to_vec(), dek);
+        }
+        Ok(dek)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Header {
+    key_name: String,
+    wrapped_dek: Vec<u8>,
+    segment_size: usize,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+}
+
+impl Header {
+    fn encode(&self) -> Result<Vec<u8>, KmsError> {
+        let key_name_len = u16::try_from(self.key_name.len())
+            .map_err(|_| KmsError::InvalidEnvelope("key name too long".to_owned()))?;
+        if self.wrapped_dek.len() > MAX_WRAPPED_DEK_LEN {
+            return Err(KmsError::InvalidEnvelope("wrapped DEK too long".to_owned()));
+        }
+
+        let mut header = Vec::with_capacity(
+            MAGIC.len() + 11 + self.key_name.len() + self.wrapped_dek.len() + NONCE_PREFIX_LEN,
+        );
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&key_name_len.to_be_bytes());
+        header.extend_from_slice(self.key_name.as_bytes());
+        header.extend_from_slice(&(self.wrapped_dek.len() as u32).to_be_bytes());
+        header.extend_from_slice(&self.wrapped_dek);
+        header.extend_from_slice(&(self.segment_size as u32).to_be_bytes());
+        header.extend_from_slice(&self.nonce_prefix);
+        Ok(header)
+    }
+
+    async fn read<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Self, Error> {
+        let invalid = |message: &str| Error::from(KmsError::InvalidEnvelope(message.to_owned()));
+
+        let mut magic = [0; 4];
+        read_exact(reader, &mut magic).await?;
+        if &magic != MAGIC {
+            return Err(invalid("not an envelope ciphertext"));
+        }
+        let version = reader.read_u8().await.map_err(truncated)?;
+        if version != VERSION {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+
+        let key_name_len = reader.read_u16().await.map_err(truncated)? as usize;
+        let mut key_name = vec![0; key_name_len];
+        read_exact(reader, &mut key_name).await?;
+        let key_name = String::from_utf8(key_name).map_err(|_| invalid("key name is not UTF-8"))?;
+
+        let wrapped_dek_len = reader.read_u32().await.map_err(truncated)? as usize;
+        if wrapped_dek_len > MAX_WRAPPED_DEK_LEN {
+            return Err(invalid("wrapped DEK too long"));
+        }
+        let mut wrapped_dek = vec![0; wrapped_dek_len];
+        read_exact(reader, &mut wrapped_dek).await?;
+
+        let segment_size = reader.read_u32().await.map_err(truncated)? as usize;
+        if !(1..=MAX_SEGMENT_SIZE).contains(&segment_size) {
+            return Err(invalid("segment size out of range"));
+        }
+        let mut nonce_prefix = [0; NONCE_PREFIX_LEN];
+        read_exact(reader, &mut nonce_prefix).await?;
+
+        Ok(Self {
+            key_name,
+            wrapped_dek,
+            segment_size,
+            nonce_prefix,
+        })
+    }
+}
+
+async fn read_exact<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> Result<(), Error> {
+    reader.read_exact(buf).await.map_err(truncated)?;
+    Ok(())
+}
+
+fn truncated(error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof => {
+            KmsError::InvalidEnvelope("truncated header".to_owned()).into()
+        }
+        _ => error.into(),
+    }
+}
+
+/// Reads up to `len` bytes, fewer only at the end of the input.
+async fn read_segment<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    len: usize,
+) -> io::Result<()> {
+    buf.clear();
+    reader.take(len as u64).read_to_end(buf).await?;
+    Ok(())
+}
+
+struct DekCache {
+    max_age: Duration,
+    max_uses: u64,
+    current: Option<CurrentDek>,
+    /// DEKs by crypto key name and wrapped DEK.
+    unwrapped: HashMap<(String, Vec<u8>), (Key<Aes256Gcm>, Instant)>,
+}
+
+struct CurrentDek {
+    dek: Key<Aes256Gcm>,
+    wrapped_dek: Vec<u8>,
+    created: Instant,
+    uses: u64,
+}
+
+impl DekCache {
+    fn new(max_age: Duration, max_uses: u64) -> Self {
+        Self {
+            max_age,
+            max_uses,
+            current: None,
+            unwrapped: HashMap::new(),
+        }
+    }
+
+    /// The DEK to encrypt with, if the current one is neither too old nor used up.
+    fn acquire(&mut self) -> Option<(Key<Aes256Gcm>, Vec<u8>)> {
+        let current = self.current.as_mut()?;
+        if current.created.elapsed() >= self.max_age || current.uses >= self.max_uses {
+            self.current = None;
+            return None;
+        }
+        current.uses += 1;
+        Some((current.dek, current.wrapped_dek.clone()))
+    }
+
+    /// Makes a freshly wrapped DEK, already used once, the current one.
+    fn insert(&mut self, key_name: &str, dek: Key<Aes256Gcm>, wrapped_dek: Vec<u8>) {
+        self.remember(key_name, wrapped_dek.clone(), dek);
+        self.current = Some(CurrentDek {
+            dek,
+            wrapped_dek,
+            created: Instant::now(),
+            uses: 1,
+        });
+    }
+
+    fn get(&self, key_name: &str, wrapped_dek: &[u8]) -> Option<Key<Aes256Gcm>> {
+        self.unwrapped
+            .get(&(key_name.to_owned(), wrapped_dek.to_vec()))
+            .filter(|(_, added)| added.elapsed() < self.max_age)
+            .map(|(dek, _)| *dek)
+    }
+
+    fn remember(&mut self, key_name: &str, wrapped_dek: Vec<u8>, dek: Key<Aes256Gcm>) {
+        let max_age = self.max_age;
+        self.unwrapped
+            .retain(|_, (_, added)| added.elapsed() < max_age);
+        if self.unwrapped.len() >= UNWRAPPED_CACHE_CAPACITY {
+            let oldest = self
+                .unwrapped
+                .iter()
+                .min_by_key(|(_, (_, added))| *added)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.unwrapped.remove(&oldest);
+            }
+        }
+        self.unwrapped
+            .insert((key_name.to_owned(), wrapped_dek), (dek, Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::kms::{crypto_key::CryptoKeyPurpose, fake, fake::FakeKms, CryptoKeyBuilder};
+
+    async fn setup() -> anyhow::Result<(KmsClient, FakeKms, String)> {
+        let (client, fake) = fake::start().await?;
+        let key_ring = client
+            .create_key_ring("projects/test/locations/global", "ring")
+            .await?;
+        let crypto_key = client
+            .create_crypto_key(
+                &key_ring.name,
+                "key",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt).build(),
+            )
+            .await?;
+        Ok((client, fake, crypto_key.name))
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(16)]
+    #[case(17)]
+    #[case(100)]
+    #[tokio::test]
+    async fn test_roundtrip(#[case] len: usize) -> anyhow::Result<()> {
+        let (client, _fake, key_name) = setup().await?;
+        let cipher = EnvelopeCipher::new(client, &key_name).with_segment_size(16);
+
+        let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let ciphertext = cipher.encrypt(&plaintext).await?;
+        assert!(ciphertext.starts_with(MAGIC));
+
+        let header = Header::read(&mut ciphertext.as_slice()).await?;
+        assert_eq!(header.key_name, key_name);
+        let segments = len.div_ceil(16).max(1);
+        assert_eq!(
+            ciphertext.len(),
+            header.encode()?.len() + len + segments * TAG_LEN
+        );
+
+        assert_eq!(cipher.decrypt(&ciphertext).await?, plaintext);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream() -> anyhow::Result<()> {
+        let (client, _fake, key_name) = setup().await?;
+        let cipher = EnvelopeCipher::new(client, key_name);
+
+        let plaintext = vec![7; 3 * DEFAULT_SEGMENT_SIZE + 5];
+        let mut ciphertext = Vec::new();
+        cipher
+            .encrypt_stream(plaintext.as_slice(), &mut ciphertext)
+            .await?;
+        let mut decrypted = Vec::new();
+        cipher
+            .decrypt_stream(ciphertext.as_slice(), &mut decrypted)
+            .await?;
+        assert_eq!(decrypted, plaintext);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tampering() -> anyhow::Result<()> {
+        let (client, _fake, key_name) = setup().await?;
+        let cipher = EnvelopeCipher::new(client, key_name).with_segment_size(16);
+        let ciphertext = cipher.encrypt([1; 40]).await?;
+
+        let mut modified = ciphertext.clone();
+        *modified.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            cipher.decrypt(&modified).await,
+            Err(Error::Kms(KmsError::EnvelopeDecryption))
+        ));
+
+        // Dropping the last segment leaves a complete, but non-final, segment.
+        let truncated = &ciphertext[..ciphertext.len() - (40 % 16 + TAG_LEN)];
+        assert!(matches!(
+            cipher.decrypt(truncated).await,
+            Err(Error::Kms(KmsError::EnvelopeDecryption))
+        ));
+
+        assert!(matches!(
+            cipher.decrypt(&ciphertext[..10]).await,
+            Err(Error::Kms(KmsError::InvalidEnvelope(_)))
+        ));
+        assert!(matches!(
+            cipher.decrypt(b"not an envelope").await,
+            Err(Error::Kms(KmsError::InvalidEnvelope(_)))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_other_key() -> anyhow::Result<()> {
+        let (client, fake, key_name) = setup().await?;
+        let other_key = client
+            .create_crypto_key(
+                "projects/test/locations/global/keyRings/ring",
+                "other",
+                CryptoKeyBuilder::new(CryptoKeyPurpose::EncryptDecrypt).build(),
+            )
+            .await?;
+        let ciphertext = EnvelopeCipher::new(client.clone(), other_key.name)
+            .encrypt("secret")
+            .await?;
+
+        let cipher = EnvelopeCipher::new(client, key_name);
+        assert!(matches!(
+            cipher.decrypt(&ciphertext).await,
+            Err(Error::Kms(KmsError::InvalidEnvelope(_)))
+        ));
+        assert_eq!(fake.request_count("Decrypt"), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dek_cache() -> anyhow::Result<()> {
+        let (client, fake, key_name) = setup().await?;
+
+        let uncached = EnvelopeCipher::new(client.clone(), &key_name);
+        let first = uncached.encrypt("first").await?;
+        let second = uncached.encrypt("second").await?;
+        assert_eq!(fake.request_count("Encrypt"), 2);
+
+        let cached =
+            EnvelopeCipher::new(client, &key_name).with_dek_cache(Duration::from_secs(60), 2);
+        let third = cached.encrypt("third").await?;
+        cached.encrypt("fourth").await?;
+        assert_eq!(fake.request_count("Encrypt"), 3);
+        cached.encrypt("fifth").await?;
+        assert_eq!(fake.request_count("Encrypt"), 4);
+
+        // DEKs wrapped by the cipher itself never need unwrapping.
+        assert_eq!(cached.decrypt(&third).await?, b"third");
+        assert_eq!(fake.request_count("Decrypt"), 0);
+
+        assert_eq!(cached.decrypt(&first).await?, b"first");
+        assert_eq!(cached.decrypt(&first).await?, b"first");
+        assert_eq!(uncached.decrypt(&second).await?, b"second");
+        assert_eq!(uncached.decrypt(&second).await?, b"second");
+        assert_eq!(fake.request_count("Decrypt"), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dek_cache_max_uses() -> anyhow::Result<()> {
+        let (client, _fake, key_name) = setup().await?;
+        let cipher = EnvelopeCipher::new(client, key_name).with_dek_cache(Duration::MAX, u64::MAX);
+        let cache = cipher.cache.as_ref().unwrap();
+        assert_eq!(cache.lock().unwrap().max_uses, MAX_DEK_USES);
+
+        cipher.encrypt("first").await?;
+        cache.lock().unwrap().current.as_mut().unwrap().uses = MAX_DEK_USES;
+        assert!(cache.lock().unwrap().acquire().is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,941 @@
+//! An in-memory Cloud KMS server for tests.
+//!
+//! Symmetric keys "encrypt" by prefixing the plaintext with the version name and a
+//! hash of the AAD, which is enough to check that requests are routed to the right
+//! key and version, and that the AAD matches.
+//! Asymmetric keys are real, but RSA keys are only 1024 bits to keep tests fast.
+//! Import jobs use the size their method calls for, 3072-bit ones a fixed key.
+
+// Helpers share the error type of the gRPC handlers they serve.
+#![allow(clippy::result_large_err)]
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime},
+};
+
+use hmac::{Hmac, Mac};
+use p256::pkcs8::{DecodePrivateKey, EncodePublicKey, LineEnding};
+use rand::Rng;
+use rsa::{traits::PublicKeyParts, RsaPrivateKey, RsaPublicKey};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::Server, Code, Request, Response, Status};
+
+use crate::{
+    auth::NoAuth,
+    endpoint::Endpoint,
+    kms::{
+        crypto_key::{
+            CryptoKeyPurpose, CryptoKeyVersionAlgorithm, CryptoKeyVersionState, ProtectionLevel,
+        },
+        import_job::{ImportJobState, ImportMethod},
+        integrity::crc32c,
+        public_key::{Hash, Scheme},
+        KmsClient,
+    },
+    proto::google::cloud::kms::v1::{
+        digest,
+        key_management_service_server::{KeyManagementService, KeyManagementServiceServer},
+        *,
+    },
+};
+
+type Reply<T> = Result<Response<T>, Status>;
+
+const SEPARATOR: u8 = b'\0';
+
+fn aad_tag(aad: &[u8]) -> [u8; 8] {
+    use sha2::Digest as _;
+
+    let digest = sha2::Sha256::digest(aad);
+    let mut tag = [0; 8];
+    tag.copy_from_slice(&digest[..8]);
+    tag
+}
+
+/// Clones share the same state.
+#[derive(Clone, Default)]
+pub(crate) struct FakeKms {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    key_rings: BTreeMap<String, KeyRing>,
+    crypto_keys: BTreeMap<String, CryptoKey>,
+    versions: BTreeMap<String, CryptoKeyVersion>,
+    private_keys: BTreeMap<String, PrivateKey>,
+    mac_keys: BTreeMap<String, Vec<u8>>,
+    /// Import jobs and their wrapping keys. New jobs become active once fetched.
+    import_jobs: BTreeMap<String, (ImportJob, RsaPrivateKey)>,
+    /// Number of `Encrypt`, `Decrypt`, `CreateKeyRing` and `GetKeyRing` requests
+    /// received.
+    requests: BTreeMap<&'static str, usize>,
+    /// The code and number of key ring requests still to fail, without effect.
+    key_ring_failures: Option<(Code, usize)>,
+    /// Flips a bit of every checksum in responses, as if they were corrupted in transit.
+    corrupt: bool,
+}
+
+enum PrivateKey {
+    Rsa(RsaPrivateKey),
+    P256(p256::ecdsa::SigningKey),
+    P384(p384::ecdsa::SigningKey),
+}
+
+impl PrivateKey {
+    fn generate(scheme: Scheme) -> Self {
+        let mut rng = rand::thread_rng();
+        match scheme {
+            Scheme::EcdsaP256 => Self::P256(p256::ecdsa::SigningKey::random(&mut rng)),
+            Scheme::EcdsaP384 => Self::P384(p384::ecdsa::SigningKey::random(&mut rng)),
+            _ => Self::Rsa(RsaPrivateKey::new(&mut rng, 1024).unwrap()),
+        }
+    }
+
+    fn from_pkcs8_der(scheme: Scheme, der: &[u8]) -> Result<Self, Status> {
+        let invalid = |e: &dyn std::fmt::Display| Status::invalid_argument(e.to_string());
+        Ok(match scheme {
+            Scheme::EcdsaP256 => {
+                Self::P256(p256::ecdsa::SigningKey::from_pkcs8_der(der).map_err(|e| invalid(&e))?)
+            }
+            Scheme::EcdsaP384 => {
+                Self::P384(p384::ecdsa::SigningKey::from_pkcs8_der(der).map_err(|e| invalid(&e))?)
+            }
+            _ => Self::Rsa(RsaPrivateKey::from_pkcs8_der(der).map_err(|e| invalid(&e))?),
+        })
+    }
+
+    fn public_key_pem(&self) -> String {
+        match self {
+            Self::Rsa(key) => RsaPublicKey::from(key).to_public_key_pem(LineEnding::LF),
+            Self::P256(key) => key.verifying_key().to_public_key_pem(LineEnding::LF),
+            Self::P384(key) => key.verifying_key().to_public_key_pem(LineEnding::LF),
+        }
+        .unwrap()
+    }
+}
+
+/// Starts a fake server on a random local port and returns a client connected to it.
+pub(crate) async fn start() -> anyhow::Result<(KmsClient, FakeKms)> {
+    let fake = FakeKms::default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = Endpoint::new(&format!("http://{}", listener.local_addr()?))?;
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(KeyManagementServiceServer::new(fake.clone()))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let client = KmsClient::builder()
+        .with_token_provider(NoAuth)
+        .with_endpoint(endpoint)
+        .build()
+        .await?;
+    Ok((client, fake))
+}
+
+impl FakeKms {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    pub(crate) fn set_corrupt(&self, corrupt: bool) {
+        self.state().corrupt = corrupt;
+    }
+
+    pub(crate) fn request_count(&self, method: &str) -> usize {
+        self.state()
+            .requests
+            .get(method)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Makes the next `calls` key ring requests fail with `code`.
+    pub(crate) fn fail_key_ring_requests(&self, code: Code, calls: usize) {
+        self.state().key_ring_failures = Some((code, calls));
+    }
+
+    fn record(&self, method: &'static str) {
+        *self.state().requests.entry(method).or_default() += 1;
+    }
+
+    /// Records a key ring request, failing it if asked to.
+    fn record_key_ring_request(&self, method: &'static str) -> Result<(), Status> {
+        self.record(method);
+        match &mut self.state().key_ring_failures {
+            Some((code, calls)) if *calls > 0 => {
+                *calls -= 1;
+                Err(Status::new(*code, "injected failure"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl State {
+    fn crypto_key(&self, name: &str) -> Result<&CryptoKey, Status> {
+        self.crypto_keys
+            .get(name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))
+    }
+
+    fn version_mut(&mut self, name: &str) -> Result<&mut CryptoKeyVersion, Status> {
+        self.versions
+            .get_mut(name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))
+    }
+
+    fn checksum(&self, data: &[u8]) -> Option<i64> {
+        crc32c(data).map(|checksum| if self.corrupt { checksum ^ 1 } else { checksum })
+    }
+
+    /// The enabled version `name` and its private key.
+    fn private_key(&self, name: &str) -> Result<(&CryptoKeyVersion, &PrivateKey), Status> {
+        let version = self
+            .versions
+            .get(name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+        if version.state() != CryptoKeyVersionState::Enabled {
+            return Err(Status::failed_precondition(format!(
+                "{} is not enabled",
+                name
+            )));
+        }
+        let private_key = self
+            .private_keys
+            .get(name)
+            .ok_or_else(|| Status::failed_precondition(format!("{} is not asymmetric", name)))?;
+        Ok((version, private_key))
+    }
+
+    /// The enabled HMAC version `name`, keyed with its secret.
+    fn mac(&self, name: &str) -> Result<(&CryptoKeyVersion, Hmac<sha2::Sha256>), Status> {
+        let version = self
+            .versions
+            .get(name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+        if version.state() != CryptoKeyVersionState::Enabled {
+            return Err(Status::failed_precondition(format!(
+                "{} is not enabled",
+                name
+            )));
+        }
+        let secret = self
+            .mac_keys
+            .get(name)
+            .ok_or_else(|| Status::failed_precondition(format!("{} is not an HMAC key", name)))?;
+        Ok((version, Hmac::new_from_slice(secret).unwrap()))
+    }
+
+    fn create_version(&mut self, parent: &str) -> Result<CryptoKeyVersion, Status> {
+        let crypto_key = self.crypto_key(parent)?;
+        let template = crypto_key.version_template.clone().unwrap_or_default();
+        let id = self
+            .versions
+            .keys()
+            .filter(|name| name.starts_with(&format!("{}/", parent)))
+            .count()
+            + 1;
+        let version = CryptoKeyVersion {
+            name: format!("{}/cryptoKeyVersions/{}", parent, id),
+            state: CryptoKeyVersionState::Enabled as i32,
+            protection_level: template.protection_level,
+            algorithm: template.algorithm,
+            create_time: Some(SystemTime::now().into()),
+            ..Default::default()
+        };
+        if let Ok(scheme) = Scheme::of(version.algorithm()) {
+            self.private_keys
+                .insert(version.name.clone(), PrivateKey::generate(scheme));
+        }
+        if version.algorithm() == CryptoKeyVersionAlgorithm::HmacSha256 {
+            let secret = rand::thread_rng().gen::<[u8; 32]>().to_vec();
+            self.mac_keys.insert(version.name.clone(), secret);
+        }
+        self.versions.insert(version.name.clone(), version.clone());
+        Ok(version)
+    }
+
+    /// Keeps `primary` of the crypto key in sync with the stored version.
+    fn refresh_primary(&mut self, version: &CryptoKeyVersion) {
+        let parent = version.name.split("/cryptoKeyVersions/").next().unwrap();
+        if let Some(crypto_key) = self.crypto_keys.get_mut(parent) {
+            if let Some(primary) = crypto_key.primary.as_mut() {
+                if primary.name == version.name {
+                    *primary = version.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Returns the page starting at `page_token`, an offset, and the token of the next page.
+fn page<'a, T: Clone + 'a>(
+    items: impl Iterator<Item = &'a T>,
+    page_size: i32,
+    page_token: &str,
+) -> Result<(Vec<T>, String), Status> {
+    let items: Vec<_> = items.cloned().collect();
+    let offset: usize = match page_token {
+        "" => 0,
+        token => token
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid page token"))?,
+    };
+    let page_size = if page_size > 0 {
+        page_size as usize
+    } else {
+        1000
+    };
+    let end = (offset + page_size).min(items.len());
+    let next_page_token = if end < items.len() {
+        end.to_string()
+    } else {
+        String::new()
+    };
+    Ok((items[offset.min(end)..end].to_vec(), next_page_token))
+}
+
+fn children<'a, T>(
+    items: &'a BTreeMap<String, T>,
+    parent: &'a str,
+    collection: &'a str,
+) -> impl Iterator<Item = &'a T> {
+    let prefix = format!("{}/{}/", parent, collection);
+    items
+        .iter()
+        .filter(move |(name, _)| {
+            name.strip_prefix(&prefix)
+                .is_some_and(|id| !id.contains('/'))
+        })
+        .map(|(_, item)| item)
+}
+
+#[tonic::async_trait]
+impl KeyManagementService for FakeKms {
+    async fn list_key_rings(
+        &self,
+        request: Request<ListKeyRingsRequest>,
+    ) -> Reply<ListKeyRingsResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        let (key_rings, next_page_token) = page(
+            children(&state.key_rings, &request.parent, "keyRings"),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListKeyRingsResponse {
+            total_size: key_rings.len() as i32,
+            key_rings,
+            next_page_token,
+        }))
+    }
+
+    async fn list_crypto_keys(
+        &self,
+        request: Request<ListCryptoKeysRequest>,
+    ) -> Reply<ListCryptoKeysResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        let (crypto_keys, next_page_token) = page(
+            children(&state.crypto_keys, &request.parent, "cryptoKeys"),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListCryptoKeysResponse {
+            total_size: crypto_keys.len() as i32,
+            crypto_keys,
+            next_page_token,
+        }))
+    }
+
+    async fn list_crypto_key_versions(
+        &self,
+        request: Request<ListCryptoKeyVersionsRequest>,
+    ) -> Reply<ListCryptoKeyVersionsResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        let (crypto_key_versions, next_page_token) = page(
+            children(&state.versions, &request.parent, "cryptoKeyVersions"),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListCryptoKeyVersionsResponse {
+            total_size: crypto_key_versions.len() as i32,
+            crypto_key_versions,
+            next_page_token,
+        }))
+    }
+
+    async fn list_import_jobs(
+        &self,
+        _request: Request<ListImportJobsRequest>,
+    ) -> Reply<ListImportJobsResponse> {
+        Err(Status::unimplemented("list_import_jobs"))
+    }
+
+    async fn get_key_ring(&self, request: Request<GetKeyRingRequest>) -> Reply<KeyRing> {
+        self.record_key_ring_request("GetKeyRing")?;
+        let name = request.into_inner().name;
+        self.state()
+            .key_rings
+            .get(&name)
+            .cloned()
+            .map(Response::new)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))
+    }
+
+    async fn get_crypto_key(&self, request: Request<GetCryptoKeyRequest>) -> Reply<CryptoKey> {
+        let name = request.into_inner().name;
+        Ok(Response::new(self.state().crypto_key(&name)?.clone()))
+    }
+
+    async fn get_crypto_key_version(
+        &self,
+        request: Request<GetCryptoKeyVersionRequest>,
+    ) -> Reply<CryptoKeyVersion> {
+        let name = request.into_inner().name;
+        Ok(Response::new(self.state().version_mut(&name)?.clone()))
+    }
+
+    async fn get_public_key(&self, request: Request<GetPublicKeyRequest>) -> Reply<PublicKey> {
+        let name = request.into_inner().name;
+        let state = self.state();
+        let (version, private_key) = state.private_key(&name)?;
+        let pem = private_key.public_key_pem();
+        Ok(Response::new(PublicKey {
+            pem_crc32c: state.checksum(pem.as_bytes()),
+            pem,
+            algorithm: version.algorithm,
+            name,
+            protection_level: version.protection_level,
+        }))
+    }
+
+    async fn get_import_job(&self, request: Request<GetImportJobRequest>) -> Reply<ImportJob> {
+        let name = request.into_inner().name;
+        let mut state = self.state();
+        let (import_job, private_key) = state
+            .import_jobs
+            .get_mut(&name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+        if import_job.state() == ImportJobState::PendingGeneration {
+            import_job.state = ImportJobState::Active as i32;
+            import_job.generate_time = Some(SystemTime::now().into());
+            import_job.public_key = Some(import_job::WrappingPublicKey {
+                pem: PrivateKey::Rsa(private_key.clone()).public_key_pem(),
+            });
+        }
+        Ok(Response::new(import_job.clone()))
+    }
+
+    async fn create_key_ring(&self, request: Request<CreateKeyRingRequest>) -> Reply<KeyRing> {
+        self.record_key_ring_request("CreateKeyRing")?;
+        let request = request.into_inner();
+        let name = format!("{}/keyRings/{}", request.parent, request.key_ring_id);
+        let mut state = self.state();
+        if state.key_rings.contains_key(&name) {
+            return Err(Status::already_exists(format!("{} already exists", name)));
+        }
+        let key_ring = KeyRing {
+            name: name.clone(),
+            create_time: Some(SystemTime::now().into()),
+        };
+        state.key_rings.insert(name, key_ring.clone());
+        Ok(Response::new(key_ring))
+    }
+
+    async fn create_crypto_key(
+        &self,
+        request: Request<CreateCryptoKeyRequest>,
+    ) -> Reply<CryptoKey> {
+        let request = request.into_inner();
+        let name = format!("{}/cryptoKeys/{}", request.parent, request.crypto_key_id);
+        let mut state = self.state();
+        if !state.key_rings.contains_key(&request.parent) {
+            return Err(Status::not_found(format!("{} not found", request.parent)));
+        }
+        if state.crypto_keys.contains_key(&name) {
+            return Err(Status::already_exists(format!("{} already exists", name)));
+        }
+        let crypto_key = CryptoKey {
+            name: name.clone(),
+            create_time: Some(SystemTime::now().into()),
+            ..request.crypto_key.unwrap_or_default()
+        };
+        if crypto_key.purpose() == CryptoKeyPurpose::Unspecified {
+            return Err(Status::invalid_argument("purpose is required"));
+        }
+        let purpose = crypto_key.purpose();
+        state.crypto_keys.insert(name.clone(), crypto_key);
+
+        if !request.skip_initial_version_creation {
+            let version = state.create_version(&name)?;
+            if purpose == CryptoKeyPurpose::EncryptDecrypt {
+                state.crypto_keys.get_mut(&name).unwrap().primary = Some(version);
+            }
+        }
+        Ok(Response::new(state.crypto_key(&name)?.clone()))
+    }
+
+    async fn create_crypto_key_version(
+        &self,
+        request: Request<CreateCryptoKeyVersionRequest>,
+    ) -> Reply<CryptoKeyVersion> {
+        let parent = request.into_inner().parent;
+        Ok(Response::new(self.state().create_version(&parent)?))
+    }
+
+    async fn import_crypto_key_version(
+        &self,
+        request: Request<ImportCryptoKeyVersionRequest>,
+    ) -> Reply<CryptoKeyVersion> {
+        let request = request.into_inner();
+        let mut state = self.state();
+        let (import_job, private_key) = state
+            .import_jobs
+            .get(&request.import_job)
+            .ok_or_else(|| Status::not_found(format!("{} not found", request.import_job)))?;
+        if import_job.state() != ImportJobState::Active {
+            return Err(Status::failed_precondition(format!(
+                "{} is not active",
+                request.import_job
+            )));
+        }
+
+        let invalid = || Status::invalid_argument("invalid wrapped key material");
+        let wrapped = match request.wrapped_key_material {
+            Some(import_crypto_key_version_request::WrappedKeyMaterial::RsaAesWrappedKey(
+                wrapped,
+            )) => wrapped,
+            None => return Err(invalid()),
+        };
+        let (aes_key, kwp) = wrapped.split_at(private_key.size().min(wrapped.len()));
+        let aes_key: [u8; 32] = private_key
+            .decrypt(Hash::Sha1.oaep(), aes_key)
+            .map_err(|_| invalid())?
+            .try_into()
+            .map_err(|_| invalid())?;
+        let key_material = aes_kw::KekAes256::from(aes_key)
+            .unwrap_with_padding_vec(kwp)
+            .map_err(|_| invalid())?;
+        let import_job = import_job.name.clone();
+
+        let mut version = state.create_version(&request.parent)?;
+        version.algorithm = request.algorithm;
+        version.import_job = import_job;
+        version.import_time = Some(SystemTime::now().into());
+        state.private_keys.remove(&version.name);
+        state.mac_keys.remove(&version.name);
+        match Scheme::of(version.algorithm()) {
+            Ok(scheme) => {
+                let private_key = PrivateKey::from_pkcs8_der(scheme, &key_material)?;
+                state.private_keys.insert(version.name.clone(), private_key);
+            }
+            Err(_) if version.algorithm() == CryptoKeyVersionAlgorithm::HmacSha256 => {
+                state.mac_keys.insert(version.name.clone(), key_material);
+            }
+            Err(_) => {}
+        }
+        state.versions.insert(version.name.clone(), version.clone());
+        Ok(Response::new(version))
+    }
+
+    async fn create_import_job(
+        &self,
+        request: Request<CreateImportJobRequest>,
+    ) -> Reply<ImportJob> {
+        let request = request.into_inner();
+        let name = format!("{}/importJobs/{}", request.parent, request.import_job_id);
+        let mut state = self.state();
+        if !state.key_rings.contains_key(&request.parent) {
+            return Err(Status::not_found(format!("{} not found", request.parent)));
+        }
+        if state.import_jobs.contains_key(&name) {
+            return Err(Status::already_exists(format!("{} already exists", name)));
+        }
+        let import_job = ImportJob {
+            name: name.clone(),
+            state: ImportJobState::PendingGeneration as i32,
+            create_time: Some(SystemTime::now().into()),
+            ..request.import_job.unwrap_or_default()
+        };
+        if import_job.import_method() == ImportMethod::Unspecified {
+            return Err(Status::invalid_argument("import_method is required"));
+        }
+        let private_key = match import_job.import_method() {
+            // Generating one takes seconds in debug builds.
+            ImportMethod::RsaOaep3072Sha1Aes256 => {
+                RsaPrivateKey::from_pkcs8_pem(include_str!("testdata/rsa_3072.pem")).unwrap()
+            }
+            method => {
+                RsaPrivateKey::new(&mut rand::thread_rng(), method.rsa_bits().unwrap()).unwrap()
+            }
+        };
+        state
+            .import_jobs
+            .insert(name, (import_job.clone(), private_key));
+        Ok(Response::new(import_job))
+    }
+
+    async fn update_crypto_key(
+        &self,
+        request: Request<UpdateCryptoKeyRequest>,
+    ) -> Reply<CryptoKey> {
+        let request = request.into_inner();
+        let update = request.crypto_key.unwrap_or_default();
+        let mut state = self.state();
+        let crypto_key = state
+            .crypto_keys
+            .get_mut(&update.name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", update.name)))?;
+
+        for path in request.update_mask.unwrap_or_default().paths {
+            match path.as_str() {
+                "labels" => crypto_key.labels = update.labels.clone(),
+                "rotation_period" => {
+                    crypto_key.rotation_schedule = update.rotation_schedule.clone()
+                }
+                "next_rotation_time" => {
+                    crypto_key.next_rotation_time = update.next_rotation_time.clone()
+                }
+                "version_template" => crypto_key.version_template = update.version_template.clone(),
+                path => return Err(Status::invalid_argument(format!("cannot update {}", path))),
+            }
+        }
+        Ok(Response::new(crypto_key.clone()))
+    }
+
+    async fn update_crypto_key_version(
+        &self,
+        request: Request<UpdateCryptoKeyVersionRequest>,
+    ) -> Reply<CryptoKeyVersion> {
+        let request = request.into_inner();
+        let update = request.crypto_key_version.unwrap_or_default();
+        let mut state = self.state();
+        let version = state.version_mut(&update.name)?;
+
+        for path in request.update_mask.unwrap_or_default().paths {
+            match (path.as_str(), update.state()) {
+                ("state", CryptoKeyVersionState::Enabled | CryptoKeyVersionState::Disabled) => {
+                    version.state = update.state
+                }
+                (path, _) => {
+                    return Err(Status::invalid_argument(format!("cannot update {}", path)))
+                }
+            }
+        }
+        let version = version.clone();
+        state.refresh_primary(&version);
+        Ok(Response::new(version))
+    }
+
+    async fn update_crypto_key_primary_version(
+        &self,
+        request: Request<UpdateCryptoKeyPrimaryVersionRequest>,
+    ) -> Reply<CryptoKey> {
+        let request = request.into_inner();
+        let version_name = format!(
+            "{}/cryptoKeyVersions/{}",
+            request.name, request.crypto_key_version_id
+        );
+        let mut state = self.state();
+        if state.crypto_key(&request.name)?.purpose() != CryptoKeyPurpose::EncryptDecrypt {
+            return Err(Status::failed_precondition(
+                "only symmetric keys have a primary",
+            ));
+        }
+        let version = state.version_mut(&version_name)?.clone();
+        if version.state() != CryptoKeyVersionState::Enabled {
+            return Err(Status::failed_precondition(format!(
+                "{} is not enabled",
+                version_name
+            )));
+        }
+        let crypto_key = state.crypto_keys.get_mut(&request.name).unwrap();
+        crypto_key.primary = Some(version);
+        Ok(Response::new(crypto_key.clone()))
+    }
+
+    async fn destroy_crypto_key_version(
+        &self,
+        request: Request<DestroyCryptoKeyVersionRequest>,
+    ) -> Reply<CryptoKeyVersion> {
+        let name = request.into_inner().name;
+        let mut state = self.state();
+        let version = state.version_mut(&name)?;
+        match version.state() {
+            CryptoKeyVersionState::Enabled | CryptoKeyVersionState::Disabled => {
+                version.state = CryptoKeyVersionState::DestroyScheduled as i32;
+                version.destroy_time =
+                    Some((SystemTime::now() + Duration::from_secs(24 * 60 * 60)).into());
+            }
+            state => {
+                return Err(Status::failed_precondition(format!(
+                    "{} is {:?}",
+                    name, state
+                )))
+            }
+        }
+        let version = version.clone();
+        state.refresh_primary(&version);
+        Ok(Response::new(version))
+    }
+
+    async fn restore_crypto_key_version(
+        &self,
+        request: Request<RestoreCryptoKeyVersionRequest>,
+    ) -> Reply<CryptoKeyVersion> {
+        let name = request.into_inner().name;
+        let mut state = self.state();
+        let version = state.version_mut(&name)?;
+        if version.state() != CryptoKeyVersionState::DestroyScheduled {
+            return Err(Status::failed_precondition(format!(
+                "{} is not scheduled for destruction",
+                name
+            )));
+        }
+        version.state = CryptoKeyVersionState::Disabled as i32;
+        version.destroy_time = None;
+        let version = version.clone();
+        state.refresh_primary(&version);
+        Ok(Response::new(version))
+    }
+
+    async fn encrypt(&self, request: Request<EncryptRequest>) -> Reply<EncryptResponse> {
+        self.record("Encrypt");
+        let request = request.into_inner();
+        let state = self.state();
+        let primary = state
+            .crypto_key(&request.name)?
+            .primary
+            .clone()
+            .ok_or_else(|| Status::failed_precondition("no primary version"))?;
+        if primary.state() != CryptoKeyVersionState::Enabled {
+            return Err(Status::failed_precondition(
+                "primary version is not enabled",
+            ));
+        }
+
+        let aad = &request.additional_authenticated_data;
+        if request.plaintext_crc32c.is_some()
+            && request.plaintext_crc32c != crc32c(&request.plaintext)
+            || request.additional_authenticated_data_crc32c.is_some()
+                && request.additional_authenticated_data_crc32c != crc32c(aad)
+        {
+            return Err(Status::invalid_argument("checksum mismatch"));
+        }
+
+        let mut ciphertext = primary.name.clone().into_bytes();
+        ciphertext.push(SEPARATOR);
+        ciphertext.extend(aad_tag(aad));
+        ciphertext.extend(request.plaintext);
+        Ok(Response::new(EncryptResponse {
+            name: primary.name,
+            ciphertext_crc32c: state.checksum(&ciphertext),
+            ciphertext,
+            verified_plaintext_crc32c: request.plaintext_crc32c.is_some(),
+            verified_additional_authenticated_data_crc32c: request
+                .additional_authenticated_data_crc32c
+                .is_some(),
+            protection_level: primary.protection_level,
+        }))
+    }
+
+    async fn decrypt(&self, request: Request<DecryptRequest>) -> Reply<DecryptResponse> {
+        self.record("Decrypt");
+        let request = request.into_inner();
+        let state = self.state();
+        let crypto_key = state.crypto_key(&request.name)?;
+        let invalid = || Status::invalid_argument("decryption failed");
+        if request.ciphertext_crc32c.is_some()
+            && request.ciphertext_crc32c != crc32c(&request.ciphertext)
+            || request.additional_authenticated_data_crc32c.is_some()
+                && request.additional_authenticated_data_crc32c
+                    != crc32c(&request.additional_authenticated_data)
+        {
+            return Err(Status::invalid_argument("checksum mismatch"));
+        }
+
+        let separator = request
+            .ciphertext
+            .iter()
+            .position(|&b| b == SEPARATOR)
+            .ok_or_else(invalid)?;
+        let version_name =
+            std::str::from_utf8(&request.ciphertext[..separator]).map_err(|_| invalid())?;
+        let version = state
+            .versions
+            .get(version_name)
+            .filter(|_| version_name.starts_with(&format!("{}/", crypto_key.name)))
+            .ok_or_else(invalid)?;
+        if version.state() != CryptoKeyVersionState::Enabled {
+            return Err(Status::failed_precondition(format!(
+                "{} is not enabled",
+                version_name
+            )));
+        }
+
+        let tagged = &request.ciphertext[separator + 1..];
+        let tag = aad_tag(&request.additional_authenticated_data);
+        let plaintext = tagged.strip_prefix(&tag[..]).ok_or_else(invalid)?.to_vec();
+        Ok(Response::new(DecryptResponse {
+            plaintext_crc32c: state.checksum(&plaintext),
+            plaintext,
+            used_primary: crypto_key.primary.as_ref().map(|primary| &primary.name)
+                == Some(&version.name),
+            protection_level: version.protection_level,
+        }))
+    }
+
+    async fn asymmetric_sign(
+        &self,
+        request: Request<AsymmetricSignRequest>,
+    ) -> Reply<AsymmetricSignResponse> {
+        use p256::ecdsa::signature::hazmat::PrehashSigner;
+
+        let request = request.into_inner();
+        let state = self.state();
+        let (version, private_key) = state.private_key(&request.name)?;
+        let scheme = Scheme::of(version.algorithm())
+            .map_err(|e| Status::failed_precondition(e.to_string()))?;
+
+        let (message, checksum) = match request.digest.and_then(|digest| digest.digest) {
+            Some(
+                digest::Digest::Sha256(digest)
+                | digest::Digest::Sha384(digest)
+                | digest::Digest::Sha512(digest),
+            ) => (digest, request.digest_crc32c),
+            None if scheme.hash().is_none() => (request.data, request.data_crc32c),
+            None => return Err(Status::invalid_argument("digest is required")),
+        };
+        if checksum.is_some() && checksum != crc32c(&message) {
+            return Err(Status::invalid_argument("checksum mismatch"));
+        }
+
+        let invalid = |e: &dyn std::fmt::Display| Status::invalid_argument(e.to_string());
+        let signature = match (private_key, scheme) {
+            (PrivateKey::Rsa(key), Scheme::RsaPss(hash)) => key
+                .sign_with_rng(&mut rand::thread_rng(), hash.pss(), &message)
+                .map_err(|e| invalid(&e))?,
+            (PrivateKey::Rsa(key), Scheme::RsaPkcs1(hash)) => key
+                .sign(hash.pkcs1v15(), &message)
+                .map_err(|e| invalid(&e))?,
+            (PrivateKey::Rsa(key), Scheme::RsaPkcs1Raw) => key
+                .sign(rsa::Pkcs1v15Sign::new_unprefixed(), &message)
+                .map_err(|e| invalid(&e))?,
+            (PrivateKey::P256(key), _) => {
+                let signature: p256::ecdsa::Signature =
+                    key.sign_prehash(&message).map_err(|e| invalid(&e))?;
+                signature.to_der().as_bytes().to_vec()
+            }
+            (PrivateKey::P384(key), _) => {
+                let signature: p384::ecdsa::Signature =
+                    key.sign_prehash(&message).map_err(|e| invalid(&e))?;
+                signature.to_der().as_bytes().to_vec()
+            }
+            _ => return Err(Status::failed_precondition("not a signing key")),
+        };
+
+        Ok(Response::new(AsymmetricSignResponse {
+            signature_crc32c: state.checksum(&signature),
+            signature,
+            verified_digest_crc32c: scheme.hash().is_some() && checksum.is_some(),
+            verified_data_crc32c: scheme.hash().is_none() && checksum.is_some(),
+            name: version.name.clone(),
+            protection_level: version.protection_level,
+        }))
+    }
+
+    async fn asymmetric_decrypt(
+        &self,
+        request: Request<AsymmetricDecryptRequest>,
+    ) -> Reply<AsymmetricDecryptResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        let (version, private_key) = state.private_key(&request.name)?;
+        if request.ciphertext_crc32c.is_some()
+            && request.ciphertext_crc32c != crc32c(&request.ciphertext)
+        {
+            return Err(Status::invalid_argument("checksum mismatch"));
+        }
+
+        let plaintext = match (private_key, Scheme::of(version.algorithm())) {
+            (PrivateKey::Rsa(key), Ok(Scheme::RsaOaep(hash))) => key
+                .decrypt(hash.oaep(), &request.ciphertext)
+                .map_err(|_| Status::invalid_argument("decryption failed"))?,
+            _ => return Err(Status::failed_precondition("not a decryption key")),
+        };
+
+        Ok(Response::new(AsymmetricDecryptResponse {
+            plaintext_crc32c: state.checksum(&plaintext),
+            plaintext,
+            verified_ciphertext_crc32c: request.ciphertext_crc32c.is_some(),
+            protection_level: version.protection_level,
+        }))
+    }
+
+    async fn mac_sign(&self, request: Request<MacSignRequest>) -> Reply<MacSignResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        let (version, mut mac) = state.mac(&request.name)?;
+        if request.data_crc32c.is_some() && request.data_crc32c != crc32c(&request.data) {
+            return Err(Status::invalid_argument("checksum mismatch"));
+        }
+
+        mac.update(&request.data);
+        let mac = mac.finalize().into_bytes().to_vec();
+        Ok(Response::new(MacSignResponse {
+            name: version.name.clone(),
+            mac_crc32c: state.checksum(&mac),
+            mac,
+            verified_data_crc32c: request.data_crc32c.is_some(),
+            protection_level: version.protection_level,
+        }))
+    }
+
+    async fn mac_verify(&self, request: Request<MacVerifyRequest>) -> Reply<MacVerifyResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        let (version, mut mac) = state.mac(&request.name)?;
+        if request.data_crc32c.is_some() && request.data_crc32c != crc32c(&request.data)
+            || request.mac_crc32c.is_some() && request.mac_crc32c != crc32c(&request.mac)
+        {
+            return Err(Status::invalid_argument("checksum mismatch"));
+        }
+
+        mac.update(&request.data);
+        Ok(Response::new(MacVerifyResponse {
+            name: version.name.clone(),
+            success: mac.verify_slice(&request.mac).is_ok(),
+            verified_data_crc32c: request.data_crc32c.is_some(),
+            verified_mac_crc32c: request.mac_crc32c.is_some(),
+            verified_success_integrity: true,
+            protection_level: version.protection_level,
+        }))
+    }
+
+    async fn generate_random_bytes(
+        &self,
+        request: Request<GenerateRandomBytesRequest>,
+    ) -> Reply<GenerateRandomBytesResponse> {
+        let request = request.into_inner();
+        if request.protection_level() != ProtectionLevel::Hsm {
+            return Err(Status::invalid_argument("only HSM is supported"));
+        }
+        if !(1..=1024).contains(&request.length_bytes) {
+            return Err(Status::invalid_argument("length_bytes out of range"));
+        }
+
+        let mut data = vec![0; request.length_bytes as usize];
+        rand::thread_rng().fill(data.as_mut_slice());
+        Ok(Response::new(GenerateRandomBytesResponse {
+            data_crc32c: self.state().checksum(&data),
+            data,
+        }))
+    }
+}
@@ -0,0 +1,100 @@
+//! Wrapping of local key material for [`KmsClient::import_key`](super::KmsClient::import_key).
+
+use aes_kw::KekAes256;
+use p256::pkcs8::DecodePublicKey;
+use rand::Rng;
+use rsa::{traits::PublicKeyParts, RsaPublicKey};
+
+pub use crate::proto::google::cloud::kms::v1::import_job::{ImportJobState, ImportMethod};
+use crate::{
+    error::Error,
+    kms::{client::KmsError, public_key::Hash},
+};
+
+impl ImportMethod {
+    /// The size of the job's RSA wrapping key in bits.
+    pub fn rsa_bits(self) -> Option<usize> {
+        match self {
+            Self::RsaOaep3072Sha1Aes256 => Some(3072),
+            Self::RsaOaep4096Sha1Aes256 => Some(4096),
+            Self::Unspecified => None,
+        }
+    }
+}
+
+/// Wraps key material the way PKCS#11 `CKM_RSA_AES_KEY_WRAP` does, as expected by
+/// `ImportCryptoKeyVersion`: a random AES-256 key encrypted with RSA-OAEP (SHA-1,
+/// empty label) under the job's public key, followed by the key material wrapped
+/// with that AES key using AES-KWP (RFC 5649).
+///
+/// # Arguments
+/// * `public_key_pem` - the `public_key` of an active import job, sized for its method.
+/// * `import_method`  - the `import_method` of the same job.
+/// * `key_material`   - raw bytes for symmetric keys, PKCS#8 DER for asymmetric ones.
+pub fn wrap_key_material(
+    public_key_pem: &str,
+    import_method: ImportMethod,
+    key_material: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let bits = import_method
+        .rsa_bits()
+        .ok_or_else(|| KmsError::InvalidImportJob("import method is unspecified".to_owned()))?;
+    let public_key = RsaPublicKey::from_public_key_pem(public_key_pem)
+        .map_err(|e| KmsError::InvalidPublicKey(e.to_string()))?;
+    if public_key.n().bits() != bits {
+        return Err(KmsError::InvalidPublicKey(format!(
+            "{} bit key for {:?}",
+            public_key.n().bits(),
+            import_method
+        ))
+        .into());
+    }
+
+    let aes_key: [u8; 32] = rand::thread_rng().gen();
+    let mut wrapped = public_key
+        .encrypt(&mut rand::thread_rng(), Hash::Sha1.oaep(), &aes_key)
+        .map_err(|e| KmsError::Rsa(e.to_string()))?;
+    debug_assert_eq!(wrapped.len(), public_key.size());
+    wrapped.extend(
+        KekAes256::from(aes_key)
+            .wrap_with_padding_vec(key_material)
+            .map_err(|e| KmsError::InvalidImportJob(format!("AES-KWP failed: {}", e)))?,
+    );
+    Ok(wrapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::pkcs8::{DecodePrivateKey, EncodePublicKey, LineEnding};
+    use pretty_assertions::assert_eq;
+    use rsa::RsaPrivateKey;
+
+    use super::*;
+
+    #[test]
+    fn test_wrap_key_material() -> anyhow::Result<()> {
+        let private_key = RsaPrivateKey::from_pkcs8_pem(include_str!("testdata/rsa_3072.pem"))?;
+        let pem = RsaPublicKey::from(&private_key).to_public_key_pem(LineEnding::LF)?;
+
+        let wrapped = wrap_key_material(&pem, ImportMethod::RsaOaep3072Sha1Aes256, b"secret")?;
+        let (aes_key, kwp) = wrapped.split_at(private_key.size());
+        let aes_key: [u8; 32] = private_key
+            .decrypt(Hash::Sha1.oaep(), aes_key)?
+            .as_slice()
+            .try_into()?;
+        let key_material = KekAes256::from(aes_key)
+            .unwrap_with_padding_vec(kwp)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        assert_eq!(key_material, b"secret");
+
+        assert!(matches!(
+            wrap_key_material(&pem, ImportMethod::Unspecified, b"secret"),
+            Err(Error::Kms(KmsError::InvalidImportJob(_)))
+        ));
+        assert!(matches!(
+            wrap_key_material(&pem, ImportMethod::RsaOaep4096Sha1Aes256, b"secret"),
+            Err(Error::Kms(KmsError::InvalidPublicKey(_)))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+//! CRC32C checksums protecting KMS requests and responses against corruption in transit.
+//!
+//! cf. https://cloud.google.com/kms/docs/data-integrity-guidelines
+
+use crate::error::Error;
+
+/// The checksum of `data`, in the form of the `*_crc32c` request fields.
+pub(crate) fn crc32c(data: &[u8]) -> Option<i64> {
+    Some(crc32c::crc32c(data) as i64)
+}
+
+/// Fails unless `checksum`, returned by the server, matches `data`.
+pub(crate) fn verify_crc32c(field: &str, data: &[u8], checksum: Option<i64>) -> Result<(), Error> {
+    match checksum {
+        Some(checksum) if checksum == crc32c::crc32c(data) as i64 => Ok(()),
+        Some(_) => Err(Error::Integrity(format!(
+            "{} does not match its checksum",
+            field
+        ))),
+        None => Err(Error::Integrity(format!(
+            "checksum of {} is missing",
+            field
+        ))),
+    }
+}
+
+/// Fails unless the server confirmed that it verified the checksum of `field`.
+pub(crate) fn ensure_verified(field: &str, verified: bool) -> Result<(), Error> {
+    if verified {
+        Ok(())
+    } else {
+        Err(Error::Integrity(format!(
+            "checksum of {} was not verified by the server",
+            field
+        )))
+    }
+}
+
+/// Fails unless the server used the key that was requested.
+pub(crate) fn ensure_name(requested: &str, used: &str) -> Result<(), Error> {
+    if used == requested {
+        Ok(())
+    } else {
+        Err(Error::Integrity(format!(
+            "requested {} but the response is for {}",
+            requested, used
+        )))
+    }
+}
+
+/// Fails unless the server used `requested` or one of its versions.
+pub(crate) fn ensure_version_of(requested: &str, used: &str) -> Result<(), Error> {
+    let is_version = used
+        .strip_prefix(requested)
+        .is_some_and(|suffix| suffix.starts_with("/cryptoKeyVersions/"));
+    if used == requested || is_version {
+        Ok(())
+    } else {
+        ensure_name(requested, used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_crc32c() {
+        // cf. RFC 3720, B.4
+        assert_eq!(crc32c(&[0; 32]), Some(0x8a9136aa));
+
+        assert!(verify_crc32c("data", b"data", crc32c(b"data")).is_ok());
+        assert!(matches!(
+            verify_crc32c("data", b"data", crc32c(b"date")),
+            Err(Error::Integrity(_))
+        ));
+        assert!(matches!(
+            verify_crc32c("data", b"data", None),
+            Err(Error::Integrity(_))
+        ));
+    }
+}
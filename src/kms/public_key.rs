@@ -0,0 +1,257 @@
+use p256::pkcs8::DecodePublicKey;
+use rsa::{Oaep, Pkcs1v15Sign, Pss, RsaPublicKey};
+use sha2::Digest as _;
+
+use crate::{
+    error::Error,
+    kms::{
+        client::KmsError,
+        crypto_key::{CryptoKeyVersionAlgorithm, ProtectionLevel},
+        integrity,
+    },
+    proto::google::cloud::kms::v1::{self, digest::Digest},
+};
+
+/// The hash function of a key version's algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hash {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Hash {
+    pub(crate) fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => sha1::Sha1::digest(data).to_vec(),
+            Self::Sha256 => sha2::Sha256::digest(data).to_vec(),
+            Self::Sha384 => sha2::Sha384::digest(data).to_vec(),
+            Self::Sha512 => sha2::Sha512::digest(data).to_vec(),
+        }
+    }
+
+    /// `digest` in the form of `AsymmetricSignRequest::digest`. KMS never signs SHA-1 digests.
+    pub(crate) fn to_proto(self, digest: Vec<u8>) -> Option<v1::Digest> {
+        let digest = match self {
+            Self::Sha1 => return None,
+            Self::Sha256 => Digest::Sha256(digest),
+            Self::Sha384 => Digest::Sha384(digest),
+            Self::Sha512 => Digest::Sha512(digest),
+        };
+        Some(v1::Digest {
+            digest: Some(digest),
+        })
+    }
+
+    pub(crate) fn pss(self) -> Pss {
+        match self {
+            Self::Sha1 => Pss::new::<sha1::Sha1>(),
+            Self::Sha256 => Pss::new::<sha2::Sha256>(),
+            Self::Sha384 => Pss::new::<sha2::Sha384>(),
+            Self::Sha512 => Pss::new::<sha2::Sha512>(),
+        }
+    }
+
+    pub(crate) fn pkcs1v15(self) -> Pkcs1v15Sign {
+        match self {
+            Self::Sha1 => Pkcs1v15Sign::new::<sha1::Sha1>(),
+            Self::Sha256 => Pkcs1v15Sign::new::<sha2::Sha256>(),
+            Self::Sha384 => Pkcs1v15Sign::new::<sha2::Sha384>(),
+            Self::Sha512 => Pkcs1v15Sign::new::<sha2::Sha512>(),
+        }
+    }
+
+    pub(crate) fn oaep(self) -> Oaep {
+        match self {
+            Self::Sha1 => Oaep::new::<sha1::Sha1>(),
+            Self::Sha256 => Oaep::new::<sha2::Sha256>(),
+            Self::Sha384 => Oaep::new::<sha2::Sha384>(),
+            Self::Sha512 => Oaep::new::<sha2::Sha512>(),
+        }
+    }
+}
+
+/// How a key version's algorithm signs or encrypts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Scheme {
+    RsaPss(Hash),
+    RsaPkcs1(Hash),
+    /// PKCS#1 v1.5 over caller-provided data, without hashing.
+    RsaPkcs1Raw,
+    EcdsaP256,
+    EcdsaP384,
+    RsaOaep(Hash),
+}
+
+impl Scheme {
+    pub(crate) fn of(algorithm: CryptoKeyVersionAlgorithm) -> Result<Self, KmsError> {
+        use CryptoKeyVersionAlgorithm::*;
+
+        Ok(match algorithm {
+            RsaSignPss2048Sha256 | RsaSignPss3072Sha256 | RsaSignPss4096Sha256 => {
+                Self::RsaPss(Hash::Sha256)
+            }
+            RsaSignPss4096Sha512 => Self::RsaPss(Hash::Sha512),
+            RsaSignPkcs12048Sha256 | RsaSignPkcs13072Sha256 | RsaSignPkcs14096Sha256 => {
+                Self::RsaPkcs1(Hash::Sha256)
+            }
+            RsaSignPkcs14096Sha512 => Self::RsaPkcs1(Hash::Sha512),
+            RsaSignRawPkcs12048 | RsaSignRawPkcs13072 | RsaSignRawPkcs14096 => Self::RsaPkcs1Raw,
+            EcSignP256Sha256 => Self::EcdsaP256,
+            EcSignP384Sha384 => Self::EcdsaP384,
+            RsaDecryptOaep2048Sha256 | RsaDecryptOaep3072Sha256 | RsaDecryptOaep4096Sha256 => {
+                Self::RsaOaep(Hash::Sha256)
+            }
+            RsaDecryptOaep4096Sha512 => Self::RsaOaep(Hash::Sha512),
+            RsaDecryptOaep2048Sha1 | RsaDecryptOaep3072Sha1 | RsaDecryptOaep4096Sha1 => {
+                Self::RsaOaep(Hash::Sha1)
+            }
+            algorithm => return Err(KmsError::UnsupportedAlgorithm(algorithm)),
+        })
+    }
+
+    /// The hash applied to the data before signing, `None` for raw signatures.
+    pub(crate) fn hash(self) -> Option<Hash> {
+        match self {
+            Self::RsaPss(hash) | Self::RsaPkcs1(hash) | Self::RsaOaep(hash) => Some(hash),
+            Self::EcdsaP256 => Some(Hash::Sha256),
+            Self::EcdsaP384 => Some(Hash::Sha384),
+            Self::RsaPkcs1Raw => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Key {
+    Rsa(RsaPublicKey),
+    P256(p256::ecdsa::VerifyingKey),
+    P384(p384::ecdsa::VerifyingKey),
+}
+
+/// The public half of an asymmetric key version, as returned by
+/// [`KmsClient::get_public_key`](super::KmsClient::get_public_key).
+///
+/// Signatures can be verified and data encrypted locally, without calling KMS.
+#[derive(Debug, Clone)]
+pub struct PublicKey {
+    name: String,
+    algorithm: CryptoKeyVersionAlgorithm,
+    protection_level: ProtectionLevel,
+    pem: String,
+    scheme: Scheme,
+    key: Key,
+}
+
+impl PublicKey {
+    /// # Arguments
+    /// * `pem`       - the SubjectPublicKeyInfo, PEM encoded.
+    /// * `algorithm` - the algorithm of the key version.
+    pub fn from_pem(pem: &str, algorithm: CryptoKeyVersionAlgorithm) -> Result<Self, Error> {
+        let scheme = Scheme::of(algorithm)?;
+        let invalid = |e: &dyn std::fmt::Display| KmsError::InvalidPublicKey(e.to_string());
+        let key = match scheme {
+            Scheme::EcdsaP256 => Key::P256(
+                p256::ecdsa::VerifyingKey::from_public_key_pem(pem).map_err(|e| invalid(&e))?,
+            ),
+            Scheme::EcdsaP384 => Key::P384(
+                p384::ecdsa::VerifyingKey::from_public_key_pem(pem).map_err(|e| invalid(&e))?,
+            ),
+            _ => Key::Rsa(RsaPublicKey::from_public_key_pem(pem).map_err(|e| invalid(&e))?),
+        };
+
+        Ok(Self {
+            name: String::new(),
+            algorithm,
+            protection_level: ProtectionLevel::Unspecified,
+            pem: pem.to_owned(),
+            scheme,
+            key,
+        })
+    }
+
+    /// The name of the key version, empty if created with [`PublicKey::from_pem`].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn algorithm(&self) -> CryptoKeyVersionAlgorithm {
+        self.algorithm
+    }
+
+    pub fn protection_level(&self) -> ProtectionLevel {
+        self.protection_level
+    }
+
+    pub fn pem(&self) -> &str {
+        &self.pem
+    }
+
+    /// Verifies a signature made by [`KmsClient::asymmetric_sign`](super::KmsClient::asymmetric_sign).
+    ///
+    /// # Arguments
+    /// * `data`      - the signed data. For raw PKCS#1 keys, the data that was signed as is.
+    /// * `signature` - the signature. DER encoded for ECDSA keys.
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> Result<(), Error> {
+        let digest = match self.scheme.hash() {
+            Some(hash) => hash.digest(data),
+            None => data.to_vec(),
+        };
+        self.verify_digest(&digest, signature)
+    }
+
+    /// Like [`PublicKey::verify`], with `digest` already hashed with the key's hash function.
+    pub fn verify_digest(&self, digest: &[u8], signature: &[u8]) -> Result<(), Error> {
+        use p256::ecdsa::signature::hazmat::PrehashVerifier;
+
+        let valid = match (&self.key, self.scheme) {
+            (Key::Rsa(key), Scheme::RsaPss(hash)) => {
+                key.verify(hash.pss(), digest, signature).is_ok()
+            }
+            (Key::Rsa(key), Scheme::RsaPkcs1(hash)) => {
+                key.verify(hash.pkcs1v15(), digest, signature).is_ok()
+            }
+            (Key::Rsa(key), Scheme::RsaPkcs1Raw) => key
+                .verify(Pkcs1v15Sign::new_unprefixed(), digest, signature)
+                .is_ok(),
+            (Key::P256(key), _) => p256::ecdsa::Signature::from_der(signature)
+                .and_then(|signature| key.verify_prehash(digest, &signature))
+                .is_ok(),
+            (Key::P384(key), _) => p384::ecdsa::Signature::from_der(signature)
+                .and_then(|signature| key.verify_prehash(digest, &signature))
+                .is_ok(),
+            _ => return Err(KmsError::UnsupportedAlgorithm(self.algorithm).into()),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(KmsError::InvalidSignature.into())
+        }
+    }
+
+    /// Encrypts `plaintext` with RSA-OAEP, to be decrypted by
+    /// [`KmsClient::asymmetric_decrypt`](super::KmsClient::asymmetric_decrypt).
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        match (&self.key, self.scheme) {
+            (Key::Rsa(key), Scheme::RsaOaep(hash)) => key
+                .encrypt(&mut rand::thread_rng(), hash.oaep(), plaintext)
+                .map_err(|e| KmsError::Rsa(e.to_string()).into()),
+            _ => Err(KmsError::UnsupportedAlgorithm(self.algorithm).into()),
+        }
+    }
+}
+
+impl TryFrom<v1::PublicKey> for PublicKey {
+    type Error = Error;
+
+    /// Checks the PEM against its checksum before parsing it.
+    fn try_from(public_key: v1::PublicKey) -> Result<Self, Error> {
+        integrity::verify_crc32c("pem", public_key.pem.as_bytes(), public_key.pem_crc32c)?;
+        Ok(Self {
+            name: public_key.name.clone(),
+            protection_level: public_key.protection_level(),
+            ..Self::from_pem(&public_key.pem, public_key.algorithm())?
+        })
+    }
+}
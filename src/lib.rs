@@ -1,8 +1,12 @@
 pub mod drive;
+pub mod endpoint;
 pub mod error;
 pub mod kms;
+pub mod options;
+pub mod pagination;
 pub mod proto;
 pub mod pubsub;
+pub mod retry;
 pub mod storage;
 
 pub mod auth;
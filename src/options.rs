@@ -0,0 +1,147 @@
+use std::{future::Future, time::Duration};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tonic::metadata::{MetadataKey, MetadataMap};
+
+use crate::{error::Error, retry::RetryPolicy};
+
+const HEADER_USER_PROJECT: &str = "x-goog-user-project";
+const HEADER_API_CLIENT: &str = "x-goog-api-client";
+const HEADER_REQUEST_ID: &str = "x-request-id";
+
+/// Options for a single call, accepted by the `*_with_options` client methods.
+///
+/// Applied the same way to gRPC metadata and HTTP headers.
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    timeout: Option<Duration>,
+    retry_policy: Option<RetryPolicy>,
+    headers: Vec<(String, String)>,
+}
+
+impl CallOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deadline for each attempt. gRPC calls also send it to the server as `grpc-timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the client's retry policy for this call.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Project billed for quota, sent as `x-goog-user-project`.
+    pub fn with_quota_project(self, project: impl Into<String>) -> Self {
+        self.with_header(HEADER_USER_PROJECT, project)
+    }
+
+    /// Sent as `x-goog-api-client`, e.g. `gl-rust/1.56.0 my-service/1.2.0`.
+    pub fn with_api_client(self, api_client: impl Into<String>) -> Self {
+        self.with_header(HEADER_API_CLIENT, api_client)
+    }
+
+    /// Sent as `x-request-id` to correlate the call in logs and proxies.
+    pub fn with_request_id(self, request_id: impl Into<String>) -> Self {
+        self.with_header(HEADER_REQUEST_ID, request_id)
+    }
+
+    /// Adds an arbitrary header. Header names are case-insensitive and a later value
+    /// replaces an earlier one with the same name.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into().to_ascii_lowercase();
+        self.headers.retain(|(key, _)| *key != name);
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub(crate) fn apply_to_metadata(&self, metadata: &mut MetadataMap) -> Result<(), Error> {
+        for (name, value) in &self.headers {
+            metadata.insert(MetadataKey::from_bytes(name.as_bytes())?, value.parse()?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn apply_to_headers(&self, headers: &mut HeaderMap) -> Result<(), Error> {
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Runs one attempt, failing with [`Error::Timeout`] if it exceeds the timeout.
+    pub(crate) async fn attempt<T>(
+        &self,
+        f: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, f)
+                .await
+                .map_err(|_| Error::Timeout(timeout))?,
+            None => f.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_to_headers() -> anyhow::Result<()> {
+        let options = CallOptions::new()
+            .with_quota_project("billing-project")
+            .with_header("X-Custom", "a")
+            .with_header("x-custom", "b");
+
+        let mut headers = HeaderMap::new();
+        options.apply_to_headers(&mut headers)?;
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-goog-user-project"], "billing-project");
+        assert_eq!(headers["x-custom"], "b");
+
+        let mut metadata = MetadataMap::new();
+        options.apply_to_metadata(&mut metadata)?;
+        assert_eq!(
+            metadata.get("x-goog-user-project"),
+            Some(&"billing-project".parse().unwrap())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_attempt_timeout() {
+        let options = CallOptions::new().with_timeout(Duration::from_millis(1));
+        let result = options
+            .attempt(async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    stream::{self, BoxStream},
+    Stream, StreamExt, TryStreamExt,
+};
+
+use crate::error::Error;
+
+/// Options for the `stream_*` methods that page through List RPCs.
+///
+/// `filter` and `order_by` are only sent to APIs that support them: Cloud KMS, and
+/// Drive as `q` and `orderBy`. Other APIs ignore them.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    page_size: Option<u32>,
+    filter: Option<String>,
+    order_by: Option<String>,
+}
+
+impl ListOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of items per page. Defaults to the API's own default.
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    pub fn with_order_by(mut self, order_by: impl Into<String>) -> Self {
+        self.order_by = Some(order_by.into());
+        self
+    }
+
+    pub fn page_size(&self) -> Option<u32> {
+        self.page_size
+    }
+
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    pub fn order_by(&self) -> Option<&str> {
+        self.order_by.as_deref()
+    }
+
+    /// `page_size` as gRPC List requests expect it, where `0` means the server default.
+    pub(crate) fn grpc_page_size(&self) -> i32 {
+        self.page_size
+            .map_or(0, |page_size| page_size.min(i32::MAX as u32) as i32)
+    }
+}
+
+/// One page of a List response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// `None` on the last page.
+    pub next_page_token: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// An empty token, as sent by gRPC APIs on the last page, is treated as `None`.
+    pub fn new(items: Vec<T>, next_page_token: impl Into<Option<String>>) -> Self {
+        Self {
+            items,
+            next_page_token: next_page_token.into().filter(|token| !token.is_empty()),
+        }
+    }
+}
+
+/// A stream of pages, fetched lazily as the stream is polled.
+///
+/// Use [`PageStream::items`] to iterate over the items of all pages instead.
+pub struct PageStream<'a, T> {
+    pages: BoxStream<'a, Result<Page<T>, Error>>,
+}
+
+impl<'a, T: Send + 'a> PageStream<'a, T> {
+    /// Pages through a List RPC, calling `fetch` with the token of the page to
+    /// fetch (`None` for the first page) until a page has no next page token.
+    pub(crate) fn new<F, Fut>(fetch: F) -> Self
+    where
+        F: FnMut(Option<String>) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Page<T>, Error>> + Send + 'a,
+    {
+        let pages = stream::try_unfold((Some(None), fetch), |(page_token, mut fetch)| async move {
+            let page_token = match page_token {
+                Some(page_token) => page_token,
+                None => return Ok(None),
+            };
+            let page = fetch(page_token).await?;
+            let next = page.next_page_token.clone().map(Some);
+            Ok(Some((page, (next, fetch))))
+        });
+        Self {
+            pages: pages.boxed(),
+        }
+    }
+
+    /// Maps the items of each page with `f`, dropping those it returns `None` for.
+    pub(crate) fn filter_map_items<U: Send + 'a>(
+        self,
+        f: impl FnMut(T) -> Option<U> + Clone + Send + 'a,
+    ) -> PageStream<'a, U> {
+        PageStream {
+            pages: self
+                .pages
+                .map_ok(move |page| Page {
+                    items: page.items.into_iter().filter_map(f.clone()).collect(),
+                    next_page_token: page.next_page_token,
+                })
+                .boxed(),
+        }
+    }
+
+    /// Flattens the pages into a stream of their items.
+    pub fn items(self) -> BoxStream<'a, Result<T, Error>> {
+        self.pages
+            .map_ok(|page| stream::iter(page.items.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+}
+
+impl<'a, T> Stream for PageStream<'a, T> {
+    type Item = Result<Page<T>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.pages.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn numbers(calls: Arc<AtomicUsize>) -> PageStream<'static, u32> {
+        PageStream::new(move |page_token: Option<String>| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                Ok(match page_token.as_deref() {
+                    None => Page::new(vec![1, 2], "2".to_owned()),
+                    Some("2") => Page::new(vec![3, 4], "4".to_owned()),
+                    Some(_) => Page::new(vec![5], String::new()),
+                })
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_items() -> anyhow::Result<()> {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let items: Vec<_> = numbers(calls.clone()).items().try_collect().await?;
+
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_filter_map_items() -> anyhow::Result<()> {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let pages = numbers(calls)
+            .filter_map_items(|n| (n % 2 == 1).then(|| n * 10))
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        assert_eq!(
+            pages,
+            vec![
+                Page::new(vec![10], "2".to_owned()),
+                Page::new(vec![30], "4".to_owned()),
+                Page::new(vec![50], None),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pages_are_fetched_lazily() -> anyhow::Result<()> {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut pages = numbers(calls.clone());
+
+        let page = pages.try_next().await?.unwrap();
+        assert_eq!(page, Page::new(vec![1, 2], "2".to_owned()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stops_on_error() {
+        let mut pages: PageStream<'_, u32> =
+            PageStream::new(|_| async { Err(tonic::Status::permission_denied("denied").into()) });
+
+        assert!(pages.next().await.unwrap().is_err());
+        assert!(pages.next().await.is_none());
+    }
+}
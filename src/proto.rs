@@ -1,9 +1,13 @@
 pub(crate) const TLS_CERT: &[u8] = include_bytes!("google.pem");
 
+#[allow(clippy::all)]
 pub mod google {
     pub mod api {
         include!("proto/google.api.rs");
     }
+    pub mod rpc {
+        include!("proto/google.rpc.rs");
+    }
     pub mod r#type {
         include!("proto/google.r#type.rs");
     }
@@ -2552,3 +2552,1213 @@ pub mod key_management_service_client {
         }
     }
 }
+#[doc = r" Generated server implementations."]
+#[cfg(test)]
+pub mod key_management_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with KeyManagementServiceServer."]
+    #[async_trait]
+    pub trait KeyManagementService: Send + Sync + 'static {
+        #[doc = " Lists [KeyRings][google.cloud.kms.v1.KeyRing]."]
+        async fn list_key_rings(
+            &self,
+            request: tonic::Request<super::ListKeyRingsRequest>,
+        ) -> Result<tonic::Response<super::ListKeyRingsResponse>, tonic::Status>;
+        #[doc = " Lists [CryptoKeys][google.cloud.kms.v1.CryptoKey]."]
+        async fn list_crypto_keys(
+            &self,
+            request: tonic::Request<super::ListCryptoKeysRequest>,
+        ) -> Result<tonic::Response<super::ListCryptoKeysResponse>, tonic::Status>;
+        #[doc = " Lists [CryptoKeyVersions][google.cloud.kms.v1.CryptoKeyVersion]."]
+        async fn list_crypto_key_versions(
+            &self,
+            request: tonic::Request<super::ListCryptoKeyVersionsRequest>,
+        ) -> Result<tonic::Response<super::ListCryptoKeyVersionsResponse>, tonic::Status>;
+        #[doc = " Lists [ImportJobs][google.cloud.kms.v1.ImportJob]."]
+        async fn list_import_jobs(
+            &self,
+            request: tonic::Request<super::ListImportJobsRequest>,
+        ) -> Result<tonic::Response<super::ListImportJobsResponse>, tonic::Status>;
+        #[doc = " Returns metadata for a given [KeyRing][google.cloud.kms.v1.KeyRing]."]
+        async fn get_key_ring(
+            &self,
+            request: tonic::Request<super::GetKeyRingRequest>,
+        ) -> Result<tonic::Response<super::KeyRing>, tonic::Status>;
+        #[doc = " Returns metadata for a given [CryptoKey][google.cloud.kms.v1.CryptoKey], as"]
+        #[doc = " well as its [primary][google.cloud.kms.v1.CryptoKey.primary]"]
+        #[doc = " [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]."]
+        async fn get_crypto_key(
+            &self,
+            request: tonic::Request<super::GetCryptoKeyRequest>,
+        ) -> Result<tonic::Response<super::CryptoKey>, tonic::Status>;
+        #[doc = " Returns metadata for a given"]
+        #[doc = " [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]."]
+        async fn get_crypto_key_version(
+            &self,
+            request: tonic::Request<super::GetCryptoKeyVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKeyVersion>, tonic::Status>;
+        #[doc = " Returns the public key for the given"]
+        #[doc = " [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]. The"]
+        #[doc = " [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose] must be"]
+        #[doc = " [ASYMMETRIC_SIGN][google.cloud.kms.v1.CryptoKey.CryptoKeyPurpose.ASYMMETRIC_SIGN]"]
+        #[doc = " or"]
+        #[doc = " [ASYMMETRIC_DECRYPT][google.cloud.kms.v1.CryptoKey.CryptoKeyPurpose.ASYMMETRIC_DECRYPT]."]
+        async fn get_public_key(
+            &self,
+            request: tonic::Request<super::GetPublicKeyRequest>,
+        ) -> Result<tonic::Response<super::PublicKey>, tonic::Status>;
+        #[doc = " Returns metadata for a given [ImportJob][google.cloud.kms.v1.ImportJob]."]
+        async fn get_import_job(
+            &self,
+            request: tonic::Request<super::GetImportJobRequest>,
+        ) -> Result<tonic::Response<super::ImportJob>, tonic::Status>;
+        #[doc = " Create a new [KeyRing][google.cloud.kms.v1.KeyRing] in a given Project and"]
+        #[doc = " Location."]
+        async fn create_key_ring(
+            &self,
+            request: tonic::Request<super::CreateKeyRingRequest>,
+        ) -> Result<tonic::Response<super::KeyRing>, tonic::Status>;
+        #[doc = " Create a new [CryptoKey][google.cloud.kms.v1.CryptoKey] within a"]
+        #[doc = " [KeyRing][google.cloud.kms.v1.KeyRing]."]
+        #[doc = ""]
+        #[doc = " [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose] and"]
+        #[doc = " [CryptoKey.version_template.algorithm][google.cloud.kms.v1.CryptoKeyVersionTemplate.algorithm]"]
+        #[doc = " are required."]
+        async fn create_crypto_key(
+            &self,
+            request: tonic::Request<super::CreateCryptoKeyRequest>,
+        ) -> Result<tonic::Response<super::CryptoKey>, tonic::Status>;
+        #[doc = " Create a new [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion] in a"]
+        #[doc = " [CryptoKey][google.cloud.kms.v1.CryptoKey]."]
+        #[doc = ""]
+        #[doc = " The server will assign the next sequential id. If unset,"]
+        #[doc = " [state][google.cloud.kms.v1.CryptoKeyVersion.state] will be set to"]
+        #[doc = " [ENABLED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.ENABLED]."]
+        async fn create_crypto_key_version(
+            &self,
+            request: tonic::Request<super::CreateCryptoKeyVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKeyVersion>, tonic::Status>;
+        #[doc = " Import wrapped key material into a"]
+        #[doc = " [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]."]
+        #[doc = ""]
+        #[doc = " All requests must specify a [CryptoKey][google.cloud.kms.v1.CryptoKey]. If"]
+        #[doc = " a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion] is additionally"]
+        #[doc = " specified in the request, key material will be reimported into that"]
+        #[doc = " version. Otherwise, a new version will be created, and will be assigned the"]
+        #[doc = " next sequential id within the [CryptoKey][google.cloud.kms.v1.CryptoKey]."]
+        async fn import_crypto_key_version(
+            &self,
+            request: tonic::Request<super::ImportCryptoKeyVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKeyVersion>, tonic::Status>;
+        #[doc = " Create a new [ImportJob][google.cloud.kms.v1.ImportJob] within a"]
+        #[doc = " [KeyRing][google.cloud.kms.v1.KeyRing]."]
+        #[doc = ""]
+        #[doc = " [ImportJob.import_method][google.cloud.kms.v1.ImportJob.import_method] is"]
+        #[doc = " required."]
+        async fn create_import_job(
+            &self,
+            request: tonic::Request<super::CreateImportJobRequest>,
+        ) -> Result<tonic::Response<super::ImportJob>, tonic::Status>;
+        #[doc = " Update a [CryptoKey][google.cloud.kms.v1.CryptoKey]."]
+        async fn update_crypto_key(
+            &self,
+            request: tonic::Request<super::UpdateCryptoKeyRequest>,
+        ) -> Result<tonic::Response<super::CryptoKey>, tonic::Status>;
+        #[doc = " Update a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]'s"]
+        #[doc = " metadata."]
+        #[doc = ""]
+        #[doc = " [state][google.cloud.kms.v1.CryptoKeyVersion.state] may be changed between"]
+        #[doc = " [ENABLED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.ENABLED]"]
+        #[doc = " and"]
+        #[doc = " [DISABLED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.DISABLED]"]
+        #[doc = " using this method. See"]
+        #[doc = " [DestroyCryptoKeyVersion][google.cloud.kms.v1.KeyManagementService.DestroyCryptoKeyVersion]"]
+        #[doc = " and"]
+        #[doc = " [RestoreCryptoKeyVersion][google.cloud.kms.v1.KeyManagementService.RestoreCryptoKeyVersion]"]
+        #[doc = " to move between other states."]
+        async fn update_crypto_key_version(
+            &self,
+            request: tonic::Request<super::UpdateCryptoKeyVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKeyVersion>, tonic::Status>;
+        #[doc = " Update the version of a [CryptoKey][google.cloud.kms.v1.CryptoKey] that"]
+        #[doc = " will be used in"]
+        #[doc = " [Encrypt][google.cloud.kms.v1.KeyManagementService.Encrypt]."]
+        #[doc = ""]
+        #[doc = " Returns an error if called on a key whose purpose is not"]
+        #[doc = " [ENCRYPT_DECRYPT][google.cloud.kms.v1.CryptoKey.CryptoKeyPurpose.ENCRYPT_DECRYPT]."]
+        async fn update_crypto_key_primary_version(
+            &self,
+            request: tonic::Request<super::UpdateCryptoKeyPrimaryVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKey>, tonic::Status>;
+        #[doc = " Schedule a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion] for"]
+        #[doc = " destruction."]
+        #[doc = ""]
+        #[doc = " Upon calling this method,"]
+        #[doc = " [CryptoKeyVersion.state][google.cloud.kms.v1.CryptoKeyVersion.state] will"]
+        #[doc = " be set to"]
+        #[doc = " [DESTROY_SCHEDULED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.DESTROY_SCHEDULED],"]
+        #[doc = " and [destroy_time][google.cloud.kms.v1.CryptoKeyVersion.destroy_time] will"]
+        #[doc = " be set to the time"]
+        #[doc = " [destroy_scheduled_duration][google.cloud.kms.v1.CryptoKey.destroy_scheduled_duration]"]
+        #[doc = " in the future. At that time, the"]
+        #[doc = " [state][google.cloud.kms.v1.CryptoKeyVersion.state] will automatically"]
+        #[doc = " change to"]
+        #[doc = " [DESTROYED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.DESTROYED],"]
+        #[doc = " and the key material will be irrevocably destroyed."]
+        #[doc = ""]
+        #[doc = " Before the"]
+        #[doc = " [destroy_time][google.cloud.kms.v1.CryptoKeyVersion.destroy_time] is"]
+        #[doc = " reached,"]
+        #[doc = " [RestoreCryptoKeyVersion][google.cloud.kms.v1.KeyManagementService.RestoreCryptoKeyVersion]"]
+        #[doc = " may be called to reverse the process."]
+        async fn destroy_crypto_key_version(
+            &self,
+            request: tonic::Request<super::DestroyCryptoKeyVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKeyVersion>, tonic::Status>;
+        #[doc = " Restore a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion] in the"]
+        #[doc = " [DESTROY_SCHEDULED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.DESTROY_SCHEDULED]"]
+        #[doc = " state."]
+        #[doc = ""]
+        #[doc = " Upon restoration of the CryptoKeyVersion,"]
+        #[doc = " [state][google.cloud.kms.v1.CryptoKeyVersion.state] will be set to"]
+        #[doc = " [DISABLED][google.cloud.kms.v1.CryptoKeyVersion.CryptoKeyVersionState.DISABLED],"]
+        #[doc = " and [destroy_time][google.cloud.kms.v1.CryptoKeyVersion.destroy_time] will"]
+        #[doc = " be cleared."]
+        async fn restore_crypto_key_version(
+            &self,
+            request: tonic::Request<super::RestoreCryptoKeyVersionRequest>,
+        ) -> Result<tonic::Response<super::CryptoKeyVersion>, tonic::Status>;
+        #[doc = " Encrypts data, so that it can only be recovered by a call to"]
+        #[doc = " [Decrypt][google.cloud.kms.v1.KeyManagementService.Decrypt]. The"]
+        #[doc = " [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose] must be"]
+        #[doc = " [ENCRYPT_DECRYPT][google.cloud.kms.v1.CryptoKey.CryptoKeyPurpose.ENCRYPT_DECRYPT]."]
+        async fn encrypt(
+            &self,
+            request: tonic::Request<super::EncryptRequest>,
+        ) -> Result<tonic::Response<super::EncryptResponse>, tonic::Status>;
+        #[doc = " Decrypts data that was protected by"]
+        #[doc = " [Encrypt][google.cloud.kms.v1.KeyManagementService.Encrypt]. The"]
+        #[doc = " [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose] must be"]
+        #[doc = " [ENCRYPT_DECRYPT][google.cloud.kms.v1.CryptoKey.CryptoKeyPurpose.ENCRYPT_DECRYPT]."]
+        async fn decrypt(
+            &self,
+            request: tonic::Request<super::DecryptRequest>,
+        ) -> Result<tonic::Response<super::DecryptResponse>, tonic::Status>;
+        #[doc = " Signs data using a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]"]
+        #[doc = " with [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose]"]
+        #[doc = " ASYMMETRIC_SIGN, producing a signature that can be verified with the public"]
+        #[doc = " key retrieved from"]
+        #[doc = " [GetPublicKey][google.cloud.kms.v1.KeyManagementService.GetPublicKey]."]
+        async fn asymmetric_sign(
+            &self,
+            request: tonic::Request<super::AsymmetricSignRequest>,
+        ) -> Result<tonic::Response<super::AsymmetricSignResponse>, tonic::Status>;
+        #[doc = " Decrypts data that was encrypted with a public key retrieved from"]
+        #[doc = " [GetPublicKey][google.cloud.kms.v1.KeyManagementService.GetPublicKey]"]
+        #[doc = " corresponding to a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]"]
+        #[doc = " with [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose]"]
+        #[doc = " ASYMMETRIC_DECRYPT."]
+        async fn asymmetric_decrypt(
+            &self,
+            request: tonic::Request<super::AsymmetricDecryptRequest>,
+        ) -> Result<tonic::Response<super::AsymmetricDecryptResponse>, tonic::Status>;
+        #[doc = " Signs data using a [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]"]
+        #[doc = " with [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose] MAC,"]
+        #[doc = " producing a tag that can be verified by another source with the same key."]
+        async fn mac_sign(
+            &self,
+            request: tonic::Request<super::MacSignRequest>,
+        ) -> Result<tonic::Response<super::MacSignResponse>, tonic::Status>;
+        #[doc = " Verifies MAC tag using a"]
+        #[doc = " [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion] with"]
+        #[doc = " [CryptoKey.purpose][google.cloud.kms.v1.CryptoKey.purpose] MAC, and returns"]
+        #[doc = " a response that indicates whether or not the verification was successful."]
+        async fn mac_verify(
+            &self,
+            request: tonic::Request<super::MacVerifyRequest>,
+        ) -> Result<tonic::Response<super::MacVerifyResponse>, tonic::Status>;
+        #[doc = " Generate random bytes using the Cloud KMS randomness source in the provided"]
+        #[doc = " location."]
+        async fn generate_random_bytes(
+            &self,
+            request: tonic::Request<super::GenerateRandomBytesRequest>,
+        ) -> Result<tonic::Response<super::GenerateRandomBytesResponse>, tonic::Status>;
+    }
+    #[doc = " Google Cloud Key Management Service"]
+    #[doc = ""]
+    #[doc = " Manages cryptographic keys and operations using those keys. Implements a REST"]
+    #[doc = " model with the following objects:"]
+    #[doc = ""]
+    #[doc = " * [KeyRing][google.cloud.kms.v1.KeyRing]"]
+    #[doc = " * [CryptoKey][google.cloud.kms.v1.CryptoKey]"]
+    #[doc = " * [CryptoKeyVersion][google.cloud.kms.v1.CryptoKeyVersion]"]
+    #[doc = " * [ImportJob][google.cloud.kms.v1.ImportJob]"]
+    #[doc = ""]
+    #[doc = " If you are using manual gRPC libraries, see"]
+    #[doc = " [Using gRPC with Cloud KMS](https://cloud.google.com/kms/docs/grpc)."]
+    #[derive(Debug)]
+    pub struct KeyManagementServiceServer<T: KeyManagementService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: KeyManagementService> KeyManagementServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        #[doc = r" Enable decompressing requests with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.accept_compression_encodings.enable_gzip();
+            self
+        }
+        #[doc = r" Compress responses with `gzip`, if the client supports it."]
+        pub fn send_gzip(mut self) -> Self {
+            self.send_compression_encodings.enable_gzip();
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for KeyManagementServiceServer<T>
+    where
+        T: KeyManagementService,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.cloud.kms.v1.KeyManagementService/ListKeyRings" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListKeyRingsSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::ListKeyRingsRequest>
+                        for ListKeyRingsSvc<T>
+                    {
+                        type Response = super::ListKeyRingsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListKeyRingsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_key_rings(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListKeyRingsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/ListCryptoKeys" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListCryptoKeysSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::ListCryptoKeysRequest>
+                        for ListCryptoKeysSvc<T>
+                    {
+                        type Response = super::ListCryptoKeysResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListCryptoKeysRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_crypto_keys(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListCryptoKeysSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/ListCryptoKeyVersions" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListCryptoKeyVersionsSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::ListCryptoKeyVersionsRequest>
+                        for ListCryptoKeyVersionsSvc<T>
+                    {
+                        type Response = super::ListCryptoKeyVersionsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListCryptoKeyVersionsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).list_crypto_key_versions(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListCryptoKeyVersionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/ListImportJobs" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListImportJobsSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::ListImportJobsRequest>
+                        for ListImportJobsSvc<T>
+                    {
+                        type Response = super::ListImportJobsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListImportJobsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_import_jobs(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListImportJobsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/GetKeyRing" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetKeyRingSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::GetKeyRingRequest> for GetKeyRingSvc<T>
+                    {
+                        type Response = super::KeyRing;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetKeyRingRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_key_ring(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetKeyRingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/GetCryptoKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetCryptoKeySvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::GetCryptoKeyRequest>
+                        for GetCryptoKeySvc<T>
+                    {
+                        type Response = super::CryptoKey;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetCryptoKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_crypto_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetCryptoKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/GetCryptoKeyVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetCryptoKeyVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::GetCryptoKeyVersionRequest>
+                        for GetCryptoKeyVersionSvc<T>
+                    {
+                        type Response = super::CryptoKeyVersion;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetCryptoKeyVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_crypto_key_version(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetCryptoKeyVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/GetPublicKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPublicKeySvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::GetPublicKeyRequest>
+                        for GetPublicKeySvc<T>
+                    {
+                        type Response = super::PublicKey;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPublicKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_public_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetPublicKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/GetImportJob" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetImportJobSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::GetImportJobRequest>
+                        for GetImportJobSvc<T>
+                    {
+                        type Response = super::ImportJob;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetImportJobRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_import_job(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetImportJobSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/CreateKeyRing" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateKeyRingSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::CreateKeyRingRequest>
+                        for CreateKeyRingSvc<T>
+                    {
+                        type Response = super::KeyRing;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateKeyRingRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_key_ring(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateKeyRingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/CreateCryptoKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateCryptoKeySvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::CreateCryptoKeyRequest>
+                        for CreateCryptoKeySvc<T>
+                    {
+                        type Response = super::CryptoKey;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateCryptoKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_crypto_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateCryptoKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/CreateCryptoKeyVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateCryptoKeyVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::CreateCryptoKeyVersionRequest>
+                        for CreateCryptoKeyVersionSvc<T>
+                    {
+                        type Response = super::CryptoKeyVersion;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateCryptoKeyVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).create_crypto_key_version(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateCryptoKeyVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/ImportCryptoKeyVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct ImportCryptoKeyVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::ImportCryptoKeyVersionRequest>
+                        for ImportCryptoKeyVersionSvc<T>
+                    {
+                        type Response = super::CryptoKeyVersion;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ImportCryptoKeyVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).import_crypto_key_version(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ImportCryptoKeyVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/CreateImportJob" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateImportJobSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::CreateImportJobRequest>
+                        for CreateImportJobSvc<T>
+                    {
+                        type Response = super::ImportJob;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateImportJobRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_import_job(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateImportJobSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/UpdateCryptoKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateCryptoKeySvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::UpdateCryptoKeyRequest>
+                        for UpdateCryptoKeySvc<T>
+                    {
+                        type Response = super::CryptoKey;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateCryptoKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_crypto_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateCryptoKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/UpdateCryptoKeyVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateCryptoKeyVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::UpdateCryptoKeyVersionRequest>
+                        for UpdateCryptoKeyVersionSvc<T>
+                    {
+                        type Response = super::CryptoKeyVersion;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateCryptoKeyVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).update_crypto_key_version(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateCryptoKeyVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/UpdateCryptoKeyPrimaryVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateCryptoKeyPrimaryVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::UpdateCryptoKeyPrimaryVersionRequest>
+                        for UpdateCryptoKeyPrimaryVersionSvc<T>
+                    {
+                        type Response = super::CryptoKey;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateCryptoKeyPrimaryVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).update_crypto_key_primary_version(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateCryptoKeyPrimaryVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/DestroyCryptoKeyVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct DestroyCryptoKeyVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::DestroyCryptoKeyVersionRequest>
+                        for DestroyCryptoKeyVersionSvc<T>
+                    {
+                        type Response = super::CryptoKeyVersion;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DestroyCryptoKeyVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).destroy_crypto_key_version(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DestroyCryptoKeyVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/RestoreCryptoKeyVersion" => {
+                    #[allow(non_camel_case_types)]
+                    struct RestoreCryptoKeyVersionSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::RestoreCryptoKeyVersionRequest>
+                        for RestoreCryptoKeyVersionSvc<T>
+                    {
+                        type Response = super::CryptoKeyVersion;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RestoreCryptoKeyVersionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).restore_crypto_key_version(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RestoreCryptoKeyVersionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/Encrypt" => {
+                    #[allow(non_camel_case_types)]
+                    struct EncryptSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService> tonic::server::UnaryService<super::EncryptRequest> for EncryptSvc<T> {
+                        type Response = super::EncryptResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::EncryptRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).encrypt(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = EncryptSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/Decrypt" => {
+                    #[allow(non_camel_case_types)]
+                    struct DecryptSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService> tonic::server::UnaryService<super::DecryptRequest> for DecryptSvc<T> {
+                        type Response = super::DecryptResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DecryptRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).decrypt(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DecryptSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/AsymmetricSign" => {
+                    #[allow(non_camel_case_types)]
+                    struct AsymmetricSignSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::AsymmetricSignRequest>
+                        for AsymmetricSignSvc<T>
+                    {
+                        type Response = super::AsymmetricSignResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AsymmetricSignRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).asymmetric_sign(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AsymmetricSignSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/AsymmetricDecrypt" => {
+                    #[allow(non_camel_case_types)]
+                    struct AsymmetricDecryptSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::AsymmetricDecryptRequest>
+                        for AsymmetricDecryptSvc<T>
+                    {
+                        type Response = super::AsymmetricDecryptResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AsymmetricDecryptRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).asymmetric_decrypt(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AsymmetricDecryptSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/MacSign" => {
+                    #[allow(non_camel_case_types)]
+                    struct MacSignSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService> tonic::server::UnaryService<super::MacSignRequest> for MacSignSvc<T> {
+                        type Response = super::MacSignResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MacSignRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).mac_sign(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MacSignSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/MacVerify" => {
+                    #[allow(non_camel_case_types)]
+                    struct MacVerifySvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::MacVerifyRequest> for MacVerifySvc<T>
+                    {
+                        type Response = super::MacVerifyResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MacVerifyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).mac_verify(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MacVerifySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.cloud.kms.v1.KeyManagementService/GenerateRandomBytes" => {
+                    #[allow(non_camel_case_types)]
+                    struct GenerateRandomBytesSvc<T: KeyManagementService>(pub Arc<T>);
+                    impl<T: KeyManagementService>
+                        tonic::server::UnaryService<super::GenerateRandomBytesRequest>
+                        for GenerateRandomBytesSvc<T>
+                    {
+                        type Response = super::GenerateRandomBytesResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GenerateRandomBytesRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).generate_random_bytes(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GenerateRandomBytesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: KeyManagementService> Clone for KeyManagementServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: KeyManagementService> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: KeyManagementService> tonic::transport::NamedService for KeyManagementServiceServer<T> {
+        const NAME: &'static str = "google.cloud.kms.v1.KeyManagementService";
+    }
+}
@@ -424,3 +424,240 @@ pub mod iam_policy_client {
         }
     }
 }
+#[doc = r" Generated server implementations."]
+#[cfg(test)]
+pub mod iam_policy_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with IamPolicyServer."]
+    #[async_trait]
+    pub trait IamPolicy: Send + Sync + 'static {
+        #[doc = " Sets the access control policy on the specified resource. Replaces any"]
+        #[doc = " existing policy."]
+        async fn set_iam_policy(
+            &self,
+            request: tonic::Request<super::SetIamPolicyRequest>,
+        ) -> Result<tonic::Response<super::Policy>, tonic::Status>;
+        #[doc = " Gets the access control policy for a resource."]
+        #[doc = " Returns an empty policy if the resource exists and does not have a policy"]
+        #[doc = " set."]
+        async fn get_iam_policy(
+            &self,
+            request: tonic::Request<super::GetIamPolicyRequest>,
+        ) -> Result<tonic::Response<super::Policy>, tonic::Status>;
+        #[doc = " Returns permissions that a caller has on the specified resource."]
+        #[doc = " If the resource does not exist, this will return an empty set of"]
+        #[doc = " permissions, not a NOT_FOUND error."]
+        #[doc = ""]
+        #[doc = " Note: This operation is designed to be used for building permission-aware"]
+        #[doc = " UIs and command-line tools, not for authorization checking. This operation"]
+        #[doc = " may \"fail open\" without warning."]
+        async fn test_iam_permissions(
+            &self,
+            request: tonic::Request<super::TestIamPermissionsRequest>,
+        ) -> Result<tonic::Response<super::TestIamPermissionsResponse>, tonic::Status>;
+    }
+    #[doc = " ## API Overview"]
+    #[doc = ""]
+    #[doc = " Manages Identity and Access Management (IAM) policies."]
+    #[doc = ""]
+    #[doc = " Any implementation of an API that offers access control features"]
+    #[doc = " implements the google.iam.v1.IAMPolicy interface."]
+    #[doc = ""]
+    #[doc = " ## Data model"]
+    #[doc = ""]
+    #[doc = " Access control is applied when a principal (user or service account), takes"]
+    #[doc = " some action on a resource exposed by a service. Resources, identified by"]
+    #[doc = " URI-like names, are the unit of access control specification. Service"]
+    #[doc = " implementations can choose the granularity of access control and the"]
+    #[doc = " supported permissions for their resources."]
+    #[doc = " For example one database service may allow access control to be"]
+    #[doc = " specified only at the Table level, whereas another might allow access control"]
+    #[doc = " to also be specified at the Column level."]
+    #[doc = ""]
+    #[doc = " ## Policy Structure"]
+    #[doc = ""]
+    #[doc = " See google.iam.v1.Policy"]
+    #[doc = ""]
+    #[doc = " This is intentionally not a CRUD style API because access control policies"]
+    #[doc = " are created and deleted implicitly with the resources to which they are"]
+    #[doc = " attached."]
+    #[derive(Debug)]
+    pub struct IamPolicyServer<T: IamPolicy> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: IamPolicy> IamPolicyServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        #[doc = r" Enable decompressing requests with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.accept_compression_encodings.enable_gzip();
+            self
+        }
+        #[doc = r" Compress responses with `gzip`, if the client supports it."]
+        pub fn send_gzip(mut self) -> Self {
+            self.send_compression_encodings.enable_gzip();
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for IamPolicyServer<T>
+    where
+        T: IamPolicy,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.iam.v1.IAMPolicy/SetIamPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetIamPolicySvc<T: IamPolicy>(pub Arc<T>);
+                    impl<T: IamPolicy> tonic::server::UnaryService<super::SetIamPolicyRequest> for SetIamPolicySvc<T> {
+                        type Response = super::Policy;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetIamPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).set_iam_policy(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetIamPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.iam.v1.IAMPolicy/GetIamPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIamPolicySvc<T: IamPolicy>(pub Arc<T>);
+                    impl<T: IamPolicy> tonic::server::UnaryService<super::GetIamPolicyRequest> for GetIamPolicySvc<T> {
+                        type Response = super::Policy;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIamPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_iam_policy(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIamPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.iam.v1.IAMPolicy/TestIamPermissions" => {
+                    #[allow(non_camel_case_types)]
+                    struct TestIamPermissionsSvc<T: IamPolicy>(pub Arc<T>);
+                    impl<T: IamPolicy> tonic::server::UnaryService<super::TestIamPermissionsRequest>
+                        for TestIamPermissionsSvc<T>
+                    {
+                        type Response = super::TestIamPermissionsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TestIamPermissionsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).test_iam_permissions(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TestIamPermissionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: IamPolicy> Clone for IamPolicyServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: IamPolicy> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: IamPolicy> tonic::transport::NamedService for IamPolicyServer<T> {
+        const NAME: &'static str = "google.iam.v1.IAMPolicy";
+    }
+}
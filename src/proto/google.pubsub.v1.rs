@@ -339,6 +339,327 @@ pub mod schema_service_client {
         }
     }
 }
+#[doc = r" Generated server implementations."]
+#[cfg(any(test, feature = "pubsub-fake"))]
+pub mod schema_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with SchemaServiceServer."]
+    #[async_trait]
+    pub trait SchemaService: Send + Sync + 'static {
+        #[doc = " Creates a schema."]
+        async fn create_schema(
+            &self,
+            request: tonic::Request<super::CreateSchemaRequest>,
+        ) -> Result<tonic::Response<super::Schema>, tonic::Status>;
+        #[doc = " Gets a schema."]
+        async fn get_schema(
+            &self,
+            request: tonic::Request<super::GetSchemaRequest>,
+        ) -> Result<tonic::Response<super::Schema>, tonic::Status>;
+        #[doc = " Lists schemas in a project."]
+        async fn list_schemas(
+            &self,
+            request: tonic::Request<super::ListSchemasRequest>,
+        ) -> Result<tonic::Response<super::ListSchemasResponse>, tonic::Status>;
+        #[doc = " Deletes a schema."]
+        async fn delete_schema(
+            &self,
+            request: tonic::Request<super::DeleteSchemaRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Validates a schema."]
+        async fn validate_schema(
+            &self,
+            request: tonic::Request<super::ValidateSchemaRequest>,
+        ) -> Result<tonic::Response<super::ValidateSchemaResponse>, tonic::Status>;
+        #[doc = " Validates a message against a schema."]
+        async fn validate_message(
+            &self,
+            request: tonic::Request<super::ValidateMessageRequest>,
+        ) -> Result<tonic::Response<super::ValidateMessageResponse>, tonic::Status>;
+    }
+    #[doc = " Service for doing schema-related operations."]
+    #[derive(Debug)]
+    pub struct SchemaServiceServer<T: SchemaService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: SchemaService> SchemaServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        #[doc = r" Enable decompressing requests with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.accept_compression_encodings.enable_gzip();
+            self
+        }
+        #[doc = r" Compress responses with `gzip`, if the client supports it."]
+        pub fn send_gzip(mut self) -> Self {
+            self.send_compression_encodings.enable_gzip();
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SchemaServiceServer<T>
+    where
+        T: SchemaService,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.pubsub.v1.SchemaService/CreateSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateSchemaSvc<T: SchemaService>(pub Arc<T>);
+                    impl<T: SchemaService> tonic::server::UnaryService<super::CreateSchemaRequest>
+                        for CreateSchemaSvc<T>
+                    {
+                        type Response = super::Schema;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_schema(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.SchemaService/GetSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSchemaSvc<T: SchemaService>(pub Arc<T>);
+                    impl<T: SchemaService> tonic::server::UnaryService<super::GetSchemaRequest> for GetSchemaSvc<T> {
+                        type Response = super::Schema;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_schema(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.SchemaService/ListSchemas" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSchemasSvc<T: SchemaService>(pub Arc<T>);
+                    impl<T: SchemaService> tonic::server::UnaryService<super::ListSchemasRequest>
+                        for ListSchemasSvc<T>
+                    {
+                        type Response = super::ListSchemasResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSchemasRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_schemas(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListSchemasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.SchemaService/DeleteSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSchemaSvc<T: SchemaService>(pub Arc<T>);
+                    impl<T: SchemaService> tonic::server::UnaryService<super::DeleteSchemaRequest>
+                        for DeleteSchemaSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_schema(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.SchemaService/ValidateSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidateSchemaSvc<T: SchemaService>(pub Arc<T>);
+                    impl<T: SchemaService> tonic::server::UnaryService<super::ValidateSchemaRequest>
+                        for ValidateSchemaSvc<T>
+                    {
+                        type Response = super::ValidateSchemaResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ValidateSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).validate_schema(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ValidateSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.SchemaService/ValidateMessage" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidateMessageSvc<T: SchemaService>(pub Arc<T>);
+                    impl<T: SchemaService>
+                        tonic::server::UnaryService<super::ValidateMessageRequest>
+                        for ValidateMessageSvc<T>
+                    {
+                        type Response = super::ValidateMessageResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ValidateMessageRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).validate_message(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ValidateMessageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: SchemaService> Clone for SchemaServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: SchemaService> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: SchemaService> tonic::transport::NamedService for SchemaServiceServer<T> {
+        const NAME: &'static str = "google.pubsub.v1.SchemaService";
+    }
+}
 /// A policy constraining the storage of messages published to the topic.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MessageStoragePolicy {
@@ -1956,3 +2277,1223 @@ pub mod subscriber_client {
         }
     }
 }
+#[doc = r" Generated server implementations."]
+#[cfg(any(test, feature = "pubsub-fake"))]
+pub mod publisher_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with PublisherServer."]
+    #[async_trait]
+    pub trait Publisher: Send + Sync + 'static {
+        #[doc = " Creates the given topic with the given name. See the [resource name rules]"]
+        #[doc = " (https://cloud.google.com/pubsub/docs/admin#resource_names)."]
+        async fn create_topic(
+            &self,
+            request: tonic::Request<super::Topic>,
+        ) -> Result<tonic::Response<super::Topic>, tonic::Status>;
+        #[doc = " Updates an existing topic. Note that certain properties of a"]
+        #[doc = " topic are not modifiable."]
+        async fn update_topic(
+            &self,
+            request: tonic::Request<super::UpdateTopicRequest>,
+        ) -> Result<tonic::Response<super::Topic>, tonic::Status>;
+        #[doc = " Adds one or more messages to the topic. Returns `NOT_FOUND` if the topic"]
+        #[doc = " does not exist."]
+        async fn publish(
+            &self,
+            request: tonic::Request<super::PublishRequest>,
+        ) -> Result<tonic::Response<super::PublishResponse>, tonic::Status>;
+        #[doc = " Gets the configuration of a topic."]
+        async fn get_topic(
+            &self,
+            request: tonic::Request<super::GetTopicRequest>,
+        ) -> Result<tonic::Response<super::Topic>, tonic::Status>;
+        #[doc = " Lists matching topics."]
+        async fn list_topics(
+            &self,
+            request: tonic::Request<super::ListTopicsRequest>,
+        ) -> Result<tonic::Response<super::ListTopicsResponse>, tonic::Status>;
+        #[doc = " Lists the names of the attached subscriptions on this topic."]
+        async fn list_topic_subscriptions(
+            &self,
+            request: tonic::Request<super::ListTopicSubscriptionsRequest>,
+        ) -> Result<tonic::Response<super::ListTopicSubscriptionsResponse>, tonic::Status>;
+        #[doc = " Lists the names of the snapshots on this topic. Snapshots are used in"]
+        #[doc = " [Seek](https://cloud.google.com/pubsub/docs/replay-overview) operations,"]
+        #[doc = " which allow you to manage message acknowledgments in bulk. That is, you can"]
+        #[doc = " set the acknowledgment state of messages in an existing subscription to the"]
+        #[doc = " state captured by a snapshot."]
+        async fn list_topic_snapshots(
+            &self,
+            request: tonic::Request<super::ListTopicSnapshotsRequest>,
+        ) -> Result<tonic::Response<super::ListTopicSnapshotsResponse>, tonic::Status>;
+        #[doc = " Deletes the topic with the given name. Returns `NOT_FOUND` if the topic"]
+        #[doc = " does not exist. After a topic is deleted, a new topic may be created with"]
+        #[doc = " the same name; this is an entirely new topic with none of the old"]
+        #[doc = " configuration or subscriptions. Existing subscriptions to this topic are"]
+        #[doc = " not deleted, but their `topic` field is set to `_deleted-topic_`."]
+        async fn delete_topic(
+            &self,
+            request: tonic::Request<super::DeleteTopicRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Detaches a subscription from this topic. All messages retained in the"]
+        #[doc = " subscription are dropped. Subsequent `Pull` and `StreamingPull` requests"]
+        #[doc = " will return FAILED_PRECONDITION. If the subscription is a push"]
+        #[doc = " subscription, pushes to the endpoint will stop."]
+        async fn detach_subscription(
+            &self,
+            request: tonic::Request<super::DetachSubscriptionRequest>,
+        ) -> Result<tonic::Response<super::DetachSubscriptionResponse>, tonic::Status>;
+    }
+    #[doc = " The service that an application uses to manipulate topics, and to send"]
+    #[doc = " messages to a topic."]
+    #[derive(Debug)]
+    pub struct PublisherServer<T: Publisher> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Publisher> PublisherServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        #[doc = r" Enable decompressing requests with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.accept_compression_encodings.enable_gzip();
+            self
+        }
+        #[doc = r" Compress responses with `gzip`, if the client supports it."]
+        pub fn send_gzip(mut self) -> Self {
+            self.send_compression_encodings.enable_gzip();
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for PublisherServer<T>
+    where
+        T: Publisher,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.pubsub.v1.Publisher/CreateTopic" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateTopicSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::Topic> for CreateTopicSvc<T> {
+                        type Response = super::Topic;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(&mut self, request: tonic::Request<super::Topic>) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_topic(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateTopicSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/UpdateTopic" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateTopicSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::UpdateTopicRequest> for UpdateTopicSvc<T> {
+                        type Response = super::Topic;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateTopicRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_topic(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateTopicSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/Publish" => {
+                    #[allow(non_camel_case_types)]
+                    struct PublishSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::PublishRequest> for PublishSvc<T> {
+                        type Response = super::PublishResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PublishRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).publish(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PublishSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/GetTopic" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTopicSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::GetTopicRequest> for GetTopicSvc<T> {
+                        type Response = super::Topic;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTopicRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_topic(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTopicSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/ListTopics" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTopicsSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::ListTopicsRequest> for ListTopicsSvc<T> {
+                        type Response = super::ListTopicsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTopicsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_topics(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTopicsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/ListTopicSubscriptions" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTopicSubscriptionsSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher>
+                        tonic::server::UnaryService<super::ListTopicSubscriptionsRequest>
+                        for ListTopicSubscriptionsSvc<T>
+                    {
+                        type Response = super::ListTopicSubscriptionsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTopicSubscriptionsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).list_topic_subscriptions(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTopicSubscriptionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/ListTopicSnapshots" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTopicSnapshotsSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::ListTopicSnapshotsRequest>
+                        for ListTopicSnapshotsSvc<T>
+                    {
+                        type Response = super::ListTopicSnapshotsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTopicSnapshotsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_topic_snapshots(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTopicSnapshotsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/DeleteTopic" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteTopicSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::DeleteTopicRequest> for DeleteTopicSvc<T> {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteTopicRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_topic(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteTopicSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Publisher/DetachSubscription" => {
+                    #[allow(non_camel_case_types)]
+                    struct DetachSubscriptionSvc<T: Publisher>(pub Arc<T>);
+                    impl<T: Publisher> tonic::server::UnaryService<super::DetachSubscriptionRequest>
+                        for DetachSubscriptionSvc<T>
+                    {
+                        type Response = super::DetachSubscriptionResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DetachSubscriptionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).detach_subscription(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DetachSubscriptionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Publisher> Clone for PublisherServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Publisher> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Publisher> tonic::transport::NamedService for PublisherServer<T> {
+        const NAME: &'static str = "google.pubsub.v1.Publisher";
+    }
+}
+#[doc = r" Generated server implementations."]
+#[cfg(any(test, feature = "pubsub-fake"))]
+pub mod subscriber_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with SubscriberServer."]
+    #[async_trait]
+    pub trait Subscriber: Send + Sync + 'static {
+        #[doc = " Creates a subscription to a given topic. See the [resource name rules]"]
+        #[doc = " (https://cloud.google.com/pubsub/docs/admin#resource_names)."]
+        #[doc = " If the subscription already exists, returns `ALREADY_EXISTS`."]
+        #[doc = " If the corresponding topic doesn't exist, returns `NOT_FOUND`."]
+        #[doc = ""]
+        #[doc = " If the name is not provided in the request, the server will assign a random"]
+        #[doc = " name for this subscription on the same project as the topic, conforming"]
+        #[doc = " to the [resource name format]"]
+        #[doc = " (https://cloud.google.com/pubsub/docs/admin#resource_names). The generated"]
+        #[doc = " name is populated in the returned Subscription object. Note that for REST"]
+        #[doc = " API requests, you must specify a name in the request."]
+        async fn create_subscription(
+            &self,
+            request: tonic::Request<super::Subscription>,
+        ) -> Result<tonic::Response<super::Subscription>, tonic::Status>;
+        #[doc = " Gets the configuration details of a subscription."]
+        async fn get_subscription(
+            &self,
+            request: tonic::Request<super::GetSubscriptionRequest>,
+        ) -> Result<tonic::Response<super::Subscription>, tonic::Status>;
+        #[doc = " Updates an existing subscription. Note that certain properties of a"]
+        #[doc = " subscription, such as its topic, are not modifiable."]
+        async fn update_subscription(
+            &self,
+            request: tonic::Request<super::UpdateSubscriptionRequest>,
+        ) -> Result<tonic::Response<super::Subscription>, tonic::Status>;
+        #[doc = " Lists matching subscriptions."]
+        async fn list_subscriptions(
+            &self,
+            request: tonic::Request<super::ListSubscriptionsRequest>,
+        ) -> Result<tonic::Response<super::ListSubscriptionsResponse>, tonic::Status>;
+        #[doc = " Deletes an existing subscription. All messages retained in the subscription"]
+        #[doc = " are immediately dropped. Calls to `Pull` after deletion will return"]
+        #[doc = " `NOT_FOUND`. After a subscription is deleted, a new one may be created with"]
+        #[doc = " the same name, but the new one has no association with the old"]
+        #[doc = " subscription or its topic unless the same topic is specified."]
+        async fn delete_subscription(
+            &self,
+            request: tonic::Request<super::DeleteSubscriptionRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Modifies the ack deadline for a specific message. This method is useful"]
+        #[doc = " to indicate that more time is needed to process a message by the"]
+        #[doc = " subscriber, or to make the message available for redelivery if the"]
+        #[doc = " processing was interrupted. Note that this does not modify the"]
+        #[doc = " subscription-level `ackDeadlineSeconds` used for subsequent messages."]
+        async fn modify_ack_deadline(
+            &self,
+            request: tonic::Request<super::ModifyAckDeadlineRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Acknowledges the messages associated with the `ack_ids` in the"]
+        #[doc = " `AcknowledgeRequest`. The Pub/Sub system can remove the relevant messages"]
+        #[doc = " from the subscription."]
+        #[doc = ""]
+        #[doc = " Acknowledging a message whose ack deadline has expired may succeed,"]
+        #[doc = " but such a message may be redelivered later. Acknowledging a message more"]
+        #[doc = " than once will not result in an error."]
+        async fn acknowledge(
+            &self,
+            request: tonic::Request<super::AcknowledgeRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Pulls messages from the server. The server may return `UNAVAILABLE` if"]
+        #[doc = " there are too many concurrent pull requests pending for the given"]
+        #[doc = " subscription."]
+        async fn pull(
+            &self,
+            request: tonic::Request<super::PullRequest>,
+        ) -> Result<tonic::Response<super::PullResponse>, tonic::Status>;
+        #[doc = "Server streaming response type for the StreamingPull method."]
+        type StreamingPullStream: futures_core::Stream<Item = Result<super::StreamingPullResponse, tonic::Status>>
+            + Send
+            + 'static;
+        #[doc = " Establishes a stream with the server, which sends messages down to the"]
+        #[doc = " client. The client streams acknowledgements and ack deadline modifications"]
+        #[doc = " back to the server. The server will close the stream and return the status"]
+        #[doc = " on any error. The server may close the stream with status `UNAVAILABLE` to"]
+        #[doc = " reassign server-side resources, in which case, the client should"]
+        #[doc = " re-establish the stream. Flow control can be achieved by configuring the"]
+        #[doc = " underlying RPC channel."]
+        async fn streaming_pull(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::StreamingPullRequest>>,
+        ) -> Result<tonic::Response<Self::StreamingPullStream>, tonic::Status>;
+        #[doc = " Modifies the `PushConfig` for a specified subscription."]
+        #[doc = ""]
+        #[doc = " This may be used to change a push subscription to a pull one (signified by"]
+        #[doc = " an empty `PushConfig`) or vice versa, or change the endpoint URL and other"]
+        #[doc = " attributes of a push subscription. Messages will accumulate for delivery"]
+        #[doc = " continuously through the call regardless of changes to the `PushConfig`."]
+        async fn modify_push_config(
+            &self,
+            request: tonic::Request<super::ModifyPushConfigRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Gets the configuration details of a snapshot. Snapshots are used in"]
+        #[doc = " <a href=\"https://cloud.google.com/pubsub/docs/replay-overview\">Seek</a>"]
+        #[doc = " operations, which allow you to manage message acknowledgments in bulk. That"]
+        #[doc = " is, you can set the acknowledgment state of messages in an existing"]
+        #[doc = " subscription to the state captured by a snapshot."]
+        async fn get_snapshot(
+            &self,
+            request: tonic::Request<super::GetSnapshotRequest>,
+        ) -> Result<tonic::Response<super::Snapshot>, tonic::Status>;
+        #[doc = " Lists the existing snapshots. Snapshots are used in [Seek]("]
+        #[doc = " https://cloud.google.com/pubsub/docs/replay-overview) operations, which"]
+        #[doc = " allow you to manage message acknowledgments in bulk. That is, you can set"]
+        #[doc = " the acknowledgment state of messages in an existing subscription to the"]
+        #[doc = " state captured by a snapshot."]
+        async fn list_snapshots(
+            &self,
+            request: tonic::Request<super::ListSnapshotsRequest>,
+        ) -> Result<tonic::Response<super::ListSnapshotsResponse>, tonic::Status>;
+        #[doc = " Creates a snapshot from the requested subscription. Snapshots are used in"]
+        #[doc = " [Seek](https://cloud.google.com/pubsub/docs/replay-overview) operations,"]
+        #[doc = " which allow you to manage message acknowledgments in bulk. That is, you can"]
+        #[doc = " set the acknowledgment state of messages in an existing subscription to the"]
+        #[doc = " state captured by a snapshot."]
+        #[doc = " If the snapshot already exists, returns `ALREADY_EXISTS`."]
+        #[doc = " If the requested subscription doesn't exist, returns `NOT_FOUND`."]
+        #[doc = " If the backlog in the subscription is too old -- and the resulting snapshot"]
+        #[doc = " would expire in less than 1 hour -- then `FAILED_PRECONDITION` is returned."]
+        #[doc = " See also the `Snapshot.expire_time` field. If the name is not provided in"]
+        #[doc = " the request, the server will assign a random"]
+        #[doc = " name for this snapshot on the same project as the subscription, conforming"]
+        #[doc = " to the [resource name format]"]
+        #[doc = " (https://cloud.google.com/pubsub/docs/admin#resource_names). The"]
+        #[doc = " generated name is populated in the returned Snapshot object. Note that for"]
+        #[doc = " REST API requests, you must specify a name in the request."]
+        async fn create_snapshot(
+            &self,
+            request: tonic::Request<super::CreateSnapshotRequest>,
+        ) -> Result<tonic::Response<super::Snapshot>, tonic::Status>;
+        #[doc = " Updates an existing snapshot. Snapshots are used in"]
+        #[doc = " <a href=\"https://cloud.google.com/pubsub/docs/replay-overview\">Seek</a>"]
+        #[doc = " operations, which allow"]
+        #[doc = " you to manage message acknowledgments in bulk. That is, you can set the"]
+        #[doc = " acknowledgment state of messages in an existing subscription to the state"]
+        #[doc = " captured by a snapshot."]
+        async fn update_snapshot(
+            &self,
+            request: tonic::Request<super::UpdateSnapshotRequest>,
+        ) -> Result<tonic::Response<super::Snapshot>, tonic::Status>;
+        #[doc = " Removes an existing snapshot. Snapshots are used in [Seek]"]
+        #[doc = " (https://cloud.google.com/pubsub/docs/replay-overview) operations, which"]
+        #[doc = " allow you to manage message acknowledgments in bulk. That is, you can set"]
+        #[doc = " the acknowledgment state of messages in an existing subscription to the"]
+        #[doc = " state captured by a snapshot."]
+        #[doc = " When the snapshot is deleted, all messages retained in the snapshot"]
+        #[doc = " are immediately dropped. After a snapshot is deleted, a new one may be"]
+        #[doc = " created with the same name, but the new one has no association with the old"]
+        #[doc = " snapshot or its subscription, unless the same subscription is specified."]
+        async fn delete_snapshot(
+            &self,
+            request: tonic::Request<super::DeleteSnapshotRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Seeks an existing subscription to a point in time or to a given snapshot,"]
+        #[doc = " whichever is provided in the request. Snapshots are used in [Seek]"]
+        #[doc = " (https://cloud.google.com/pubsub/docs/replay-overview) operations, which"]
+        #[doc = " allow you to manage message acknowledgments in bulk. That is, you can set"]
+        #[doc = " the acknowledgment state of messages in an existing subscription to the"]
+        #[doc = " state captured by a snapshot. Note that both the subscription and the"]
+        #[doc = " snapshot must be on the same topic."]
+        async fn seek(
+            &self,
+            request: tonic::Request<super::SeekRequest>,
+        ) -> Result<tonic::Response<super::SeekResponse>, tonic::Status>;
+    }
+    #[doc = " The service that an application uses to manipulate subscriptions and to"]
+    #[doc = " consume messages from a subscription via the `Pull` method or by"]
+    #[doc = " establishing a bi-directional stream using the `StreamingPull` method."]
+    #[derive(Debug)]
+    pub struct SubscriberServer<T: Subscriber> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Subscriber> SubscriberServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        #[doc = r" Enable decompressing requests with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.accept_compression_encodings.enable_gzip();
+            self
+        }
+        #[doc = r" Compress responses with `gzip`, if the client supports it."]
+        pub fn send_gzip(mut self) -> Self {
+            self.send_compression_encodings.enable_gzip();
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SubscriberServer<T>
+    where
+        T: Subscriber,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.pubsub.v1.Subscriber/CreateSubscription" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateSubscriptionSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::Subscription> for CreateSubscriptionSvc<T> {
+                        type Response = super::Subscription;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::Subscription>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_subscription(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateSubscriptionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/GetSubscription" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSubscriptionSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::GetSubscriptionRequest>
+                        for GetSubscriptionSvc<T>
+                    {
+                        type Response = super::Subscription;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSubscriptionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_subscription(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSubscriptionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/UpdateSubscription" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSubscriptionSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber>
+                        tonic::server::UnaryService<super::UpdateSubscriptionRequest>
+                        for UpdateSubscriptionSvc<T>
+                    {
+                        type Response = super::Subscription;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSubscriptionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_subscription(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateSubscriptionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/ListSubscriptions" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSubscriptionsSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::ListSubscriptionsRequest>
+                        for ListSubscriptionsSvc<T>
+                    {
+                        type Response = super::ListSubscriptionsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSubscriptionsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_subscriptions(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListSubscriptionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/DeleteSubscription" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSubscriptionSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber>
+                        tonic::server::UnaryService<super::DeleteSubscriptionRequest>
+                        for DeleteSubscriptionSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteSubscriptionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_subscription(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteSubscriptionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/ModifyAckDeadline" => {
+                    #[allow(non_camel_case_types)]
+                    struct ModifyAckDeadlineSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::ModifyAckDeadlineRequest>
+                        for ModifyAckDeadlineSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ModifyAckDeadlineRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).modify_ack_deadline(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ModifyAckDeadlineSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/Acknowledge" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcknowledgeSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::AcknowledgeRequest> for AcknowledgeSvc<T> {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcknowledgeRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).acknowledge(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AcknowledgeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/Pull" => {
+                    #[allow(non_camel_case_types)]
+                    struct PullSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::PullRequest> for PullSvc<T> {
+                        type Response = super::PullResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PullRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).pull(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PullSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/StreamingPull" => {
+                    #[allow(non_camel_case_types)]
+                    struct StreamingPullSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::StreamingService<super::StreamingPullRequest>
+                        for StreamingPullSvc<T>
+                    {
+                        type Response = super::StreamingPullResponse;
+                        type ResponseStream = T::StreamingPullStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::StreamingPullRequest>>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).streaming_pull(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = StreamingPullSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/ModifyPushConfig" => {
+                    #[allow(non_camel_case_types)]
+                    struct ModifyPushConfigSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::ModifyPushConfigRequest>
+                        for ModifyPushConfigSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ModifyPushConfigRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).modify_push_config(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ModifyPushConfigSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/GetSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSnapshotSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::GetSnapshotRequest> for GetSnapshotSvc<T> {
+                        type Response = super::Snapshot;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_snapshot(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/ListSnapshots" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSnapshotsSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::ListSnapshotsRequest>
+                        for ListSnapshotsSvc<T>
+                    {
+                        type Response = super::ListSnapshotsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSnapshotsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_snapshots(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListSnapshotsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/CreateSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateSnapshotSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::CreateSnapshotRequest>
+                        for CreateSnapshotSvc<T>
+                    {
+                        type Response = super::Snapshot;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_snapshot(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/UpdateSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSnapshotSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::UpdateSnapshotRequest>
+                        for UpdateSnapshotSvc<T>
+                    {
+                        type Response = super::Snapshot;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_snapshot(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/DeleteSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSnapshotSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::DeleteSnapshotRequest>
+                        for DeleteSnapshotSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_snapshot(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.pubsub.v1.Subscriber/Seek" => {
+                    #[allow(non_camel_case_types)]
+                    struct SeekSvc<T: Subscriber>(pub Arc<T>);
+                    impl<T: Subscriber> tonic::server::UnaryService<super::SeekRequest> for SeekSvc<T> {
+                        type Response = super::SeekResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SeekRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).seek(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SeekSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Subscriber> Clone for SubscriberServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Subscriber> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Subscriber> tonic::transport::NamedService for SubscriberServer<T> {
+        const NAME: &'static str = "google.pubsub.v1.Subscriber";
+    }
+}
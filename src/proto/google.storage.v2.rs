@@ -2495,3 +2495,1257 @@ pub mod storage_client {
         }
     }
 }
+#[doc = r" Generated server implementations."]
+#[cfg(test)]
+pub mod storage_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with StorageServer."]
+    #[async_trait]
+    pub trait Storage: Send + Sync + 'static {
+        #[doc = " Permanently deletes an empty bucket."]
+        async fn delete_bucket(
+            &self,
+            request: tonic::Request<super::DeleteBucketRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Returns metadata for the specified bucket."]
+        async fn get_bucket(
+            &self,
+            request: tonic::Request<super::GetBucketRequest>,
+        ) -> Result<tonic::Response<super::Bucket>, tonic::Status>;
+        #[doc = " Creates a new bucket."]
+        async fn create_bucket(
+            &self,
+            request: tonic::Request<super::CreateBucketRequest>,
+        ) -> Result<tonic::Response<super::Bucket>, tonic::Status>;
+        #[doc = " Retrieves a list of buckets for a given project."]
+        async fn list_buckets(
+            &self,
+            request: tonic::Request<super::ListBucketsRequest>,
+        ) -> Result<tonic::Response<super::ListBucketsResponse>, tonic::Status>;
+        #[doc = " Locks retention policy on a bucket."]
+        async fn lock_bucket_retention_policy(
+            &self,
+            request: tonic::Request<super::LockBucketRetentionPolicyRequest>,
+        ) -> Result<tonic::Response<super::Bucket>, tonic::Status>;
+        #[doc = " Gets the IAM policy for a specified bucket."]
+        async fn get_iam_policy(
+            &self,
+            request: tonic::Request<super::super::super::iam::v1::GetIamPolicyRequest>,
+        ) -> Result<tonic::Response<super::super::super::iam::v1::Policy>, tonic::Status>;
+        #[doc = " Updates an IAM policy for the specified bucket."]
+        async fn set_iam_policy(
+            &self,
+            request: tonic::Request<super::super::super::iam::v1::SetIamPolicyRequest>,
+        ) -> Result<tonic::Response<super::super::super::iam::v1::Policy>, tonic::Status>;
+        #[doc = " Tests a set of permissions on the given bucket to see which, if"]
+        #[doc = " any, are held by the caller."]
+        async fn test_iam_permissions(
+            &self,
+            request: tonic::Request<super::super::super::iam::v1::TestIamPermissionsRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::iam::v1::TestIamPermissionsResponse>,
+            tonic::Status,
+        >;
+        #[doc = " Updates a bucket. Equivalent to JSON API's storage.buckets.patch method."]
+        async fn update_bucket(
+            &self,
+            request: tonic::Request<super::UpdateBucketRequest>,
+        ) -> Result<tonic::Response<super::Bucket>, tonic::Status>;
+        #[doc = " Permanently deletes a notification subscription."]
+        async fn delete_notification(
+            &self,
+            request: tonic::Request<super::DeleteNotificationRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " View a notification config."]
+        async fn get_notification(
+            &self,
+            request: tonic::Request<super::GetNotificationRequest>,
+        ) -> Result<tonic::Response<super::Notification>, tonic::Status>;
+        #[doc = " Creates a notification subscription for a given bucket."]
+        #[doc = " These notifications, when triggered, publish messages to the specified"]
+        #[doc = " Pub/Sub topics."]
+        #[doc = " See https://cloud.google.com/storage/docs/pubsub-notifications."]
+        async fn create_notification(
+            &self,
+            request: tonic::Request<super::CreateNotificationRequest>,
+        ) -> Result<tonic::Response<super::Notification>, tonic::Status>;
+        #[doc = " Retrieves a list of notification subscriptions for a given bucket."]
+        async fn list_notifications(
+            &self,
+            request: tonic::Request<super::ListNotificationsRequest>,
+        ) -> Result<tonic::Response<super::ListNotificationsResponse>, tonic::Status>;
+        #[doc = " Concatenates a list of existing objects into a new object in the same"]
+        #[doc = " bucket."]
+        async fn compose_object(
+            &self,
+            request: tonic::Request<super::ComposeObjectRequest>,
+        ) -> Result<tonic::Response<super::Object>, tonic::Status>;
+        #[doc = " Deletes an object and its metadata. Deletions are permanent if versioning"]
+        #[doc = " is not enabled for the bucket, or if the `generation` parameter"]
+        #[doc = " is used."]
+        async fn delete_object(
+            &self,
+            request: tonic::Request<super::DeleteObjectRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Retrieves an object's metadata."]
+        async fn get_object(
+            &self,
+            request: tonic::Request<super::GetObjectRequest>,
+        ) -> Result<tonic::Response<super::Object>, tonic::Status>;
+        #[doc = "Server streaming response type for the ReadObject method."]
+        type ReadObjectStream: futures_core::Stream<Item = Result<super::ReadObjectResponse, tonic::Status>>
+            + Send
+            + 'static;
+        #[doc = " Reads an object's data."]
+        async fn read_object(
+            &self,
+            request: tonic::Request<super::ReadObjectRequest>,
+        ) -> Result<tonic::Response<Self::ReadObjectStream>, tonic::Status>;
+        #[doc = " Updates an object's metadata."]
+        #[doc = " Equivalent to JSON API's storage.objects.patch."]
+        async fn update_object(
+            &self,
+            request: tonic::Request<super::UpdateObjectRequest>,
+        ) -> Result<tonic::Response<super::Object>, tonic::Status>;
+        #[doc = " Stores a new object and metadata."]
+        #[doc = ""]
+        #[doc = " An object can be written either in a single message stream or in a"]
+        #[doc = " resumable sequence of message streams. To write using a single stream,"]
+        #[doc = " the client should include in the first message of the stream an"]
+        #[doc = " `WriteObjectSpec` describing the destination bucket, object, and any"]
+        #[doc = " preconditions. Additionally, the final message must set 'finish_write' to"]
+        #[doc = " true, or else it is an error."]
+        #[doc = ""]
+        #[doc = " For a resumable write, the client should instead call"]
+        #[doc = " `StartResumableWrite()` and provide that method an `WriteObjectSpec.`"]
+        #[doc = " They should then attach the returned `upload_id` to the first message of"]
+        #[doc = " each following call to `Create`. If there is an error or the connection is"]
+        #[doc = " broken during the resumable `Create()`, the client should check the status"]
+        #[doc = " of the `Create()` by calling `QueryWriteStatus()` and continue writing from"]
+        #[doc = " the returned `persisted_size`. This may be less than the amount of data the"]
+        #[doc = " client previously sent."]
+        #[doc = ""]
+        #[doc = " The service will not view the object as complete until the client has"]
+        #[doc = " sent a `WriteObjectRequest` with `finish_write` set to `true`. Sending any"]
+        #[doc = " requests on a stream after sending a request with `finish_write` set to"]
+        #[doc = " `true` will cause an error. The client **should** check the response it"]
+        #[doc = " receives to determine how much data the service was able to commit and"]
+        #[doc = " whether the service views the object as complete."]
+        async fn write_object(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::WriteObjectRequest>>,
+        ) -> Result<tonic::Response<super::WriteObjectResponse>, tonic::Status>;
+        #[doc = " Retrieves a list of objects matching the criteria."]
+        async fn list_objects(
+            &self,
+            request: tonic::Request<super::ListObjectsRequest>,
+        ) -> Result<tonic::Response<super::ListObjectsResponse>, tonic::Status>;
+        #[doc = " Rewrites a source object to a destination object. Optionally overrides"]
+        #[doc = " metadata."]
+        async fn rewrite_object(
+            &self,
+            request: tonic::Request<super::RewriteObjectRequest>,
+        ) -> Result<tonic::Response<super::RewriteResponse>, tonic::Status>;
+        #[doc = " Starts a resumable write. How long the write operation remains valid, and"]
+        #[doc = " what happens when the write operation becomes invalid, are"]
+        #[doc = " service-dependent."]
+        async fn start_resumable_write(
+            &self,
+            request: tonic::Request<super::StartResumableWriteRequest>,
+        ) -> Result<tonic::Response<super::StartResumableWriteResponse>, tonic::Status>;
+        #[doc = " Determines the `persisted_size` for an object that is being written, which"]
+        #[doc = " can then be used as the `write_offset` for the next `Write()` call."]
+        #[doc = ""]
+        #[doc = " If the object does not exist (i.e., the object has been deleted, or the"]
+        #[doc = " first `Write()` has not yet reached the service), this method returns the"]
+        #[doc = " error `NOT_FOUND`."]
+        #[doc = ""]
+        #[doc = " The client **may** call `QueryWriteStatus()` at any time to determine how"]
+        #[doc = " much data has been processed for this object. This is useful if the"]
+        #[doc = " client is buffering data and needs to know which data can be safely"]
+        #[doc = " evicted. For any sequence of `QueryWriteStatus()` calls for a given"]
+        #[doc = " object name, the sequence of returned `persisted_size` values will be"]
+        #[doc = " non-decreasing."]
+        async fn query_write_status(
+            &self,
+            request: tonic::Request<super::QueryWriteStatusRequest>,
+        ) -> Result<tonic::Response<super::QueryWriteStatusResponse>, tonic::Status>;
+        #[doc = " Retrieves the name of a project's Google Cloud Storage service account."]
+        async fn get_service_account(
+            &self,
+            request: tonic::Request<super::GetServiceAccountRequest>,
+        ) -> Result<tonic::Response<super::ServiceAccount>, tonic::Status>;
+        #[doc = " Creates a new HMAC key for the given service account."]
+        async fn create_hmac_key(
+            &self,
+            request: tonic::Request<super::CreateHmacKeyRequest>,
+        ) -> Result<tonic::Response<super::CreateHmacKeyResponse>, tonic::Status>;
+        #[doc = " Deletes a given HMAC key.  Key must be in an INACTIVE state."]
+        async fn delete_hmac_key(
+            &self,
+            request: tonic::Request<super::DeleteHmacKeyRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " Gets an existing HMAC key metadata for the given id."]
+        async fn get_hmac_key(
+            &self,
+            request: tonic::Request<super::GetHmacKeyRequest>,
+        ) -> Result<tonic::Response<super::HmacKeyMetadata>, tonic::Status>;
+        #[doc = " Lists HMAC keys under a given project with the additional filters provided."]
+        async fn list_hmac_keys(
+            &self,
+            request: tonic::Request<super::ListHmacKeysRequest>,
+        ) -> Result<tonic::Response<super::ListHmacKeysResponse>, tonic::Status>;
+        #[doc = " Updates a given HMAC key state between ACTIVE and INACTIVE."]
+        async fn update_hmac_key(
+            &self,
+            request: tonic::Request<super::UpdateHmacKeyRequest>,
+        ) -> Result<tonic::Response<super::HmacKeyMetadata>, tonic::Status>;
+    }
+    #[doc = " ## API Overview and Naming Syntax"]
+    #[doc = ""]
+    #[doc = " The Cloud Storage gRPC API allows applications to read and write data through"]
+    #[doc = " the abstractions of buckets and objects. For a description of these"]
+    #[doc = " abstractions please see https://cloud.google.com/storage/docs."]
+    #[doc = ""]
+    #[doc = " Resources are named as follows:"]
+    #[doc = "   - Projects are referred to as they are defined by the Resource Manager API,"]
+    #[doc = "     using strings like `projects/123456` or `projects/my-string-id`."]
+    #[doc = "   - Buckets are named using string names of the form:"]
+    #[doc = "     `projects/{project}/buckets/{bucket}`"]
+    #[doc = "     For globally unique buckets, `_` may be substituted for the project."]
+    #[doc = "   - Objects are uniquely identified by their name along with the name of the"]
+    #[doc = "     bucket they belong to, as separate strings in this API. For example:"]
+    #[doc = ""]
+    #[doc = "       ReadObjectRequest {"]
+    #[doc = "         bucket: 'projects/_/buckets/my-bucket'"]
+    #[doc = "         object: 'my-object'"]
+    #[doc = "       }"]
+    #[doc = "     Note that object names can contain `/` characters, which are treated as"]
+    #[doc = "     any other character (no special directory semantics)."]
+    #[derive(Debug)]
+    pub struct StorageServer<T: Storage> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Storage> StorageServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        #[doc = r" Enable decompressing requests with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.accept_compression_encodings.enable_gzip();
+            self
+        }
+        #[doc = r" Compress responses with `gzip`, if the client supports it."]
+        pub fn send_gzip(mut self) -> Self {
+            self.send_compression_encodings.enable_gzip();
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for StorageServer<T>
+    where
+        T: Storage,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.storage.v2.Storage/DeleteBucket" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteBucketSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::DeleteBucketRequest> for DeleteBucketSvc<T> {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteBucketRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_bucket(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteBucketSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/GetBucket" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetBucketSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::GetBucketRequest> for GetBucketSvc<T> {
+                        type Response = super::Bucket;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetBucketRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_bucket(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetBucketSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/CreateBucket" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateBucketSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::CreateBucketRequest> for CreateBucketSvc<T> {
+                        type Response = super::Bucket;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateBucketRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_bucket(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateBucketSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/ListBuckets" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListBucketsSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::ListBucketsRequest> for ListBucketsSvc<T> {
+                        type Response = super::ListBucketsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListBucketsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_buckets(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListBucketsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/LockBucketRetentionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct LockBucketRetentionPolicySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage>
+                        tonic::server::UnaryService<super::LockBucketRetentionPolicyRequest>
+                        for LockBucketRetentionPolicySvc<T>
+                    {
+                        type Response = super::Bucket;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LockBucketRetentionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).lock_bucket_retention_policy(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LockBucketRetentionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/GetIamPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIamPolicySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage>
+                        tonic::server::UnaryService<
+                            super::super::super::iam::v1::GetIamPolicyRequest,
+                        > for GetIamPolicySvc<T>
+                    {
+                        type Response = super::super::super::iam::v1::Policy;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::super::iam::v1::GetIamPolicyRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_iam_policy(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIamPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/SetIamPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetIamPolicySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage>
+                        tonic::server::UnaryService<
+                            super::super::super::iam::v1::SetIamPolicyRequest,
+                        > for SetIamPolicySvc<T>
+                    {
+                        type Response = super::super::super::iam::v1::Policy;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::super::iam::v1::SetIamPolicyRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).set_iam_policy(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetIamPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/TestIamPermissions" => {
+                    #[allow(non_camel_case_types)]
+                    struct TestIamPermissionsSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage>
+                        tonic::server::UnaryService<
+                            super::super::super::iam::v1::TestIamPermissionsRequest,
+                        > for TestIamPermissionsSvc<T>
+                    {
+                        type Response = super::super::super::iam::v1::TestIamPermissionsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::super::iam::v1::TestIamPermissionsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).test_iam_permissions(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TestIamPermissionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/UpdateBucket" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateBucketSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::UpdateBucketRequest> for UpdateBucketSvc<T> {
+                        type Response = super::Bucket;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateBucketRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_bucket(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateBucketSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/DeleteNotification" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteNotificationSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::DeleteNotificationRequest>
+                        for DeleteNotificationSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteNotificationRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_notification(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteNotificationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/GetNotification" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetNotificationSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::GetNotificationRequest>
+                        for GetNotificationSvc<T>
+                    {
+                        type Response = super::Notification;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetNotificationRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_notification(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetNotificationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/CreateNotification" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateNotificationSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::CreateNotificationRequest>
+                        for CreateNotificationSvc<T>
+                    {
+                        type Response = super::Notification;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateNotificationRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_notification(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateNotificationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/ListNotifications" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListNotificationsSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::ListNotificationsRequest>
+                        for ListNotificationsSvc<T>
+                    {
+                        type Response = super::ListNotificationsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListNotificationsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_notifications(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListNotificationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/ComposeObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct ComposeObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::ComposeObjectRequest> for ComposeObjectSvc<T> {
+                        type Response = super::Object;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ComposeObjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).compose_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ComposeObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/DeleteObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::DeleteObjectRequest> for DeleteObjectSvc<T> {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteObjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/GetObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::GetObjectRequest> for GetObjectSvc<T> {
+                        type Response = super::Object;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetObjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/ReadObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReadObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::ServerStreamingService<super::ReadObjectRequest>
+                        for ReadObjectSvc<T>
+                    {
+                        type Response = super::ReadObjectResponse;
+                        type ResponseStream = T::ReadObjectStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReadObjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).read_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ReadObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/UpdateObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::UpdateObjectRequest> for UpdateObjectSvc<T> {
+                        type Response = super::Object;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateObjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/WriteObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct WriteObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage>
+                        tonic::server::ClientStreamingService<super::WriteObjectRequest>
+                        for WriteObjectSvc<T>
+                    {
+                        type Response = super::WriteObjectResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::WriteObjectRequest>>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).write_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = WriteObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.client_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/ListObjects" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListObjectsSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::ListObjectsRequest> for ListObjectsSvc<T> {
+                        type Response = super::ListObjectsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListObjectsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_objects(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListObjectsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/RewriteObject" => {
+                    #[allow(non_camel_case_types)]
+                    struct RewriteObjectSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::RewriteObjectRequest> for RewriteObjectSvc<T> {
+                        type Response = super::RewriteResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RewriteObjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).rewrite_object(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RewriteObjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/StartResumableWrite" => {
+                    #[allow(non_camel_case_types)]
+                    struct StartResumableWriteSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::StartResumableWriteRequest>
+                        for StartResumableWriteSvc<T>
+                    {
+                        type Response = super::StartResumableWriteResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::StartResumableWriteRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).start_resumable_write(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = StartResumableWriteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/QueryWriteStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct QueryWriteStatusSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::QueryWriteStatusRequest>
+                        for QueryWriteStatusSvc<T>
+                    {
+                        type Response = super::QueryWriteStatusResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryWriteStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).query_write_status(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = QueryWriteStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/GetServiceAccount" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetServiceAccountSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::GetServiceAccountRequest>
+                        for GetServiceAccountSvc<T>
+                    {
+                        type Response = super::ServiceAccount;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetServiceAccountRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_service_account(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetServiceAccountSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/CreateHmacKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateHmacKeySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::CreateHmacKeyRequest> for CreateHmacKeySvc<T> {
+                        type Response = super::CreateHmacKeyResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateHmacKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_hmac_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateHmacKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/DeleteHmacKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteHmacKeySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::DeleteHmacKeyRequest> for DeleteHmacKeySvc<T> {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteHmacKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_hmac_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteHmacKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/GetHmacKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetHmacKeySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::GetHmacKeyRequest> for GetHmacKeySvc<T> {
+                        type Response = super::HmacKeyMetadata;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetHmacKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_hmac_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetHmacKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/ListHmacKeys" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListHmacKeysSvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::ListHmacKeysRequest> for ListHmacKeysSvc<T> {
+                        type Response = super::ListHmacKeysResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListHmacKeysRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_hmac_keys(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListHmacKeysSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.storage.v2.Storage/UpdateHmacKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateHmacKeySvc<T: Storage>(pub Arc<T>);
+                    impl<T: Storage> tonic::server::UnaryService<super::UpdateHmacKeyRequest> for UpdateHmacKeySvc<T> {
+                        type Response = super::HmacKeyMetadata;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateHmacKeyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_hmac_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateHmacKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Storage> Clone for StorageServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Storage> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Storage> tonic::transport::NamedService for StorageServer<T> {
+        const NAME: &'static str = "google.storage.v2.Storage";
+    }
+}
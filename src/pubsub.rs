@@ -1,3 +1,23 @@
 pub mod client;
+pub mod codec;
+#[cfg(any(test, feature = "pubsub-fake"))]
+pub mod fake;
+pub mod message;
+pub mod publisher;
+pub mod schema;
+pub mod subscriber;
+pub mod subscription;
+pub mod topic;
+pub mod typed;
 
-pub use client::PubSubClient;
+pub use client::{AckStatus, PubSubClient};
+#[cfg(feature = "bincode")]
+pub use codec::BincodeCodec;
+pub use codec::{JsonCodec, MessageCodec, ProtobufCodec};
+pub use message::MessageBuilder;
+pub use publisher::{PublishHandle, Publisher, PublisherBuilder};
+pub use schema::{SchemaPublisher, SchemaValidator};
+pub use subscriber::{Message, Subscriber};
+pub use subscription::SubscriptionBuilder;
+pub use topic::TopicBuilder;
+pub use typed::{TypedMessage, TypedPublisher, TypedSubscriber};
@@ -1,122 +1,560 @@
-use tonic::{
-    transport::{Certificate, Channel, ClientTlsConfig},
-    IntoRequest, Request,
-};
+mod ack;
+mod admin;
+mod schema;
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use futures::Stream;
+use tonic::{transport::Channel, Code, IntoRequest, Request, Response, Status, Streaming};
 
 use crate::{
-    auth::TokenManager,
+    auth::{DefaultCredentials, NoAuth, TokenManager, TokenProvider},
+    endpoint::{emulator_endpoint, Endpoint},
     error::Error,
-    proto::{
-        google::pubsub::v1::{
-            publisher_client::PublisherClient, subscriber_client::SubscriberClient,
-            AcknowledgeRequest, PublishRequest, PublishResponse, PubsubMessage, PullRequest,
-            PullResponse,
-        },
-        TLS_CERT,
+    options::CallOptions,
+    pagination::{ListOptions, Page, PageStream},
+    proto::google::pubsub::v1::{
+        publisher_client::PublisherClient, schema_service_client::SchemaServiceClient,
+        subscriber_client::SubscriberClient, AcknowledgeRequest, ListSchemasRequest,
+        ListSubscriptionsRequest, ListTopicsRequest, ModifyAckDeadlineRequest, PublishRequest,
+        PublishResponse, PubsubMessage, PullRequest, PullResponse, Schema, SchemaView,
+        StreamingPullRequest, StreamingPullResponse, Subscription, Topic,
     },
+    retry::RetryPolicy,
     util::construct_request,
 };
+pub use ack::AckStatus;
 
-pub const DOMAIN_NAME: &str = "pubsub.googleapis.com";
+/// When set, clients connect to the emulator at this address without TLS or credentials.
+pub const EMULATOR_HOST_ENV: &str = "PUBSUB_EMULATOR_HOST";
 pub const ENDPOINT: &str = "https://pubsub.googleapis.com";
 pub const SCOPES: [&str; 2] = [
     "https://www.googleapis.com/auth/cloud-platform",
     "https://www.googleapis.com/auth/pubsub",
 ];
 
+// cf. https://github.com/googleapis/googleapis/blob/master/google/pubsub/v1/pubsub_grpc_service_config.json
+const PUBLISH_RETRY_CODES: &[Code] = &[
+    Code::Aborted,
+    Code::Cancelled,
+    Code::DeadlineExceeded,
+    Code::Internal,
+    Code::ResourceExhausted,
+    Code::Unavailable,
+    Code::Unknown,
+];
+const PULL_RETRY_CODES: &[Code] = &[
+    Code::Aborted,
+    Code::Internal,
+    Code::Unavailable,
+    Code::Unknown,
+];
+const ACKNOWLEDGE_RETRY_CODES: &[Code] = &[Code::Unavailable];
+const MODIFY_ACK_DEADLINE_RETRY_CODES: &[Code] = &[Code::Unavailable];
+const LIST_RETRY_CODES: &[Code] = &[Code::Aborted, Code::Unavailable, Code::Unknown];
+const GET_RETRY_CODES: &[Code] = LIST_RETRY_CODES;
+const ADMIN_RETRY_CODES: &[Code] = &[Code::Unavailable];
+
+#[derive(thiserror::Error, Debug)]
+pub enum PubSubError {
+    /// The request carrying the message failed. Shared by every message of the batch.
+    #[error("publish failed: {0}")]
+    Publish(Arc<Error>),
+
+    #[error("the publisher was shut down")]
+    PublisherClosed,
+
+    /// The server did not return one message id per message of the batch.
+    #[error("{received} message ids returned for {sent} messages")]
+    MessageIdMismatch { sent: usize, received: usize },
+
+    #[error("publishing with an ordering key requires message ordering on the publisher")]
+    OrderingNotEnabled,
+
+    /// A message with this ordering key failed to publish, see
+    /// [`Publisher::resume_publish`](super::Publisher::resume_publish).
+    #[error("publishing is paused for ordering key {0:?}")]
+    OrderingKeyPaused(String),
+
+    /// The definition of a schema could not be parsed, or a topic has no schema.
+    #[error("invalid schema: {0}")]
+    InvalidSchema(String),
+
+    /// A message does not conform to the schema of its topic.
+    #[error("message does not match the schema: {0}")]
+    SchemaMismatch(String),
+
+    /// A value could not be encoded by a [`MessageCodec`](super::codec::MessageCodec).
+    #[error("cannot encode message: {0}")]
+    Encode(String),
+
+    /// Message data could not be decoded by a
+    /// [`MessageCodec`](super::codec::MessageCodec). Retrying does not help; such
+    /// messages are better dead-lettered, see [`Message::dead_letter`](super::Message::dead_letter).
+    #[error("cannot decode message: {0}")]
+    Decode(String),
+}
+
+#[derive(Clone)]
 pub struct PubSubClient {
     token_manager: TokenManager,
     publisher_client: PublisherClient<Channel>,
     subscriber_client: SubscriberClient<Channel>,
+    schema_client: SchemaServiceClient<Channel>,
+    retry_policy: Option<RetryPolicy>,
 }
 
-impl PubSubClient {
-    pub async fn new() -> Result<Self, Error> {
-        Ok(Self {
-            token_manager: TokenManager::new(&SCOPES).await?,
-            publisher_client: PublisherClient::new(Self::channel().await?),
-            subscriber_client: SubscriberClient::new(Self::channel().await?),
+#[derive(Default)]
+pub struct PubSubClientBuilder {
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    endpoint: Option<Endpoint>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl PubSubClientBuilder {
+    pub fn with_token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.token_provider = Some(Arc::new(token_provider));
+        self
+    }
+
+    /// Defaults to the emulator in [`EMULATOR_HOST_ENV`] if set, otherwise [`ENDPOINT`].
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Replaces the per-method defaults, which follow Google's retry settings for Pub/Sub.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub async fn build(self) -> Result<PubSubClient, Error> {
+        let emulator = match self.endpoint {
+            Some(_) => None,
+            None => emulator_endpoint(EMULATOR_HOST_ENV)?,
+        };
+        let token_provider: Arc<dyn TokenProvider> = match (self.token_provider, &emulator) {
+            (Some(token_provider), _) => token_provider,
+            (None, Some(_)) => Arc::new(NoAuth),
+            (None, None) => Arc::new(DefaultCredentials::new().await?),
+        };
+        let endpoint = match self.endpoint.or(emulator) {
+            Some(endpoint) => endpoint,
+            None => Endpoint::new(ENDPOINT)?,
+        };
+        let channel = endpoint.connect().await?;
+
+        Ok(PubSubClient {
+            token_manager: TokenManager::with_provider(token_provider, &SCOPES),
+            publisher_client: PublisherClient::new(channel.clone()),
+            subscriber_client: SubscriberClient::new(channel.clone()),
+            schema_client: SchemaServiceClient::new(channel),
+            retry_policy: self.retry_policy,
         })
     }
+}
 
-    async fn channel() -> Result<Channel, Error> {
-        let tls_config = ClientTlsConfig::new()
-            .ca_certificate(Certificate::from_pem(TLS_CERT))
-            .domain_name(DOMAIN_NAME);
+impl PubSubClient {
+    pub async fn new() -> Result<Self, Error> {
+        Self::builder().build().await
+    }
 
-        Ok(Channel::from_static(ENDPOINT)
-            .tls_config(tls_config)?
-            .connect()
-            .await?)
+    pub fn builder() -> PubSubClientBuilder {
+        PubSubClientBuilder::default()
     }
 
     async fn construct_request<T: IntoRequest<T>>(
-        &mut self,
+        &self,
         request: T,
+        options: &CallOptions,
     ) -> Result<Request<T>, Error> {
         construct_request(
             request,
-            self.token_manager.get_token().await?.as_str(),
+            self.token_manager
+                .get_token()
+                .await?
+                .as_ref()
+                .map(|token| token.as_str()),
             vec![],
+            options,
         )
         .await
     }
 
+    /// Sends `request` through `method` of `client`, retrying according to `options`,
+    /// the client's retry policy or `default_policy`, in that order.
+    async fn call<C, T, U, F, Fut>(
+        &self,
+        client: &C,
+        request: T,
+        options: &CallOptions,
+        default_policy: impl FnOnce() -> RetryPolicy,
+        method: F,
+    ) -> Result<U, Error>
+    where
+        C: Clone,
+        T: Clone,
+        F: Fn(C, Request<T>) -> Fut,
+        Fut: Future<Output = Result<Response<U>, Status>>,
+    {
+        let request = &request;
+        let method = &method;
+        options
+            .retry_policy()
+            .or(self.retry_policy.as_ref())
+            .cloned()
+            .unwrap_or_else(default_policy)
+            .run(|| {
+                options.attempt(async move {
+                    let request = self.construct_request(request.clone(), options).await?;
+                    Ok(method(client.clone(), request).await?.into_inner())
+                })
+            })
+            .await
+    }
+
     /// # Arguments
     /// * `topic` - in the format `projects/{project}/topics/{topic}`
     pub async fn publish(
-        &mut self,
+        &self,
         topic: &str,
         data: impl Into<Vec<u8>>,
     ) -> Result<PublishResponse, Error> {
-        let request = self
-            .construct_request(PublishRequest {
+        self.publish_with_options(topic, data, &CallOptions::default())
+            .await
+    }
+
+    pub async fn publish_with_options(
+        &self,
+        topic: &str,
+        data: impl Into<Vec<u8>>,
+        options: &CallOptions,
+    ) -> Result<PublishResponse, Error> {
+        let message = PubsubMessage {
+            data: data.into(),
+            ..Default::default()
+        };
+        self.publish_message_with_options(topic, message, options)
+            .await
+    }
+
+    /// Publishes a message with attributes or an ordering key, see
+    /// [`MessageBuilder`](super::MessageBuilder).
+    ///
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub async fn publish_message(
+        &self,
+        topic: &str,
+        message: PubsubMessage,
+    ) -> Result<PublishResponse, Error> {
+        self.publish_message_with_options(topic, message, &CallOptions::default())
+            .await
+    }
+
+    pub async fn publish_message_with_options(
+        &self,
+        topic: &str,
+        message: PubsubMessage,
+        options: &CallOptions,
+    ) -> Result<PublishResponse, Error> {
+        self.publish_messages_with_options(topic, vec![message], options)
+            .await
+    }
+
+    /// Publishes several messages in one request. The returned ids are in the order of
+    /// `messages`.
+    ///
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub async fn publish_messages(
+        &self,
+        topic: &str,
+        messages: Vec<PubsubMessage>,
+    ) -> Result<PublishResponse, Error> {
+        self.publish_messages_with_options(topic, messages, &CallOptions::default())
+            .await
+    }
+
+    pub async fn publish_messages_with_options(
+        &self,
+        topic: &str,
+        messages: Vec<PubsubMessage>,
+        options: &CallOptions,
+    ) -> Result<PublishResponse, Error> {
+        self.call(
+            &self.publisher_client,
+            PublishRequest {
                 topic: topic.to_owned(),
-                messages: vec![PubsubMessage {
-                    data: data.into(),
-                    attributes: Default::default(),
-                    message_id: Default::default(),
-                    publish_time: None,
-                    ordering_key: Default::default(),
-                }],
-            })
-            .await?;
-        let res = self.publisher_client.publish(request).await?;
-        Ok(res.into_inner())
+                messages,
+            },
+            options,
+            || {
+                RetryPolicy::default()
+                    .with_multiplier(4.0)
+                    .with_total_timeout(Some(Duration::from_secs(600)))
+                    .with_retryable_codes(PUBLISH_RETRY_CODES)
+            },
+            |mut client, request| async move { client.publish(request).await },
+        )
+        .await
     }
 
     /// # Arguments
     /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
-    pub async fn pull(&mut self, subscription: &str) -> Result<PullResponse, Error> {
-        let request = self
-            .construct_request(
-                #[allow(deprecated)]
-                PullRequest {
-                    subscription: subscription.to_owned(),
-                    return_immediately: true,
-                    max_messages: 100,
-                },
-            )
-            .await?;
-        let res = self.subscriber_client.pull(request).await?;
-        Ok(res.into_inner())
+    pub async fn pull(&self, subscription: &str) -> Result<PullResponse, Error> {
+        self.pull_with_options(subscription, &CallOptions::default())
+            .await
+    }
+
+    pub async fn pull_with_options(
+        &self,
+        subscription: &str,
+        options: &CallOptions,
+    ) -> Result<PullResponse, Error> {
+        self.call(
+            &self.subscriber_client,
+            #[allow(deprecated)]
+            PullRequest {
+                subscription: subscription.to_owned(),
+                return_immediately: true,
+                max_messages: 100,
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(PULL_RETRY_CODES),
+            |mut client, request| async move { client.pull(request).await },
+        )
+        .await
     }
 
     /// # Arguments
     /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
     /// * `ack_ids`      - acknowledge ids
-    pub async fn acknowledge(
-        &mut self,
+    pub async fn acknowledge(&self, subscription: &str, ack_ids: Vec<String>) -> Result<(), Error> {
+        self.acknowledge_with_options(subscription, ack_ids, &CallOptions::default())
+            .await
+    }
+
+    pub async fn acknowledge_with_options(
+        &self,
         subscription: &str,
         ack_ids: Vec<String>,
+        options: &CallOptions,
     ) -> Result<(), Error> {
-        let request = self
-            .construct_request(AcknowledgeRequest {
+        self.call(
+            &self.subscriber_client,
+            AcknowledgeRequest {
                 subscription: subscription.to_owned(),
                 ack_ids,
-            })
-            .await?;
-        self.subscriber_client.acknowledge(request).await?;
-        Ok(())
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ACKNOWLEDGE_RETRY_CODES),
+            |mut client, request| async move { client.acknowledge(request).await },
+        )
+        .await
+    }
+
+    /// Sets the ack deadline of messages, counted from now. `0` makes them available
+    /// for redelivery immediately, like a negative acknowledgement.
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    /// * `ack_ids`      - acknowledge ids
+    /// * `ack_deadline` - truncated to whole seconds and clamped to 600 seconds.
+    pub async fn modify_ack_deadline(
+        &self,
+        subscription: &str,
+        ack_ids: Vec<String>,
+        ack_deadline: Duration,
+    ) -> Result<(), Error> {
+        self.modify_ack_deadline_with_options(
+            subscription,
+            ack_ids,
+            ack_deadline,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn modify_ack_deadline_with_options(
+        &self,
+        subscription: &str,
+        ack_ids: Vec<String>,
+        ack_deadline: Duration,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.subscriber_client,
+            ModifyAckDeadlineRequest {
+                subscription: subscription.to_owned(),
+                ack_ids,
+                ack_deadline_seconds: ack::ack_deadline_seconds(ack_deadline),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(MODIFY_ACK_DEADLINE_RETRY_CODES),
+            |mut client, request| async move { client.modify_ack_deadline(request).await },
+        )
+        .await
+    }
+
+    /// Opens a streaming pull. The first request must name the subscription.
+    /// Not retried, reconnecting is up to the caller.
+    pub(crate) async fn streaming_pull(
+        &self,
+        subscription: &str,
+        requests: impl Stream<Item = StreamingPullRequest> + Send + 'static,
+    ) -> Result<Streaming<StreamingPullResponse>, Error> {
+        let request = construct_request(
+            requests,
+            self.token_manager
+                .get_token()
+                .await?
+                .as_ref()
+                .map(|token| token.as_str()),
+            vec![("subscription", subscription)],
+            &CallOptions::default(),
+        )
+        .await?;
+        Ok(self
+            .subscriber_client
+            .clone()
+            .streaming_pull(request)
+            .await?
+            .into_inner())
+    }
+
+    /// Streams all topics of a project, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `project` - in the format `projects/{project}`
+    pub fn stream_topics(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, Topic> {
+        self.stream_topics_with_options(project, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_topics_with_options(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, Topic> {
+        let project = project.to_owned();
+        let page_size = list_options.grpc_page_size();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListTopicsRequest {
+                project: project.clone(),
+                page_size,
+                page_token: page_token.unwrap_or_default(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        &self.publisher_client,
+                        request,
+                        &options,
+                        || RetryPolicy::default().with_retryable_codes(LIST_RETRY_CODES),
+                        |mut client, request| async move { client.list_topics(request).await },
+                    )
+                    .await?;
+                Ok(Page::new(response.topics, response.next_page_token))
+            }
+        })
+    }
+
+    /// Streams all subscriptions of a project, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `project` - in the format `projects/{project}`
+    pub fn stream_subscriptions(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, Subscription> {
+        self.stream_subscriptions_with_options(project, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_subscriptions_with_options(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, Subscription> {
+        let project = project.to_owned();
+        let page_size = list_options.grpc_page_size();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListSubscriptionsRequest {
+                project: project.clone(),
+                page_size,
+                page_token: page_token.unwrap_or_default(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        &self.subscriber_client,
+                        request,
+                        &options,
+                        || RetryPolicy::default().with_retryable_codes(LIST_RETRY_CODES),
+                        |mut client, request| async move {
+                            client.list_subscriptions(request).await
+                        },
+                    )
+                    .await?;
+                Ok(Page::new(response.subscriptions, response.next_page_token))
+            }
+        })
+    }
+
+    /// Streams all schemas of a project, fetching further pages as needed.
+    ///
+    /// Schemas are returned in the basic view, without their definitions.
+    ///
+    /// # Arguments
+    /// * `parent` - in the format `projects/{project}`
+    pub fn stream_schemas(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, Schema> {
+        self.stream_schemas_with_options(parent, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_schemas_with_options(
+        &self,
+        parent: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, Schema> {
+        let parent = parent.to_owned();
+        let page_size = list_options.grpc_page_size();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListSchemasRequest {
+                parent: parent.clone(),
+                view: SchemaView::Basic as i32,
+                page_size,
+                page_token: page_token.unwrap_or_default(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        &self.schema_client,
+                        request,
+                        &options,
+                        || RetryPolicy::default().with_retryable_codes(LIST_RETRY_CODES),
+                        |mut client, request| async move { client.list_schemas(request).await },
+                    )
+                    .await?;
+                Ok(Page::new(response.schemas, response.next_page_token))
+            }
+        })
     }
 }
@@ -0,0 +1,326 @@
+use std::{collections::HashMap, future::Future, sync::Mutex, time::Duration};
+
+use tonic::Code;
+
+use super::{PubSubClient, ACKNOWLEDGE_RETRY_CODES, MODIFY_ACK_DEADLINE_RETRY_CODES};
+use crate::{
+    error::Error,
+    options::CallOptions,
+    proto::google::pubsub::v1::{AcknowledgeRequest, ModifyAckDeadlineRequest},
+    retry::RetryPolicy,
+};
+
+/// The `ErrorInfo` reason of calls that failed for some ack ids, on subscriptions with
+/// exactly-once delivery. Its metadata maps those ack ids to the cause.
+const EXACTLY_ONCE_FAILURE_REASON: &str = "EXACTLY_ONCE_ACKID_FAILURE";
+const TRANSIENT_FAILURE_PREFIX: &str = "TRANSIENT_";
+const INVALID_ACK_ID_FAILURE: &str = "PERMANENT_FAILURE_INVALID_ACK_ID";
+/// The longest ack deadline the service accepts.
+const MAX_ACK_DEADLINE: Duration = Duration::from_secs(600);
+
+/// The outcome of acknowledging, or modifying the ack deadline of, one message.
+///
+/// On subscriptions with exactly-once delivery, an acknowledged message is guaranteed
+/// not to be redelivered only once its status is `Success`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AckStatus {
+    Success,
+    /// The ack id is malformed or its ack deadline expired; the message is redelivered.
+    InvalidAckId,
+    PermissionDenied,
+    /// E.g. the subscription was detached.
+    FailedPrecondition,
+    /// Any other failure, with the cause reported by the service. Includes transient
+    /// failures that persisted after retries.
+    Other(String),
+}
+
+impl AckStatus {
+    pub fn is_success(&self) -> bool {
+        *self == Self::Success
+    }
+}
+
+impl PubSubClient {
+    /// Like [`PubSubClient::acknowledge`], reporting the outcome of each ack id.
+    ///
+    /// On subscriptions with exactly-once delivery, ack ids failing transiently are
+    /// retried according to the retry policy; other failures are reported rather than
+    /// returned as errors. Fails only if the whole call failed, e.g. for a missing
+    /// subscription.
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    /// * `ack_ids`      - acknowledge ids
+    pub async fn acknowledge_confirmed(
+        &self,
+        subscription: &str,
+        ack_ids: Vec<String>,
+    ) -> Result<HashMap<String, AckStatus>, Error> {
+        self.acknowledge_confirmed_with_options(subscription, ack_ids, &CallOptions::default())
+            .await
+    }
+
+    pub async fn acknowledge_confirmed_with_options(
+        &self,
+        subscription: &str,
+        ack_ids: Vec<String>,
+        options: &CallOptions,
+    ) -> Result<HashMap<String, AckStatus>, Error> {
+        let policy = || RetryPolicy::default().with_retryable_codes(ACKNOWLEDGE_RETRY_CODES);
+        self.confirm(ack_ids, options, policy, |ack_ids, options| async move {
+            self.call(
+                &self.subscriber_client,
+                AcknowledgeRequest {
+                    subscription: subscription.to_owned(),
+                    ack_ids,
+                },
+                &options,
+                policy,
+                |mut client, request| async move { client.acknowledge(request).await },
+            )
+            .await
+        })
+        .await
+    }
+
+    /// Like [`PubSubClient::modify_ack_deadline`], reporting the outcome of each ack
+    /// id, see [`PubSubClient::acknowledge_confirmed`].
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    /// * `ack_ids`      - acknowledge ids
+    /// * `ack_deadline` - truncated to whole seconds and clamped to 600 seconds.
+    pub async fn modify_ack_deadline_confirmed(
+        &self,
+        subscription: &str,
+        ack_ids: Vec<String>,
+        ack_deadline: Duration,
+    ) -> Result<HashMap<String, AckStatus>, Error> {
+        self.modify_ack_deadline_confirmed_with_options(
+            subscription,
+            ack_ids,
+            ack_deadline,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn modify_ack_deadline_confirmed_with_options(
+        &self,
+        subscription: &str,
+        ack_ids: Vec<String>,
+        ack_deadline: Duration,
+        options: &CallOptions,
+    ) -> Result<HashMap<String, AckStatus>, Error> {
+        let policy =
+            || RetryPolicy::default().with_retryable_codes(MODIFY_ACK_DEADLINE_RETRY_CODES);
+        self.confirm(ack_ids, options, policy, |ack_ids, options| async move {
+            self.call(
+                &self.subscriber_client,
+                ModifyAckDeadlineRequest {
+                    subscription: subscription.to_owned(),
+                    ack_ids,
+                    ack_deadline_seconds: ack_deadline_seconds(ack_deadline),
+                },
+                &options,
+                policy,
+                |mut client, request| async move { client.modify_ack_deadline(request).await },
+            )
+            .await
+        })
+        .await
+    }
+
+    /// Sends `ack_ids` through `send` until none fails transiently, or the retry
+    /// policy gives up. Each attempt is sent without retries of its own.
+    async fn confirm<F, Fut>(
+        &self,
+        ack_ids: Vec<String>,
+        options: &CallOptions,
+        default_policy: impl FnOnce() -> RetryPolicy,
+        send: F,
+    ) -> Result<HashMap<String, AckStatus>, Error>
+    where
+        F: Fn(Vec<String>, CallOptions) -> Fut,
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        let policy = options
+            .retry_policy()
+            .or(self.retry_policy.as_ref())
+            .cloned()
+            .unwrap_or_else(default_policy);
+        let retryable = policy.clone();
+        let policy = policy.with_retryable(move |error| {
+            retryable.is_retryable(error)
+                || ack_failures(error).is_some_and(|failures| {
+                    failures
+                        .values()
+                        .any(|failure| failure.starts_with(TRANSIENT_FAILURE_PREFIX))
+                })
+        });
+        let attempt_options = options.clone().with_retry_policy(RetryPolicy::none());
+
+        let pending = Mutex::new(ack_ids);
+        let statuses = Mutex::new(HashMap::new());
+        let result = policy
+            .run(|| async {
+                let ack_ids = pending.lock().unwrap().clone();
+                let error = match send(ack_ids.clone(), attempt_options.clone()).await {
+                    Ok(()) => {
+                        settle(&pending, &statuses, ack_ids, AckStatus::Success);
+                        return Ok(());
+                    }
+                    Err(error) => error,
+                };
+                let failures = match ack_failures(&error) {
+                    Some(failures) => failures,
+                    None => {
+                        let status = match &error {
+                            Error::Status(status) if status.code() == Code::PermissionDenied => {
+                                AckStatus::PermissionDenied
+                            }
+                            Error::Status(status) if status.code() == Code::FailedPrecondition => {
+                                AckStatus::FailedPrecondition
+                            }
+                            _ => return Err(error),
+                        };
+                        settle(&pending, &statuses, ack_ids, status);
+                        return Ok(());
+                    }
+                };
+
+                let mut retry = Vec::new();
+                let mut statuses = statuses.lock().unwrap();
+                for ack_id in ack_ids {
+                    let status = match failures.get(&ack_id) {
+                        None => AckStatus::Success,
+                        Some(failure) if failure.starts_with(TRANSIENT_FAILURE_PREFIX) => {
+                            retry.push(ack_id);
+                            continue;
+                        }
+                        Some(failure) if failure == INVALID_ACK_ID_FAILURE => {
+                            AckStatus::InvalidAckId
+                        }
+                        Some(failure) => AckStatus::Other(failure.clone()),
+                    };
+                    statuses.insert(ack_id, status);
+                }
+                let done = retry.is_empty();
+                *pending.lock().unwrap() = retry;
+                if done {
+                    Ok(())
+                } else {
+                    Err(error)
+                }
+            })
+            .await;
+
+        let mut statuses = statuses.into_inner().unwrap();
+        match result {
+            Err(error) if ack_failures(&error).is_some() => {
+                for ack_id in pending.into_inner().unwrap() {
+                    let failure = ack_failures(&error).and_then(|f| f.get(&ack_id)).cloned();
+                    statuses.insert(ack_id, AckStatus::Other(failure.unwrap_or_default()));
+                }
+                Ok(statuses)
+            }
+            Err(error) => Err(error),
+            Ok(()) => Ok(statuses),
+        }
+    }
+}
+
+/// `ack_deadline` in whole seconds as sent to the service, at most 600.
+pub(super) fn ack_deadline_seconds(ack_deadline: Duration) -> i32 {
+    ack_deadline.min(MAX_ACK_DEADLINE).as_secs() as i32
+}
+
+/// Records the same status for every ack id of an attempt.
+fn settle(
+    pending: &Mutex<Vec<String>>,
+    statuses: &Mutex<HashMap<String, AckStatus>>,
+    ack_ids: Vec<String>,
+    status: AckStatus,
+) {
+    pending.lock().unwrap().clear();
+    statuses
+        .lock()
+        .unwrap()
+        .extend(ack_ids.into_iter().map(|ack_id| (ack_id, status.clone())));
+}
+
+/// The failures by ack id reported by a call on a subscription with exactly-once
+/// delivery. Ack ids missing from it succeeded.
+fn ack_failures(error: &Error) -> Option<&HashMap<String, String>> {
+    match error {
+        Error::Status(status) => status
+            .details()
+            .error_info
+            .as_ref()
+            .filter(|info| info.reason == EXACTLY_ONCE_FAILURE_REASON)
+            .map(|info| &info.metadata),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::pubsub::{fake, SubscriptionBuilder};
+
+    const TOPIC: &str = "projects/test/topics/topic";
+    const SUBSCRIPTION: &str = "projects/test/subscriptions/subscription";
+
+    #[test]
+    fn test_ack_deadline_seconds() {
+        assert_eq!(ack_deadline_seconds(Duration::from_millis(10_900)), 10);
+        assert_eq!(ack_deadline_seconds(Duration::from_secs(1 << 32)), 600);
+        assert_eq!(ack_deadline_seconds(Duration::MAX), 600);
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_confirmed() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        client
+            .create_subscription(
+                SubscriptionBuilder::new(SUBSCRIPTION, TOPIC)
+                    .with_exactly_once_delivery(true)
+                    .build(),
+            )
+            .await?;
+        client.publish(TOPIC, "message").await?;
+        let ack_id = client.pull(SUBSCRIPTION).await?.received_messages[0]
+            .ack_id
+            .clone();
+
+        fake.fail_acks_transiently(1);
+        let statuses = client
+            .acknowledge_confirmed(SUBSCRIPTION, vec![ack_id.clone(), "expired".to_owned()])
+            .await?;
+        assert_eq!(
+            statuses,
+            [
+                (ack_id.clone(), AckStatus::Success),
+                ("expired".to_owned(), AckStatus::InvalidAckId),
+            ]
+            .into()
+        );
+        assert_eq!(fake.request_count("Acknowledge"), 2);
+        assert_eq!(fake.unacked(SUBSCRIPTION), 0);
+
+        let statuses = client
+            .modify_ack_deadline_confirmed(SUBSCRIPTION, vec![ack_id.clone()], Duration::ZERO)
+            .await?;
+        assert_eq!(statuses, [(ack_id, AckStatus::InvalidAckId)].into());
+
+        let result = client
+            .acknowledge_confirmed("projects/test/subscriptions/missing", vec![])
+            .await;
+        assert!(matches!(result, Err(error) if error.is_not_found()));
+        Ok(())
+    }
+}
@@ -0,0 +1,700 @@
+use std::time::SystemTime;
+
+use super::{PubSubClient, ADMIN_RETRY_CODES, GET_RETRY_CODES, LIST_RETRY_CODES};
+use crate::{
+    error::Error,
+    options::CallOptions,
+    pagination::{ListOptions, Page, PageStream},
+    proto::google::pubsub::v1::{
+        seek_request::Target, CreateSnapshotRequest, DeleteSnapshotRequest,
+        DeleteSubscriptionRequest, DeleteTopicRequest, DetachSubscriptionRequest,
+        GetSnapshotRequest, GetSubscriptionRequest, GetTopicRequest, ListSnapshotsRequest,
+        ListTopicSubscriptionsRequest, ModifyPushConfigRequest, PushConfig, SeekRequest, Snapshot,
+        Subscription, Topic, UpdateSnapshotRequest, UpdateSubscriptionRequest, UpdateTopicRequest,
+    },
+    retry::RetryPolicy,
+    util::field_mask,
+};
+
+impl PubSubClient {
+    /// # Arguments
+    /// * `topic` - its `name` in the format `projects/{project}/topics/{topic}`
+    pub async fn create_topic(&self, topic: Topic) -> Result<Topic, Error> {
+        self.create_topic_with_options(topic, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_topic_with_options(
+        &self,
+        topic: Topic,
+        options: &CallOptions,
+    ) -> Result<Topic, Error> {
+        self.call(
+            &self.publisher_client,
+            topic,
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.create_topic(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub async fn get_topic(&self, topic: &str) -> Result<Topic, Error> {
+        self.get_topic_with_options(topic, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_topic_with_options(
+        &self,
+        topic: &str,
+        options: &CallOptions,
+    ) -> Result<Topic, Error> {
+        self.call(
+            &self.publisher_client,
+            GetTopicRequest {
+                topic: topic.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(GET_RETRY_CODES),
+            |mut client, request| async move { client.get_topic(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `topic`       - the new settings, identified by `name`.
+    /// * `update_mask` - the fields to update, e.g. `["labels", "message_retention_duration"]`.
+    pub async fn update_topic(&self, topic: Topic, update_mask: &[&str]) -> Result<Topic, Error> {
+        self.update_topic_with_options(topic, update_mask, &CallOptions::default())
+            .await
+    }
+
+    pub async fn update_topic_with_options(
+        &self,
+        topic: Topic,
+        update_mask: &[&str],
+        options: &CallOptions,
+    ) -> Result<Topic, Error> {
+        self.call(
+            &self.publisher_client,
+            UpdateTopicRequest {
+                topic: Some(topic),
+                update_mask: Some(field_mask(update_mask)),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.update_topic(request).await },
+        )
+        .await
+    }
+
+    /// Subscriptions of the topic keep existing, detached from it.
+    ///
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub async fn delete_topic(&self, topic: &str) -> Result<(), Error> {
+        self.delete_topic_with_options(topic, &CallOptions::default())
+            .await
+    }
+
+    pub async fn delete_topic_with_options(
+        &self,
+        topic: &str,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.publisher_client,
+            DeleteTopicRequest {
+                topic: topic.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.delete_topic(request).await },
+        )
+        .await
+    }
+
+    /// Streams the names of the subscriptions attached to a topic.
+    ///
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub fn stream_topic_subscriptions(
+        &self,
+        topic: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, String> {
+        self.stream_topic_subscriptions_with_options(topic, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_topic_subscriptions_with_options(
+        &self,
+        topic: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, String> {
+        let topic = topic.to_owned();
+        let page_size = list_options.grpc_page_size();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListTopicSubscriptionsRequest {
+                topic: topic.clone(),
+                page_size,
+                page_token: page_token.unwrap_or_default(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        &self.publisher_client,
+                        request,
+                        &options,
+                        || RetryPolicy::default().with_retryable_codes(LIST_RETRY_CODES),
+                        |mut client, request| async move {
+                            client.list_topic_subscriptions(request).await
+                        },
+                    )
+                    .await?;
+                Ok(Page::new(response.subscriptions, response.next_page_token))
+            }
+        })
+    }
+
+    /// # Arguments
+    /// * `subscription` - see [`SubscriptionBuilder`](crate::pubsub::SubscriptionBuilder)
+    pub async fn create_subscription(
+        &self,
+        subscription: Subscription,
+    ) -> Result<Subscription, Error> {
+        self.create_subscription_with_options(subscription, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_subscription_with_options(
+        &self,
+        subscription: Subscription,
+        options: &CallOptions,
+    ) -> Result<Subscription, Error> {
+        self.call(
+            &self.subscriber_client,
+            subscription,
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.create_subscription(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    pub async fn get_subscription(&self, subscription: &str) -> Result<Subscription, Error> {
+        self.get_subscription_with_options(subscription, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_subscription_with_options(
+        &self,
+        subscription: &str,
+        options: &CallOptions,
+    ) -> Result<Subscription, Error> {
+        self.call(
+            &self.subscriber_client,
+            GetSubscriptionRequest {
+                subscription: subscription.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(GET_RETRY_CODES),
+            |mut client, request| async move { client.get_subscription(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `subscription` - the new settings, identified by `name`.
+    /// * `update_mask`  - the fields to update, e.g. `["ack_deadline_seconds", "filter"]`.
+    pub async fn update_subscription(
+        &self,
+        subscription: Subscription,
+        update_mask: &[&str],
+    ) -> Result<Subscription, Error> {
+        self.update_subscription_with_options(subscription, update_mask, &CallOptions::default())
+            .await
+    }
+
+    pub async fn update_subscription_with_options(
+        &self,
+        subscription: Subscription,
+        update_mask: &[&str],
+        options: &CallOptions,
+    ) -> Result<Subscription, Error> {
+        self.call(
+            &self.subscriber_client,
+            UpdateSubscriptionRequest {
+                subscription: Some(subscription),
+                update_mask: Some(field_mask(update_mask)),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.update_subscription(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    pub async fn delete_subscription(&self, subscription: &str) -> Result<(), Error> {
+        self.delete_subscription_with_options(subscription, &CallOptions::default())
+            .await
+    }
+
+    pub async fn delete_subscription_with_options(
+        &self,
+        subscription: &str,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.subscriber_client,
+            DeleteSubscriptionRequest {
+                subscription: subscription.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.delete_subscription(request).await },
+        )
+        .await
+    }
+
+    /// Detaches a subscription from its topic. Its messages are dropped and pulls fail
+    /// with `FAILED_PRECONDITION`; the subscription itself is not deleted.
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    pub async fn detach_subscription(&self, subscription: &str) -> Result<(), Error> {
+        self.detach_subscription_with_options(subscription, &CallOptions::default())
+            .await
+    }
+
+    pub async fn detach_subscription_with_options(
+        &self,
+        subscription: &str,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.publisher_client,
+            DetachSubscriptionRequest {
+                subscription: subscription.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.detach_subscription(request).await },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Switches a subscription between push and pull delivery.
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    /// * `push_config`  - `None` to stop pushing, so that messages are pulled instead.
+    pub async fn modify_push_config(
+        &self,
+        subscription: &str,
+        push_config: Option<PushConfig>,
+    ) -> Result<(), Error> {
+        self.modify_push_config_with_options(subscription, push_config, &CallOptions::default())
+            .await
+    }
+
+    pub async fn modify_push_config_with_options(
+        &self,
+        subscription: &str,
+        push_config: Option<PushConfig>,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.subscriber_client,
+            ModifyPushConfigRequest {
+                subscription: subscription.to_owned(),
+                push_config: Some(push_config.unwrap_or_default()),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.modify_push_config(request).await },
+        )
+        .await
+    }
+
+    /// Captures the messages of a subscription not acknowledged yet, and those
+    /// published to its topic afterwards.
+    ///
+    /// # Arguments
+    /// * `snapshot`     - in the format `projects/{project}/snapshots/{snapshot}`
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    pub async fn create_snapshot(
+        &self,
+        snapshot: &str,
+        subscription: &str,
+    ) -> Result<Snapshot, Error> {
+        self.create_snapshot_with_options(snapshot, subscription, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_snapshot_with_options(
+        &self,
+        snapshot: &str,
+        subscription: &str,
+        options: &CallOptions,
+    ) -> Result<Snapshot, Error> {
+        self.call(
+            &self.subscriber_client,
+            CreateSnapshotRequest {
+                name: snapshot.to_owned(),
+                subscription: subscription.to_owned(),
+                labels: Default::default(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.create_snapshot(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `snapshot` - in the format `projects/{project}/snapshots/{snapshot}`
+    pub async fn get_snapshot(&self, snapshot: &str) -> Result<Snapshot, Error> {
+        self.get_snapshot_with_options(snapshot, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_snapshot_with_options(
+        &self,
+        snapshot: &str,
+        options: &CallOptions,
+    ) -> Result<Snapshot, Error> {
+        self.call(
+            &self.subscriber_client,
+            GetSnapshotRequest {
+                snapshot: snapshot.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(GET_RETRY_CODES),
+            |mut client, request| async move { client.get_snapshot(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `snapshot`    - the new settings, identified by `name`.
+    /// * `update_mask` - the fields to update, e.g. `["labels", "expire_time"]`.
+    pub async fn update_snapshot(
+        &self,
+        snapshot: Snapshot,
+        update_mask: &[&str],
+    ) -> Result<Snapshot, Error> {
+        self.update_snapshot_with_options(snapshot, update_mask, &CallOptions::default())
+            .await
+    }
+
+    pub async fn update_snapshot_with_options(
+        &self,
+        snapshot: Snapshot,
+        update_mask: &[&str],
+        options: &CallOptions,
+    ) -> Result<Snapshot, Error> {
+        self.call(
+            &self.subscriber_client,
+            UpdateSnapshotRequest {
+                snapshot: Some(snapshot),
+                update_mask: Some(field_mask(update_mask)),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.update_snapshot(request).await },
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `snapshot` - in the format `projects/{project}/snapshots/{snapshot}`
+    pub async fn delete_snapshot(&self, snapshot: &str) -> Result<(), Error> {
+        self.delete_snapshot_with_options(snapshot, &CallOptions::default())
+            .await
+    }
+
+    pub async fn delete_snapshot_with_options(
+        &self,
+        snapshot: &str,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.subscriber_client,
+            DeleteSnapshotRequest {
+                snapshot: snapshot.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.delete_snapshot(request).await },
+        )
+        .await
+    }
+
+    /// Streams all snapshots of a project, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `project` - in the format `projects/{project}`
+    pub fn stream_snapshots(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, Snapshot> {
+        self.stream_snapshots_with_options(project, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_snapshots_with_options(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, Snapshot> {
+        let project = project.to_owned();
+        let page_size = list_options.grpc_page_size();
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let request = ListSnapshotsRequest {
+                project: project.clone(),
+                page_size,
+                page_token: page_token.unwrap_or_default(),
+            };
+            let options = options.clone();
+            async move {
+                let response = self
+                    .call(
+                        &self.subscriber_client,
+                        request,
+                        &options,
+                        || RetryPolicy::default().with_retryable_codes(LIST_RETRY_CODES),
+                        |mut client, request| async move { client.list_snapshots(request).await },
+                    )
+                    .await?;
+                Ok(Page::new(response.snapshots, response.next_page_token))
+            }
+        })
+    }
+
+    /// Marks the messages published before `time` as acknowledged and the others as
+    /// not acknowledged. Acknowledged messages are only restored if the subscription
+    /// retains them.
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    pub async fn seek_to_time(&self, subscription: &str, time: SystemTime) -> Result<(), Error> {
+        self.seek_to_time_with_options(subscription, time, &CallOptions::default())
+            .await
+    }
+
+    pub async fn seek_to_time_with_options(
+        &self,
+        subscription: &str,
+        time: SystemTime,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.seek(subscription, Target::Time(time.into()), options)
+            .await
+    }
+
+    /// Restores the acknowledgement state captured by a snapshot of the same topic.
+    ///
+    /// # Arguments
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    /// * `snapshot`     - in the format `projects/{project}/snapshots/{snapshot}`
+    pub async fn seek_to_snapshot(&self, subscription: &str, snapshot: &str) -> Result<(), Error> {
+        self.seek_to_snapshot_with_options(subscription, snapshot, &CallOptions::default())
+            .await
+    }
+
+    pub async fn seek_to_snapshot_with_options(
+        &self,
+        subscription: &str,
+        snapshot: &str,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.seek(subscription, Target::Snapshot(snapshot.to_owned()), options)
+            .await
+    }
+
+    async fn seek(
+        &self,
+        subscription: &str,
+        target: Target,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.subscriber_client,
+            SeekRequest {
+                subscription: subscription.to_owned(),
+                target: Some(target),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.seek(request).await },
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use tonic::Code;
+
+    use super::*;
+    use crate::pubsub::{fake, SubscriptionBuilder};
+
+    const PROJECT: &str = "projects/test";
+    const TOPIC: &str = "projects/test/topics/topic";
+    const SUBSCRIPTION: &str = "projects/test/subscriptions/subscription";
+
+    fn topic(name: &str) -> Topic {
+        Topic {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    async fn unacked(client: &PubSubClient) -> anyhow::Result<Vec<Vec<u8>>> {
+        let response = client.pull(SUBSCRIPTION).await?;
+        let mut data = Vec::new();
+        let mut ack_ids = Vec::new();
+        for received in response.received_messages {
+            data.push(received.message.unwrap_or_default().data);
+            ack_ids.push(received.ack_id);
+        }
+        client.acknowledge(SUBSCRIPTION, ack_ids).await?;
+        Ok(data)
+    }
+
+    #[tokio::test]
+    async fn test_topics_and_subscriptions() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        client.create_topic(topic(TOPIC)).await?;
+        let mut update = topic(TOPIC);
+        update.labels.insert("env".to_owned(), "test".to_owned());
+        client.update_topic(update, &["labels"]).await?;
+        assert_eq!(client.get_topic(TOPIC).await?.labels["env"], "test");
+
+        let subscription = SubscriptionBuilder::new(SUBSCRIPTION, TOPIC)
+            .with_ack_deadline(Duration::from_secs(30))
+            .with_push_endpoint("https://example.com/push", None)
+            .build();
+        assert_eq!(
+            client.create_subscription(subscription.clone()).await?,
+            subscription
+        );
+        let mut update = subscription.clone();
+        update.ack_deadline_seconds = 60;
+        client
+            .update_subscription(update, &["ack_deadline_seconds"])
+            .await?;
+        client.modify_push_config(SUBSCRIPTION, None).await?;
+        let subscription = client.get_subscription(SUBSCRIPTION).await?;
+        assert_eq!(subscription.ack_deadline_seconds, 60);
+        assert_eq!(subscription.push_config, None);
+
+        let subscriptions: Vec<_> = client
+            .stream_topic_subscriptions(TOPIC, &ListOptions::new())
+            .items()
+            .try_collect()
+            .await?;
+        assert_eq!(subscriptions, [SUBSCRIPTION]);
+
+        let other = "projects/test/topics/other";
+        client.create_topic(topic(other)).await?;
+        client
+            .create_topic(topic("projects/other/topics/topic"))
+            .await?;
+        let pages: Vec<_> = client
+            .stream_topics(PROJECT, &ListOptions::new().with_page_size(1))
+            .try_collect()
+            .await?;
+        let names: Vec<_> = pages
+            .iter()
+            .map(|page| {
+                page.items
+                    .iter()
+                    .map(|topic| topic.name.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(names, [[other], [TOPIC]]);
+        let subscriptions: Vec<_> = client
+            .stream_subscriptions(PROJECT, &ListOptions::new())
+            .items()
+            .map_ok(|subscription| subscription.name)
+            .try_collect()
+            .await?;
+        assert_eq!(subscriptions, [SUBSCRIPTION]);
+
+        client.publish(TOPIC, "message").await?;
+        client.detach_subscription(SUBSCRIPTION).await?;
+        let result = client.pull(SUBSCRIPTION).await;
+        assert!(
+            matches!(result, Err(Error::Status(status)) if status.code() == Code::FailedPrecondition)
+        );
+
+        client.delete_topic(TOPIC).await?;
+        client.delete_subscription(SUBSCRIPTION).await?;
+        assert!(client.get_topic(TOPIC).await.unwrap_err().is_not_found());
+        assert!(client
+            .get_subscription(SUBSCRIPTION)
+            .await
+            .unwrap_err()
+            .is_not_found());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_and_seek() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        client.create_topic(topic(TOPIC)).await?;
+        client
+            .create_subscription(
+                SubscriptionBuilder::new(SUBSCRIPTION, TOPIC)
+                    .with_retain_acked_messages(true)
+                    .build(),
+            )
+            .await?;
+        client.publish(TOPIC, "a").await?;
+
+        let name = "projects/test/snapshots/snapshot";
+        let snapshot = client.create_snapshot(name, SUBSCRIPTION).await?;
+        assert_eq!(snapshot.topic, TOPIC);
+        let snapshots: Vec<_> = client
+            .stream_snapshots(PROJECT, &ListOptions::new())
+            .items()
+            .try_collect()
+            .await?;
+        assert_eq!(snapshots, [snapshot]);
+
+        client.publish(TOPIC, "b").await?;
+        assert_eq!(unacked(&client).await?, [b"a", b"b"]);
+        assert_eq!(unacked(&client).await?, Vec::<Vec<u8>>::new());
+
+        client.seek_to_snapshot(SUBSCRIPTION, name).await?;
+        assert_eq!(unacked(&client).await?, [b"a"]);
+
+        client
+            .seek_to_time(SUBSCRIPTION, SystemTime::UNIX_EPOCH)
+            .await?;
+        assert_eq!(unacked(&client).await?, [b"a", b"b"]);
+
+        client.delete_snapshot(name).await?;
+        assert!(client.get_snapshot(name).await.unwrap_err().is_not_found());
+        Ok(())
+    }
+}
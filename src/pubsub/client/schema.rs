@@ -0,0 +1,182 @@
+use super::{PubSubClient, ADMIN_RETRY_CODES, GET_RETRY_CODES};
+use crate::{
+    error::Error,
+    options::CallOptions,
+    proto::google::pubsub::v1::{
+        validate_message_request::SchemaSpec, CreateSchemaRequest, DeleteSchemaRequest, Encoding,
+        GetSchemaRequest, Schema, SchemaView, ValidateMessageRequest, ValidateSchemaRequest,
+    },
+    retry::RetryPolicy,
+};
+
+impl PubSubClient {
+    /// # Arguments
+    /// * `parent`    - in the format `projects/{project}`
+    /// * `schema_id` - the last component of the schema name.
+    /// * `schema`    - its `type` and `definition`; `name` is ignored.
+    pub async fn create_schema(
+        &self,
+        parent: &str,
+        schema_id: &str,
+        schema: Schema,
+    ) -> Result<Schema, Error> {
+        self.create_schema_with_options(parent, schema_id, schema, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_schema_with_options(
+        &self,
+        parent: &str,
+        schema_id: &str,
+        schema: Schema,
+        options: &CallOptions,
+    ) -> Result<Schema, Error> {
+        self.call(
+            &self.schema_client,
+            CreateSchemaRequest {
+                parent: parent.to_owned(),
+                schema: Some(schema),
+                schema_id: schema_id.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.create_schema(request).await },
+        )
+        .await
+    }
+
+    /// Gets a schema with its definition.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/{project}/schemas/{schema}`
+    pub async fn get_schema(&self, name: &str) -> Result<Schema, Error> {
+        self.get_schema_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn get_schema_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<Schema, Error> {
+        self.call(
+            &self.schema_client,
+            GetSchemaRequest {
+                name: name.to_owned(),
+                view: SchemaView::Full as i32,
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(GET_RETRY_CODES),
+            |mut client, request| async move { client.get_schema(request).await },
+        )
+        .await
+    }
+
+    /// Topics using the schema keep existing; publishing to them fails.
+    ///
+    /// # Arguments
+    /// * `name` - in the format `projects/{project}/schemas/{schema}`
+    pub async fn delete_schema(&self, name: &str) -> Result<(), Error> {
+        self.delete_schema_with_options(name, &CallOptions::default())
+            .await
+    }
+
+    pub async fn delete_schema_with_options(
+        &self,
+        name: &str,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.schema_client,
+            DeleteSchemaRequest {
+                name: name.to_owned(),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(ADMIN_RETRY_CODES),
+            |mut client, request| async move { client.delete_schema(request).await },
+        )
+        .await
+    }
+
+    /// Checks a schema definition without creating it. Fails with `INVALID_ARGUMENT`
+    /// if the definition is invalid.
+    ///
+    /// # Arguments
+    /// * `parent` - in the format `projects/{project}`
+    pub async fn validate_schema(&self, parent: &str, schema: Schema) -> Result<(), Error> {
+        self.validate_schema_with_options(parent, schema, &CallOptions::default())
+            .await
+    }
+
+    pub async fn validate_schema_with_options(
+        &self,
+        parent: &str,
+        schema: Schema,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.schema_client,
+            ValidateSchemaRequest {
+                parent: parent.to_owned(),
+                schema: Some(schema),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(GET_RETRY_CODES),
+            |mut client, request| async move { client.validate_schema(request).await },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Checks that message data conforms to an existing schema. Fails with
+    /// `INVALID_ARGUMENT` if it does not.
+    ///
+    /// See [`SchemaValidator`](crate::pubsub::SchemaValidator) to validate
+    /// messages locally instead.
+    ///
+    /// # Arguments
+    /// * `parent`   - in the format `projects/{project}`
+    /// * `schema`   - in the format `projects/{project}/schemas/{schema}`
+    /// * `message`  - the message data.
+    /// * `encoding` - the encoding of `message`.
+    pub async fn validate_message(
+        &self,
+        parent: &str,
+        schema: &str,
+        message: impl Into<Vec<u8>>,
+        encoding: Encoding,
+    ) -> Result<(), Error> {
+        self.validate_message_with_options(
+            parent,
+            schema,
+            message,
+            encoding,
+            &CallOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn validate_message_with_options(
+        &self,
+        parent: &str,
+        schema: &str,
+        message: impl Into<Vec<u8>>,
+        encoding: Encoding,
+        options: &CallOptions,
+    ) -> Result<(), Error> {
+        self.call(
+            &self.schema_client,
+            ValidateMessageRequest {
+                parent: parent.to_owned(),
+                message: message.into(),
+                encoding: encoding as i32,
+                schema_spec: Some(SchemaSpec::Name(schema.to_owned())),
+            },
+            options,
+            || RetryPolicy::default().with_retryable_codes(GET_RETRY_CODES),
+            |mut client, request| async move { client.validate_message(request).await },
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! Encoding of typed payloads as message data, see
+//! [`TypedPublisher`](super::TypedPublisher) and [`TypedSubscriber`](super::TypedSubscriber).
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{error::Error, pubsub::client::PubSubError};
+
+/// The attribute naming the codec of a message, set by
+/// [`TypedPublisher`](super::TypedPublisher) and checked when decoding.
+pub const CONTENT_TYPE_ATTRIBUTE: &str = "content-type";
+
+/// Converts values of `T` to and from message data.
+pub trait MessageCodec<T>: Send + Sync + 'static {
+    /// The MIME type stored in the [`CONTENT_TYPE_ATTRIBUTE`].
+    fn content_type(&self) -> &str;
+
+    /// Fails with [`PubSubError::Encode`].
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error>;
+
+    /// Fails with [`PubSubError::Decode`].
+    fn decode(&self, data: &[u8]) -> Result<T, Error>;
+}
+
+/// Encodes values as JSON with serde.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl<T: Serialize + DeserializeOwned> MessageCodec<T> for JsonCodec {
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::to_vec(value).map_err(|e| PubSubError::Encode(e.to_string()))?)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, Error> {
+        Ok(serde_json::from_slice(data).map_err(|e| PubSubError::Decode(e.to_string()))?)
+    }
+}
+
+/// Encodes prost messages in the protobuf binary format.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProtobufCodec;
+
+impl<T: prost::Message + Default> MessageCodec<T> for ProtobufCodec {
+    fn content_type(&self) -> &str {
+        "application/x-protobuf"
+    }
+
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        Ok(value.encode_to_vec())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, Error> {
+        Ok(T::decode(data).map_err(|e| PubSubError::Decode(e.to_string()))?)
+    }
+}
+
+/// Encodes values with bincode, enabled by the `bincode` feature.
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeCodec;
+
+#[cfg(feature = "bincode")]
+impl<T: Serialize + DeserializeOwned> MessageCodec<T> for BincodeCodec {
+    fn content_type(&self) -> &str {
+        "application/x-bincode"
+    }
+
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(value).map_err(|e| PubSubError::Encode(e.to_string()))?)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, Error> {
+        Ok(bincode::deserialize(data).map_err(|e| PubSubError::Decode(e.to_string()))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::proto::google::pubsub::v1::PubsubMessage;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        id: String,
+        amount: i64,
+    }
+
+    #[test]
+    fn test_json() -> anyhow::Result<()> {
+        let order = Order {
+            id: "o-1".to_owned(),
+            amount: 3,
+        };
+        let data = JsonCodec.encode(&order)?;
+        assert_eq!(data, br#"{"id":"o-1","amount":3}"#);
+        assert_eq!(MessageCodec::<Order>::decode(&JsonCodec, &data)?, order);
+
+        let error = MessageCodec::<Order>::decode(&JsonCodec, b"{}").unwrap_err();
+        assert!(matches!(error, Error::PubSub(PubSubError::Decode(_))));
+        Ok(())
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode() -> anyhow::Result<()> {
+        let order = Order {
+            id: "o-1".to_owned(),
+            amount: 3,
+        };
+        let data = BincodeCodec.encode(&order)?;
+        assert_eq!(MessageCodec::<Order>::decode(&BincodeCodec, &data)?, order);
+        assert_eq!(
+            MessageCodec::<Order>::content_type(&BincodeCodec),
+            "application/x-bincode"
+        );
+
+        let error = MessageCodec::<Order>::decode(&BincodeCodec, &data[..4]).unwrap_err();
+        assert!(matches!(error, Error::PubSub(PubSubError::Decode(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_protobuf() -> anyhow::Result<()> {
+        let message = PubsubMessage {
+            data: b"data".to_vec(),
+            ordering_key: "key".to_owned(),
+            ..Default::default()
+        };
+        let data = ProtobufCodec.encode(&message)?;
+        assert_eq!(
+            MessageCodec::<PubsubMessage>::decode(&ProtobufCodec, &data)?,
+            message
+        );
+
+        let error = MessageCodec::<PubsubMessage>::decode(&ProtobufCodec, b"\xff").unwrap_err();
+        assert!(matches!(error, Error::PubSub(PubSubError::Decode(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,1045 @@
+//! An in-memory Pub/Sub server for tests, enabled by the `pubsub-fake` feature.
+//!
+//! It implements the Publisher, Subscriber and SchemaService APIs on a local port, so
+//! code using [`PubSubClient`] can be tested without the service or its emulator.
+//! Messages published to a topic are copied to each of its subscriptions, leased to
+//! one puller at a time and redelivered once their ack deadline expires. Streaming
+//! pulls apply `max_outstanding_messages` per stream but ignore `max_outstanding_bytes`.
+//!
+//! ```ignore
+//! let (client, fake) = google_apis_ex::pubsub::fake::start().await?;
+//! fake.create_topic("projects/test/topics/topic");
+//! fake.create_subscription("projects/test/subscriptions/subscription", "projects/test/topics/topic");
+//! client.publish("projects/test/topics/topic", "message").await?;
+//! ```
+
+// Helpers share the error type of the gRPC handlers they serve.
+#![allow(clippy::result_large_err)]
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant, SystemTime},
+};
+
+use futures::{stream::BoxStream, SinkExt};
+use prost::Message as _;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+
+use crate::{
+    auth::NoAuth,
+    endpoint::Endpoint,
+    error::Error,
+    proto::google::pubsub::v1::{
+        publisher_server::{Publisher, PublisherServer},
+        schema_service_server::{SchemaService, SchemaServiceServer},
+        subscriber_server::{Subscriber, SubscriberServer},
+        *,
+    },
+    proto::google::rpc,
+    pubsub::{PubSubClient, SchemaValidator},
+};
+
+type Reply<T> = Result<Response<T>, Status>;
+
+const DEFAULT_ACK_DEADLINE_SECONDS: i32 = 10;
+/// The topic of subscriptions whose topic was deleted.
+const DELETED_TOPIC: &str = "_deleted-topic_";
+
+/// Clones share the same state.
+#[derive(Clone, Default)]
+pub struct FakePubSub {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    topics: BTreeMap<String, Topic>,
+    subscriptions: BTreeMap<String, SubscriptionState>,
+    next_id: u64,
+    /// Number of requests received, by method.
+    requests: BTreeMap<&'static str, usize>,
+    /// Open streaming pulls fail with `UNAVAILABLE` once this changes.
+    stream_generation: u64,
+    /// Snapshots with the messages they captured.
+    snapshots: BTreeMap<String, (Snapshot, Vec<Delivery>)>,
+    schemas: BTreeMap<String, Schema>,
+    /// Calls acknowledging messages of subscriptions with exactly-once delivery that
+    /// fail transiently for every ack id.
+    transient_ack_failures: usize,
+}
+
+struct SubscriptionState {
+    subscription: Subscription,
+    backlog: VecDeque<Delivery>,
+    /// Deliveries by ack id, with their ack deadline.
+    leased: BTreeMap<String, (Delivery, Instant)>,
+    /// Acknowledged messages, kept if `retain_acked_messages` is set.
+    acked: Vec<Delivery>,
+}
+
+#[derive(Clone)]
+struct Delivery {
+    message: PubsubMessage,
+    attempts: i32,
+}
+
+impl Delivery {
+    /// Ids are assigned in publish order.
+    fn id(&self) -> u64 {
+        self.message.message_id.parse().unwrap_or_default()
+    }
+}
+
+/// Starts a fake server on a random local port and returns a client connected to it.
+pub async fn start() -> Result<(PubSubClient, FakePubSub), Error> {
+    let fake = FakePubSub::default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = Endpoint::new(&format!("http://{}", listener.local_addr()?))?;
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(PublisherServer::new(fake.clone()))
+            .add_service(SubscriberServer::new(fake.clone()))
+            .add_service(SchemaServiceServer::new(fake.clone()))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let client = PubSubClient::builder()
+        .with_token_provider(NoAuth)
+        .with_endpoint(endpoint)
+        .build()
+        .await?;
+    Ok((client, fake))
+}
+
+impl FakePubSub {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn record(&self, method: &'static str) {
+        *self.state().requests.entry(method).or_default() += 1;
+    }
+
+    /// Requests received by a gRPC method, e.g. `"Publish"`.
+    pub fn request_count(&self, method: &str) -> usize {
+        self.state()
+            .requests
+            .get(method)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn create_topic(&self, name: &str) {
+        self.state().topics.insert(
+            name.to_owned(),
+            Topic {
+                name: name.to_owned(),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn create_subscription(&self, name: &str, topic: &str) {
+        self.state().subscriptions.insert(
+            name.to_owned(),
+            SubscriptionState::new(Subscription {
+                name: name.to_owned(),
+                topic: topic.to_owned(),
+                ack_deadline_seconds: DEFAULT_ACK_DEADLINE_SECONDS,
+                ..Default::default()
+            }),
+        );
+    }
+
+    /// Messages of a subscription that were not acknowledged yet.
+    pub fn unacked(&self, subscription: &str) -> usize {
+        self.state()
+            .subscriptions
+            .get(subscription)
+            .map_or(0, |state| state.backlog.len() + state.leased.len())
+    }
+
+    /// Makes the next `calls` acknowledgements on subscriptions with exactly-once
+    /// delivery fail transiently, as a `TRANSIENT_FAILURE_*` for each ack id.
+    pub fn fail_acks_transiently(&self, calls: usize) {
+        self.state().transient_ack_failures = calls;
+    }
+
+    /// Makes every open streaming pull fail with `UNAVAILABLE`.
+    pub fn disconnect_streams(&self) {
+        self.state().stream_generation += 1;
+    }
+}
+
+impl State {
+    fn subscription_mut(&mut self, name: &str) -> Result<&mut SubscriptionState, Status> {
+        self.subscriptions
+            .get_mut(name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))
+    }
+
+    fn validator(&self, schema: &str, encoding: i32) -> Result<SchemaValidator, Status> {
+        let schema = self
+            .schemas
+            .get(schema)
+            .ok_or_else(|| Status::not_found(format!("{} not found", schema)))?;
+        let encoding = Encoding::from_i32(encoding).unwrap_or(Encoding::Unspecified);
+        SchemaValidator::new(schema, encoding).map_err(|e| Status::invalid_argument(e.to_string()))
+    }
+
+    /// On subscriptions with exactly-once delivery, the ack ids that cannot be
+    /// acknowledged, with the cause.
+    fn ack_failures(
+        &mut self,
+        subscription: &str,
+        ack_ids: &[String],
+    ) -> Result<HashMap<String, String>, Status> {
+        let transient = self.transient_ack_failures > 0;
+        let state = self.subscription_mut(subscription)?;
+        if !state.subscription.enable_exactly_once_delivery {
+            return Ok(HashMap::new());
+        }
+        state.expire();
+        let failures = ack_ids
+            .iter()
+            .filter_map(|ack_id| {
+                let failure = if transient {
+                    "TRANSIENT_FAILURE_UNORDERED_ACK_ID"
+                } else if !state.leased.contains_key(ack_id) {
+                    "PERMANENT_FAILURE_INVALID_ACK_ID"
+                } else {
+                    return None;
+                };
+                Some((ack_id.clone(), failure.to_owned()))
+            })
+            .collect();
+        if transient {
+            self.transient_ack_failures -= 1;
+        }
+        Ok(failures)
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Leases up to `max_messages` messages, `0` meaning no limit.
+    fn pull(
+        &mut self,
+        subscription: &str,
+        max_messages: usize,
+        ack_deadline: Duration,
+    ) -> Result<Vec<ReceivedMessage>, Status> {
+        let first_id = self.next_id;
+        let state = self.subscription_mut(subscription)?;
+        if state.subscription.detached {
+            return Err(Status::failed_precondition(format!(
+                "{} is detached",
+                subscription
+            )));
+        }
+        state.expire();
+        let max_messages = if max_messages == 0 {
+            usize::MAX
+        } else {
+            max_messages
+        };
+        let with_attempts = state.subscription.dead_letter_policy.is_some();
+
+        let mut received = Vec::new();
+        while received.len() < max_messages {
+            let mut delivery = match state.backlog.pop_front() {
+                Some(delivery) => delivery,
+                None => break,
+            };
+            delivery.attempts += 1;
+            let ack_id = format!(
+                "{}-{}",
+                delivery.message.message_id,
+                first_id + 1 + received.len() as u64
+            );
+            received.push(ReceivedMessage {
+                ack_id: ack_id.clone(),
+                message: Some(delivery.message.clone()),
+                delivery_attempt: if with_attempts { delivery.attempts } else { 0 },
+            });
+            state
+                .leased
+                .insert(ack_id, (delivery, Instant::now() + ack_deadline));
+        }
+        self.next_id += received.len() as u64;
+        Ok(received)
+    }
+}
+
+impl SubscriptionState {
+    fn new(subscription: Subscription) -> Self {
+        Self {
+            subscription,
+            backlog: VecDeque::new(),
+            leased: BTreeMap::new(),
+            acked: Vec::new(),
+        }
+    }
+
+    /// Messages not acknowledged yet.
+    fn unacked(&self) -> Vec<Delivery> {
+        let leased = self.leased.values().map(|(delivery, _)| delivery);
+        let mut unacked: Vec<_> = leased.chain(&self.backlog).cloned().collect();
+        unacked.sort_by_key(Delivery::id);
+        unacked
+    }
+
+    fn seek(&mut self, unacked: Vec<Delivery>) {
+        self.leased.clear();
+        self.acked
+            .retain(|acked| unacked.iter().all(|delivery| delivery.id() != acked.id()));
+        self.backlog = unacked.into();
+    }
+
+    fn ack_deadline(&self) -> Duration {
+        Duration::from_secs(self.subscription.ack_deadline_seconds.max(1) as u64)
+    }
+
+    /// Returns messages whose ack deadline passed to the backlog.
+    fn expire(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<_> = self
+            .leased
+            .iter()
+            .filter(|(_, (_, deadline))| *deadline <= now)
+            .map(|(ack_id, _)| ack_id.clone())
+            .collect();
+        for ack_id in expired {
+            let (delivery, _) = self.leased.remove(&ack_id).unwrap();
+            self.backlog.push_back(delivery);
+        }
+    }
+
+    fn acknowledge(&mut self, ack_ids: &[String]) {
+        for ack_id in ack_ids {
+            if let Some((delivery, _)) = self.leased.remove(ack_id) {
+                if self.subscription.retain_acked_messages {
+                    self.acked.push(delivery);
+                }
+            }
+        }
+    }
+
+    fn modify_ack_deadline(&mut self, ack_ids: &[String], seconds: i32) {
+        let deadline = Instant::now() + Duration::from_secs(seconds.max(0) as u64);
+        for ack_id in ack_ids {
+            if let Some((_, current)) = self.leased.get_mut(ack_id) {
+                *current = deadline;
+            }
+        }
+        self.expire();
+    }
+}
+
+/// Fails if some ack ids failed, with the causes in the `ErrorInfo` as the service
+/// reports them.
+fn ack_result(failures: HashMap<String, String>) -> Reply<()> {
+    if failures.is_empty() {
+        return Ok(Response::new(()));
+    }
+    let error_info = rpc::ErrorInfo {
+        reason: "EXACTLY_ONCE_ACKID_FAILURE".to_owned(),
+        domain: "pubsub.googleapis.com".to_owned(),
+        metadata: failures,
+    };
+    let status = rpc::Status {
+        code: tonic::Code::InvalidArgument as i32,
+        message: "some ack ids failed".to_owned(),
+        details: vec![prost_types::Any {
+            type_url: "type.googleapis.com/google.rpc.ErrorInfo".to_owned(),
+            value: error_info.encode_to_vec(),
+        }],
+    };
+    Err(Status::with_details(
+        tonic::Code::InvalidArgument,
+        status.message.clone(),
+        status.encode_to_vec().into(),
+    ))
+}
+
+/// Splits `items` into pages of `page_size`, all of them if `0`. Page tokens are the
+/// offset of the page.
+fn page<T>(
+    items: impl Iterator<Item = T>,
+    page_size: i32,
+    page_token: &str,
+) -> Result<(Vec<T>, String), Status> {
+    let offset = match page_token {
+        "" => 0,
+        token => token
+            .parse()
+            .map_err(|_| Status::invalid_argument(format!("invalid page token {}", token)))?,
+    };
+    let page_size = match page_size {
+        0 => usize::MAX,
+        size => usize::try_from(size)
+            .map_err(|_| Status::invalid_argument(format!("invalid page size {}", size)))?,
+    };
+    let mut items = items.skip(offset).peekable();
+    let page: Vec<_> = items.by_ref().take(page_size).collect();
+    let next_page_token = match items.peek() {
+        Some(_) => (offset + page.len()).to_string(),
+        None => String::new(),
+    };
+    Ok((page, next_page_token))
+}
+
+#[tonic::async_trait]
+impl Publisher for FakePubSub {
+    async fn create_topic(&self, request: Request<Topic>) -> Reply<Topic> {
+        let topic = request.into_inner();
+        let mut state = self.state();
+        if state.topics.contains_key(&topic.name) {
+            return Err(Status::already_exists(format!(
+                "{} already exists",
+                topic.name
+            )));
+        }
+        state.topics.insert(topic.name.clone(), topic.clone());
+        Ok(Response::new(topic))
+    }
+
+    async fn update_topic(&self, request: Request<UpdateTopicRequest>) -> Reply<Topic> {
+        let request = request.into_inner();
+        let update = request.topic.unwrap_or_default();
+        let mut state = self.state();
+        let topic = state
+            .topics
+            .get_mut(&update.name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", update.name)))?;
+        for path in request.update_mask.unwrap_or_default().paths {
+            match path.as_str() {
+                "labels" => topic.labels = update.labels.clone(),
+                "message_retention_duration" => {
+                    topic.message_retention_duration = update.message_retention_duration.clone()
+                }
+                "schema_settings" => topic.schema_settings = update.schema_settings.clone(),
+                path => return Err(Status::invalid_argument(format!("cannot update {}", path))),
+            }
+        }
+        Ok(Response::new(topic.clone()))
+    }
+
+    async fn publish(&self, request: Request<PublishRequest>) -> Reply<PublishResponse> {
+        self.record("Publish");
+        let request = request.into_inner();
+        let mut state = self.state();
+        let topic = state
+            .topics
+            .get(&request.topic)
+            .ok_or_else(|| Status::not_found(format!("{} not found", request.topic)))?;
+        if let Some(settings) = &topic.schema_settings {
+            let validator = state.validator(&settings.schema, settings.encoding)?;
+            for message in &request.messages {
+                validator
+                    .validate(&message.data)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+            }
+        }
+
+        let mut message_ids = Vec::new();
+        for mut message in request.messages {
+            message.message_id = state.next_id().to_string();
+            message.publish_time = Some(SystemTime::now().into());
+            for subscription in state.subscriptions.values_mut() {
+                if subscription.subscription.topic == request.topic {
+                    subscription.backlog.push_back(Delivery {
+                        message: message.clone(),
+                        attempts: 0,
+                    });
+                }
+            }
+            message_ids.push(message.message_id);
+        }
+        Ok(Response::new(PublishResponse { message_ids }))
+    }
+
+    async fn get_topic(&self, request: Request<GetTopicRequest>) -> Reply<Topic> {
+        let name = request.into_inner().topic;
+        self.state()
+            .topics
+            .get(&name)
+            .cloned()
+            .map(Response::new)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))
+    }
+
+    async fn list_topics(&self, request: Request<ListTopicsRequest>) -> Reply<ListTopicsResponse> {
+        let request = request.into_inner();
+        let prefix = format!("{}/topics/", request.project);
+        let (topics, next_page_token) = page(
+            self.state()
+                .topics
+                .values()
+                .filter(|topic| topic.name.starts_with(&prefix))
+                .cloned(),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListTopicsResponse {
+            topics,
+            next_page_token,
+        }))
+    }
+
+    async fn list_topic_subscriptions(
+        &self,
+        request: Request<ListTopicSubscriptionsRequest>,
+    ) -> Reply<ListTopicSubscriptionsResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        if !state.topics.contains_key(&request.topic) {
+            return Err(Status::not_found(format!("{} not found", request.topic)));
+        }
+        let (subscriptions, next_page_token) = page(
+            state
+                .subscriptions
+                .values()
+                .filter(|state| state.subscription.topic == request.topic)
+                .map(|state| state.subscription.name.clone()),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListTopicSubscriptionsResponse {
+            subscriptions,
+            next_page_token,
+        }))
+    }
+
+    async fn list_topic_snapshots(
+        &self,
+        request: Request<ListTopicSnapshotsRequest>,
+    ) -> Reply<ListTopicSnapshotsResponse> {
+        let request = request.into_inner();
+        let state = self.state();
+        if !state.topics.contains_key(&request.topic) {
+            return Err(Status::not_found(format!("{} not found", request.topic)));
+        }
+        let (snapshots, next_page_token) = page(
+            state
+                .snapshots
+                .values()
+                .filter(|(snapshot, _)| snapshot.topic == request.topic)
+                .map(|(snapshot, _)| snapshot.name.clone()),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListTopicSnapshotsResponse {
+            snapshots,
+            next_page_token,
+        }))
+    }
+
+    async fn delete_topic(&self, request: Request<DeleteTopicRequest>) -> Reply<()> {
+        let topic = request.into_inner().topic;
+        let mut state = self.state();
+        if state.topics.remove(&topic).is_none() {
+            return Err(Status::not_found(format!("{} not found", topic)));
+        }
+        for state in state.subscriptions.values_mut() {
+            if state.subscription.topic == topic {
+                state.subscription.topic = DELETED_TOPIC.to_owned();
+            }
+        }
+        Ok(Response::new(()))
+    }
+
+    async fn detach_subscription(
+        &self,
+        request: Request<DetachSubscriptionRequest>,
+    ) -> Reply<DetachSubscriptionResponse> {
+        let name = request.into_inner().subscription;
+        let mut state = self.state();
+        let state = state.subscription_mut(&name)?;
+        state.subscription.detached = true;
+        state.seek(Vec::new());
+        Ok(Response::new(DetachSubscriptionResponse {}))
+    }
+}
+
+#[tonic::async_trait]
+impl Subscriber for FakePubSub {
+    async fn create_subscription(&self, request: Request<Subscription>) -> Reply<Subscription> {
+        let mut subscription = request.into_inner();
+        let mut state = self.state();
+        if state.subscriptions.contains_key(&subscription.name) {
+            return Err(Status::already_exists(format!(
+                "{} already exists",
+                subscription.name
+            )));
+        }
+        if !state.topics.contains_key(&subscription.topic) {
+            return Err(Status::not_found(format!(
+                "{} not found",
+                subscription.topic
+            )));
+        }
+        if subscription.ack_deadline_seconds == 0 {
+            subscription.ack_deadline_seconds = DEFAULT_ACK_DEADLINE_SECONDS;
+        }
+        state.subscriptions.insert(
+            subscription.name.clone(),
+            SubscriptionState::new(subscription.clone()),
+        );
+        Ok(Response::new(subscription))
+    }
+
+    async fn get_subscription(
+        &self,
+        request: Request<GetSubscriptionRequest>,
+    ) -> Reply<Subscription> {
+        let name = request.into_inner().subscription;
+        Ok(Response::new(
+            self.state().subscription_mut(&name)?.subscription.clone(),
+        ))
+    }
+
+    async fn update_subscription(
+        &self,
+        request: Request<UpdateSubscriptionRequest>,
+    ) -> Reply<Subscription> {
+        let request = request.into_inner();
+        let update = request.subscription.unwrap_or_default();
+        let mut state = self.state();
+        let subscription = &mut state.subscription_mut(&update.name)?.subscription;
+        for path in request.update_mask.unwrap_or_default().paths {
+            match path.as_str() {
+                "ack_deadline_seconds" => {
+                    subscription.ack_deadline_seconds = update.ack_deadline_seconds
+                }
+                "labels" => subscription.labels = update.labels.clone(),
+                "retain_acked_messages" => {
+                    subscription.retain_acked_messages = update.retain_acked_messages
+                }
+                "message_retention_duration" => {
+                    subscription.message_retention_duration =
+                        update.message_retention_duration.clone()
+                }
+                "push_config" => subscription.push_config = update.push_config.clone(),
+                "dead_letter_policy" => {
+                    subscription.dead_letter_policy = update.dead_letter_policy.clone()
+                }
+                "retry_policy" => subscription.retry_policy = update.retry_policy.clone(),
+                "enable_exactly_once_delivery" => {
+                    subscription.enable_exactly_once_delivery = update.enable_exactly_once_delivery
+                }
+                "expiration_policy" => {
+                    subscription.expiration_policy = update.expiration_policy.clone()
+                }
+                path => return Err(Status::invalid_argument(format!("cannot update {}", path))),
+            }
+        }
+        Ok(Response::new(subscription.clone()))
+    }
+
+    async fn list_subscriptions(
+        &self,
+        request: Request<ListSubscriptionsRequest>,
+    ) -> Reply<ListSubscriptionsResponse> {
+        let request = request.into_inner();
+        let prefix = format!("{}/subscriptions/", request.project);
+        let (subscriptions, next_page_token) = page(
+            self.state()
+                .subscriptions
+                .values()
+                .filter(|state| state.subscription.name.starts_with(&prefix))
+                .map(|state| state.subscription.clone()),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListSubscriptionsResponse {
+            subscriptions,
+            next_page_token,
+        }))
+    }
+
+    async fn delete_subscription(&self, request: Request<DeleteSubscriptionRequest>) -> Reply<()> {
+        let name = request.into_inner().subscription;
+        self.state()
+            .subscriptions
+            .remove(&name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+        Ok(Response::new(()))
+    }
+
+    async fn modify_ack_deadline(&self, request: Request<ModifyAckDeadlineRequest>) -> Reply<()> {
+        self.record("ModifyAckDeadline");
+        let request = request.into_inner();
+        let mut state = self.state();
+        let failures = state.ack_failures(&request.subscription, &request.ack_ids)?;
+        let ack_ids: Vec<_> = request
+            .ack_ids
+            .into_iter()
+            .filter(|ack_id| !failures.contains_key(ack_id))
+            .collect();
+        state
+            .subscription_mut(&request.subscription)?
+            .modify_ack_deadline(&ack_ids, request.ack_deadline_seconds);
+        ack_result(failures)
+    }
+
+    async fn acknowledge(&self, request: Request<AcknowledgeRequest>) -> Reply<()> {
+        self.record("Acknowledge");
+        let request = request.into_inner();
+        let mut state = self.state();
+        let failures = state.ack_failures(&request.subscription, &request.ack_ids)?;
+        let ack_ids: Vec<_> = request
+            .ack_ids
+            .into_iter()
+            .filter(|ack_id| !failures.contains_key(ack_id))
+            .collect();
+        state
+            .subscription_mut(&request.subscription)?
+            .acknowledge(&ack_ids);
+        ack_result(failures)
+    }
+
+    async fn pull(&self, request: Request<PullRequest>) -> Reply<PullResponse> {
+        self.record("Pull");
+        let request = request.into_inner();
+        let mut state = self.state();
+        let ack_deadline = state
+            .subscription_mut(&request.subscription)?
+            .ack_deadline();
+        let received_messages = state.pull(
+            &request.subscription,
+            request.max_messages.max(1) as usize,
+            ack_deadline,
+        )?;
+        Ok(Response::new(PullResponse { received_messages }))
+    }
+
+    type StreamingPullStream = BoxStream<'static, Result<StreamingPullResponse, Status>>;
+
+    async fn streaming_pull(
+        &self,
+        request: Request<Streaming<StreamingPullRequest>>,
+    ) -> Reply<Self::StreamingPullStream> {
+        self.record("StreamingPull");
+        let mut requests = request.into_inner();
+        let first = requests
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("missing initial request"))?;
+        let subscription = first.subscription;
+        let generation = {
+            let mut state = self.state();
+            state.subscription_mut(&subscription)?;
+            state.stream_generation
+        };
+        let ack_deadline = Duration::from_secs(first.stream_ack_deadline_seconds.max(1) as u64);
+        // Flow control by bytes is not implemented, only by messages.
+        let max_outstanding = first.max_outstanding_messages.max(0) as usize;
+
+        let (mut tx, rx) = futures::channel::mpsc::channel(16);
+        let fake = self.clone();
+        tokio::spawn(async move {
+            // Ack ids leased to this stream, the only ones its flow control counts.
+            let mut leases = HashSet::new();
+            let mut interval = tokio::time::interval(Duration::from_millis(10));
+            loop {
+                tokio::select! {
+                    request = requests.message() => match request {
+                        Ok(Some(request)) => {
+                            let mut state = fake.state();
+                            if let Ok(state) = state.subscription_mut(&subscription) {
+                                state.acknowledge(&request.ack_ids);
+                                for (ack_id, seconds) in request
+                                    .modify_deadline_ack_ids
+                                    .iter()
+                                    .zip(request.modify_deadline_seconds)
+                                {
+                                    state.modify_ack_deadline(std::slice::from_ref(ack_id), seconds);
+                                }
+                            }
+                        }
+                        _ => return,
+                    },
+                    _ = interval.tick() => {
+                        let response = {
+                            let mut state = fake.state();
+                            if state.stream_generation != generation {
+                                Err(Status::unavailable("stream reset"))
+                            } else {
+                                if let Ok(state) = state.subscription_mut(&subscription) {
+                                    state.expire();
+                                    leases.retain(|ack_id| state.leased.contains_key(ack_id));
+                                }
+                                match max_outstanding {
+                                    0 => state.pull(&subscription, 0, ack_deadline),
+                                    max if leases.len() < max => {
+                                        state.pull(&subscription, max - leases.len(), ack_deadline)
+                                    }
+                                    _ => Ok(Vec::new()),
+                                }
+                            }
+                        };
+                        match response {
+                            Ok(received_messages) if received_messages.is_empty() => {}
+                            Ok(received_messages) => {
+                                leases.extend(received_messages.iter().map(|m| m.ack_id.clone()));
+                                let response = StreamingPullResponse {
+                                    received_messages,
+                                    ..Default::default()
+                                };
+                                if tx.send(Ok(response)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(status) => {
+                                let _ = tx.send(Err(status)).await;
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(rx)))
+    }
+
+    async fn modify_push_config(&self, request: Request<ModifyPushConfigRequest>) -> Reply<()> {
+        let request = request.into_inner();
+        let push_config = request
+            .push_config
+            .filter(|push_config| !push_config.push_endpoint.is_empty());
+        self.state()
+            .subscription_mut(&request.subscription)?
+            .subscription
+            .push_config = push_config;
+        Ok(Response::new(()))
+    }
+
+    async fn get_snapshot(&self, request: Request<GetSnapshotRequest>) -> Reply<Snapshot> {
+        let name = request.into_inner().snapshot;
+        self.state()
+            .snapshots
+            .get(&name)
+            .map(|(snapshot, _)| Response::new(snapshot.clone()))
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))
+    }
+
+    async fn list_snapshots(
+        &self,
+        request: Request<ListSnapshotsRequest>,
+    ) -> Reply<ListSnapshotsResponse> {
+        let request = request.into_inner();
+        let prefix = format!("{}/snapshots/", request.project);
+        let (snapshots, next_page_token) = page(
+            self.state()
+                .snapshots
+                .iter()
+                .filter(|(name, _)| name.starts_with(&prefix))
+                .map(|(_, (snapshot, _))| snapshot.clone()),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListSnapshotsResponse {
+            snapshots,
+            next_page_token,
+        }))
+    }
+
+    async fn create_snapshot(&self, request: Request<CreateSnapshotRequest>) -> Reply<Snapshot> {
+        let request = request.into_inner();
+        let mut state = self.state();
+        if state.snapshots.contains_key(&request.name) {
+            return Err(Status::already_exists(format!(
+                "{} already exists",
+                request.name
+            )));
+        }
+        let subscription = state.subscription_mut(&request.subscription)?;
+        let snapshot = Snapshot {
+            name: request.name.clone(),
+            topic: subscription.subscription.topic.clone(),
+            expire_time: Some((SystemTime::now() + Duration::from_secs(7 * 86400)).into()),
+            labels: request.labels,
+        };
+        let unacked = subscription.unacked();
+        state
+            .snapshots
+            .insert(request.name, (snapshot.clone(), unacked));
+        Ok(Response::new(snapshot))
+    }
+
+    async fn update_snapshot(&self, request: Request<UpdateSnapshotRequest>) -> Reply<Snapshot> {
+        let request = request.into_inner();
+        let update = request.snapshot.unwrap_or_default();
+        let mut state = self.state();
+        let (snapshot, _) = state
+            .snapshots
+            .get_mut(&update.name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", update.name)))?;
+        for path in request.update_mask.unwrap_or_default().paths {
+            match path.as_str() {
+                "labels" => snapshot.labels = update.labels.clone(),
+                "expire_time" => snapshot.expire_time = update.expire_time.clone(),
+                path => return Err(Status::invalid_argument(format!("cannot update {}", path))),
+            }
+        }
+        Ok(Response::new(snapshot.clone()))
+    }
+
+    async fn delete_snapshot(&self, request: Request<DeleteSnapshotRequest>) -> Reply<()> {
+        let name = request.into_inner().snapshot;
+        self.state()
+            .snapshots
+            .remove(&name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+        Ok(Response::new(()))
+    }
+
+    async fn seek(&self, request: Request<SeekRequest>) -> Reply<SeekResponse> {
+        let request = request.into_inner();
+        let mut state = self.state();
+        match request.target {
+            Some(seek_request::Target::Snapshot(name)) => {
+                let (snapshot, unacked) = state
+                    .snapshots
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+                let subscription = state.subscription_mut(&request.subscription)?;
+                if subscription.subscription.topic != snapshot.topic {
+                    return Err(Status::failed_precondition(
+                        "the snapshot is of another topic",
+                    ));
+                }
+                subscription.seek(unacked);
+            }
+            Some(seek_request::Target::Time(time)) => {
+                let time = SystemTime::try_from(time)
+                    .map_err(|_| Status::invalid_argument("invalid time"))?;
+                let subscription = state.subscription_mut(&request.subscription)?;
+                let mut retained = subscription.unacked();
+                retained.append(&mut subscription.acked);
+                retained.sort_by_key(Delivery::id);
+                retained.dedup_by_key(|delivery| delivery.id());
+                let (acked, unacked) = retained.into_iter().partition(|delivery| {
+                    let published = delivery.message.publish_time.clone().unwrap_or_default();
+                    SystemTime::try_from(published).map_or(true, |published| published < time)
+                });
+                subscription.seek(unacked);
+                if subscription.subscription.retain_acked_messages {
+                    subscription.acked = acked;
+                }
+            }
+            None => return Err(Status::invalid_argument("missing target")),
+        }
+        Ok(Response::new(SeekResponse {}))
+    }
+}
+
+#[tonic::async_trait]
+impl SchemaService for FakePubSub {
+    async fn create_schema(&self, request: Request<CreateSchemaRequest>) -> Reply<Schema> {
+        let request = request.into_inner();
+        let mut schema = request.schema.unwrap_or_default();
+        schema.name = format!("{}/schemas/{}", request.parent, request.schema_id);
+        SchemaValidator::new(&schema, Encoding::Json)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let mut state = self.state();
+        if state.schemas.contains_key(&schema.name) {
+            return Err(Status::already_exists(format!(
+                "{} already exists",
+                schema.name
+            )));
+        }
+        state.schemas.insert(schema.name.clone(), schema.clone());
+        Ok(Response::new(schema))
+    }
+
+    async fn get_schema(&self, request: Request<GetSchemaRequest>) -> Reply<Schema> {
+        let request = request.into_inner();
+        let mut schema = self
+            .state()
+            .schemas
+            .get(&request.name)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("{} not found", request.name)))?;
+        if request.view != SchemaView::Full as i32 {
+            schema.definition.clear();
+        }
+        Ok(Response::new(schema))
+    }
+
+    async fn list_schemas(
+        &self,
+        request: Request<ListSchemasRequest>,
+    ) -> Reply<ListSchemasResponse> {
+        let request = request.into_inner();
+        let prefix = format!("{}/schemas/", request.parent);
+        let (schemas, next_page_token) = page(
+            self.state()
+                .schemas
+                .values()
+                .filter(|schema| schema.name.starts_with(&prefix))
+                .map(|schema| Schema {
+                    definition: match request.view == SchemaView::Full as i32 {
+                        true => schema.definition.clone(),
+                        false => String::new(),
+                    },
+                    ..schema.clone()
+                }),
+            request.page_size,
+            &request.page_token,
+        )?;
+        Ok(Response::new(ListSchemasResponse {
+            schemas,
+            next_page_token,
+        }))
+    }
+
+    async fn delete_schema(&self, request: Request<DeleteSchemaRequest>) -> Reply<()> {
+        let name = request.into_inner().name;
+        self.state()
+            .schemas
+            .remove(&name)
+            .ok_or_else(|| Status::not_found(format!("{} not found", name)))?;
+        Ok(Response::new(()))
+    }
+
+    async fn validate_schema(
+        &self,
+        request: Request<ValidateSchemaRequest>,
+    ) -> Reply<ValidateSchemaResponse> {
+        let schema = request.into_inner().schema.unwrap_or_default();
+        SchemaValidator::new(&schema, Encoding::Json)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(ValidateSchemaResponse {}))
+    }
+
+    async fn validate_message(
+        &self,
+        request: Request<ValidateMessageRequest>,
+    ) -> Reply<ValidateMessageResponse> {
+        let request = request.into_inner();
+        let validator = match request.schema_spec {
+            Some(validate_message_request::SchemaSpec::Name(name)) => {
+                self.state().validator(&name, request.encoding)?
+            }
+            Some(validate_message_request::SchemaSpec::Schema(schema)) => {
+                let encoding =
+                    Encoding::from_i32(request.encoding).unwrap_or(Encoding::Unspecified);
+                SchemaValidator::new(&schema, encoding)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?
+            }
+            None => return Err(Status::invalid_argument("missing schema")),
+        };
+        validator
+            .validate(&request.message)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(ValidateMessageResponse {}))
+    }
+}
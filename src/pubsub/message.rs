@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use crate::proto::google::pubsub::v1::PubsubMessage;
+
+/// Builds the [`PubsubMessage`] passed to
+/// [`PubSubClient::publish_message`](super::PubSubClient::publish_message) and
+/// [`Publisher::publish_message`](super::Publisher::publish_message).
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    data: Vec<u8>,
+    attributes: HashMap<String, String>,
+    ordering_key: String,
+}
+
+impl MessageBuilder {
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            data: data.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Keys must not start with `goog`, which is reserved.
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_attributes<K, V>(mut self, attributes: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.attributes.extend(
+            attributes
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Messages sharing an ordering key are delivered in the order they were published,
+    /// to subscriptions with message ordering enabled. Publishing them in order requires
+    /// a [`Publisher`](super::Publisher) with message ordering enabled.
+    pub fn with_ordering_key(mut self, ordering_key: impl Into<String>) -> Self {
+        self.ordering_key = ordering_key.into();
+        self
+    }
+
+    pub fn build(self) -> PubsubMessage {
+        PubsubMessage {
+            data: self.data,
+            attributes: self.attributes,
+            ordering_key: self.ordering_key,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,802 @@
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use prost::Message as _;
+use tokio::{
+    sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore},
+    task::{JoinHandle, JoinSet},
+    time::Instant,
+};
+
+use crate::{
+    error::Error,
+    proto::google::pubsub::v1::PubsubMessage,
+    pubsub::{client::PubSubError, PubSubClient},
+};
+
+const DEFAULT_MAX_MESSAGES: usize = 100;
+const DEFAULT_MAX_BYTES: usize = 1_000_000;
+const DEFAULT_MAX_DELAY: Duration = Duration::from_millis(10);
+const DEFAULT_MAX_OUTSTANDING_MESSAGES: usize = 1000;
+const DEFAULT_MAX_OUTSTANDING_BYTES: usize = 100 * 1024 * 1024;
+/// The most messages a single `Publish` request may carry.
+const MAX_MESSAGES_PER_REQUEST: usize = 1000;
+/// The largest `Publish` request accepted.
+const MAX_REQUEST_BYTES: usize = 10_000_000;
+/// How long a batcher waits without messages before stopping; one is started again
+/// for the next message of its topic and ordering key.
+const BATCHER_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Publishes messages in batches.
+///
+/// Messages are buffered per topic and ordering key, and sent once a batch holds `max_messages`
+/// messages or `max_bytes` bytes, or `max_delay` after its first message. Clones share
+/// the same buffers.
+///
+/// Call [`Publisher::shutdown`] to send buffered messages before exiting; dropping the
+/// last clone sends them in the background.
+#[derive(Clone)]
+pub struct Publisher {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: PubSubClient,
+    settings: BatchSettings,
+    message_ordering: bool,
+    max_outstanding_bytes: usize,
+    outstanding_messages: Arc<Semaphore>,
+    outstanding_bytes: Arc<Semaphore>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Clone, Copy)]
+struct BatchSettings {
+    max_messages: usize,
+    max_bytes: usize,
+    max_delay: Duration,
+    idle_timeout: Duration,
+}
+
+/// A topic and an ordering key, empty for unordered messages.
+type BatchKey = (String, String);
+
+#[derive(Default)]
+struct State {
+    closed: bool,
+    batchers: HashMap<BatchKey, Batcher>,
+    /// Ordering keys whose messages fail until resumed.
+    paused: HashSet<BatchKey>,
+}
+
+struct Batcher {
+    sender: mpsc::UnboundedSender<Pending>,
+    task: JoinHandle<()>,
+}
+
+/// A buffered message, holding its share of the outstanding limits until it is sent.
+struct Pending {
+    message: PubsubMessage,
+    result: oneshot::Sender<Result<String, Error>>,
+    _permits: (OwnedSemaphorePermit, OwnedSemaphorePermit),
+}
+
+impl Pending {
+    fn fail(self, error: impl Into<Error>) {
+        let _ = self.result.send(Err(error.into()));
+    }
+}
+
+pub struct PublisherBuilder {
+    client: PubSubClient,
+    settings: BatchSettings,
+    message_ordering: bool,
+    max_outstanding_messages: usize,
+    max_outstanding_bytes: usize,
+}
+
+impl PublisherBuilder {
+    /// Messages per batch, at most 1000. Defaults to 100.
+    pub fn with_max_messages(mut self, max_messages: usize) -> Self {
+        self.settings.max_messages = max_messages.clamp(1, MAX_MESSAGES_PER_REQUEST);
+        self
+    }
+
+    /// Bytes per batch, counting the whole `Publish` request, at most 10 MB. Defaults
+    /// to 1 MB.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.settings.max_bytes = max_bytes.clamp(1, MAX_REQUEST_BYTES);
+        self
+    }
+
+    /// How long the first message of a batch waits for others. Defaults to 10 ms.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.settings.max_delay = max_delay;
+        self
+    }
+
+    /// Messages buffered or being sent, across topics, before [`Publisher::publish`]
+    /// waits. Defaults to 1000.
+    pub fn with_max_outstanding_messages(mut self, max_outstanding_messages: usize) -> Self {
+        self.max_outstanding_messages = max_outstanding_messages.max(1);
+        self
+    }
+
+    /// Bytes buffered or being sent, across topics, before [`Publisher::publish`]
+    /// waits. Defaults to 100 MiB.
+    pub fn with_max_outstanding_bytes(mut self, max_outstanding_bytes: usize) -> Self {
+        self.max_outstanding_bytes = max_outstanding_bytes.clamp(1, u32::MAX as usize);
+        self
+    }
+
+    /// Sends the messages of each ordering key one batch at a time, so that they are
+    /// stored in the order they were published. After a failure, messages with that
+    /// key fail with [`PubSubError::OrderingKeyPaused`] until
+    /// [`Publisher::resume_publish`] is called. Messages without ordering key are
+    /// batched as usual.
+    ///
+    /// Without it, publishing a message with an ordering key fails with
+    /// [`PubSubError::OrderingNotEnabled`].
+    pub fn with_message_ordering(mut self, message_ordering: bool) -> Self {
+        self.message_ordering = message_ordering;
+        self
+    }
+
+    pub fn build(self) -> Publisher {
+        Publisher {
+            inner: Arc::new(Inner {
+                client: self.client,
+                settings: self.settings,
+                message_ordering: self.message_ordering,
+                max_outstanding_bytes: self.max_outstanding_bytes,
+                outstanding_messages: Arc::new(Semaphore::new(self.max_outstanding_messages)),
+                outstanding_bytes: Arc::new(Semaphore::new(self.max_outstanding_bytes)),
+                state: Default::default(),
+            }),
+        }
+    }
+}
+
+impl Publisher {
+    pub fn new(client: PubSubClient) -> Self {
+        Self::builder(client).build()
+    }
+
+    pub fn builder(client: PubSubClient) -> PublisherBuilder {
+        PublisherBuilder {
+            client,
+            settings: BatchSettings {
+                max_messages: DEFAULT_MAX_MESSAGES,
+                max_bytes: DEFAULT_MAX_BYTES,
+                max_delay: DEFAULT_MAX_DELAY,
+                idle_timeout: BATCHER_IDLE_TIMEOUT,
+            },
+            message_ordering: false,
+            max_outstanding_messages: DEFAULT_MAX_OUTSTANDING_MESSAGES,
+            max_outstanding_bytes: DEFAULT_MAX_OUTSTANDING_BYTES,
+        }
+    }
+
+    pub fn client(&self) -> &PubSubClient {
+        &self.inner.client
+    }
+
+    /// Buffers a message, waiting first while the outstanding limits are reached.
+    ///
+    /// The returned handle resolves to the message id once the batch was sent.
+    ///
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub async fn publish(&self, topic: &str, data: impl Into<Vec<u8>>) -> PublishHandle {
+        let message = PubsubMessage {
+            data: data.into(),
+            ..Default::default()
+        };
+        self.publish_message(topic, message).await
+    }
+
+    /// Like [`Publisher::publish`], for messages with attributes or an ordering key,
+    /// see [`MessageBuilder`](super::MessageBuilder).
+    pub async fn publish_message(&self, topic: &str, message: PubsubMessage) -> PublishHandle {
+        let (result, receiver) = oneshot::channel();
+        let handle = PublishHandle { receiver };
+        if !self.inner.message_ordering && !message.ordering_key.is_empty() {
+            let _ = result.send(Err(PubSubError::OrderingNotEnabled.into()));
+            return handle;
+        }
+
+        let size = message.encoded_len().min(self.inner.max_outstanding_bytes);
+        // The semaphores are never closed.
+        let permits = (
+            self.inner
+                .outstanding_messages
+                .clone()
+                .acquire_owned()
+                .await
+                .unwrap(),
+            self.inner
+                .outstanding_bytes
+                .clone()
+                .acquire_many_owned(size as u32)
+                .await
+                .unwrap(),
+        );
+        let key = (topic.to_owned(), message.ordering_key.clone());
+        let pending = Pending {
+            message,
+            result,
+            _permits: permits,
+        };
+
+        let mut state = self.inner.state.lock().unwrap();
+        if state.closed {
+            pending.fail(PubSubError::PublisherClosed);
+        } else if state.paused.contains(&key) {
+            pending.fail(PubSubError::OrderingKeyPaused(key.1));
+        } else {
+            let batcher = state
+                .batchers
+                .entry(key.clone())
+                .or_insert_with(|| Batcher::spawn(&self.inner, key));
+            // The task only stops once its sender is dropped, or after removing itself
+            // from `batchers` under this lock.
+            let _ = batcher.sender.send(pending);
+        }
+        handle
+    }
+
+    /// Lets messages with an ordering key be published again after a failure.
+    pub fn resume_publish(&self, topic: &str, ordering_key: &str) {
+        self.inner
+            .state
+            .lock()
+            .unwrap()
+            .paused
+            .remove(&(topic.to_owned(), ordering_key.to_owned()));
+    }
+
+    /// Sends every buffered message and waits for the responses. Later calls to
+    /// [`Publisher::publish`] fail with [`PubSubError::PublisherClosed`].
+    pub async fn shutdown(&self) {
+        let batchers = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.closed = true;
+            mem::take(&mut state.batchers)
+        };
+        for (_, batcher) in batchers {
+            drop(batcher.sender);
+            let _ = batcher.task.await;
+        }
+    }
+}
+
+impl Batcher {
+    fn spawn(inner: &Inner, key: BatchKey) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(batch(
+            inner.client.clone(),
+            key,
+            inner.settings,
+            // Not an `Arc`: the task must not keep `batchers`, and so its own sender,
+            // alive once the publisher is dropped.
+            Arc::downgrade(&inner.state),
+            receiver,
+        ));
+        Self { sender, task }
+    }
+}
+
+/// The messages of a batch being filled.
+struct Batch {
+    pending: Vec<Pending>,
+    /// The encoded size of the `PublishRequest` carrying the batch.
+    bytes: usize,
+    /// The size of the request without messages.
+    overhead: usize,
+    deadline: Option<Instant>,
+}
+
+impl Batch {
+    fn new(topic: &str) -> Self {
+        let overhead = prost::encoding::string::encoded_len(1, &topic.to_owned());
+        Self {
+            pending: Vec::new(),
+            bytes: overhead,
+            overhead,
+            deadline: None,
+        }
+    }
+
+    /// Adds a message, moving the batches that are full to `ready`.
+    fn push(&mut self, pending: Pending, settings: &BatchSettings, ready: &mut Vec<Vec<Pending>>) {
+        // Messages are a repeated field of the request, framed by a tag and length.
+        let size = prost::encoding::message::encoded_len(2, &pending.message);
+        if !self.pending.is_empty() && self.bytes + size > settings.max_bytes {
+            ready.push(self.take());
+        }
+        if self.pending.is_empty() {
+            self.deadline = Some(Instant::now() + settings.max_delay);
+        }
+        self.pending.push(pending);
+        self.bytes += size;
+        if self.pending.len() >= settings.max_messages || self.bytes >= settings.max_bytes {
+            ready.push(self.take());
+        }
+    }
+
+    fn take(&mut self) -> Vec<Pending> {
+        self.bytes = self.overhead;
+        self.deadline = None;
+        mem::take(&mut self.pending)
+    }
+}
+
+/// Groups messages into batches until the sender is dropped, or the batcher was idle
+/// for `idle_timeout`, then sends what is left.
+///
+/// Batches of unordered messages are sent concurrently; those of an ordering key one
+/// after the other, stopping at the first failure.
+async fn batch(
+    client: PubSubClient,
+    key: BatchKey,
+    settings: BatchSettings,
+    state: Weak<Mutex<State>>,
+    mut receiver: mpsc::UnboundedReceiver<Pending>,
+) {
+    let ordered = !key.1.is_empty();
+    let mut requests = JoinSet::new();
+    let mut batch = Batch::new(&key.0);
+    let mut closed = false;
+
+    while !closed {
+        let mut ready = Vec::new();
+        let deadline = batch
+            .deadline
+            .unwrap_or_else(|| Instant::now() + settings.idle_timeout);
+        tokio::select! {
+            pending = receiver.recv() => match pending {
+                Some(pending) => batch.push(pending, &settings, &mut ready),
+                None => closed = true,
+            },
+            // Reap sent batches so the set does not grow without bound.
+            Some(_) = requests.join_next() => {}
+            _ = tokio::time::sleep_until(deadline) => match batch.deadline {
+                Some(_) => ready.push(batch.take()),
+                None => match evict(&state, &key, &mut receiver) {
+                    Some(pending) => batch.push(pending, &settings, &mut ready),
+                    None => closed = true,
+                },
+            },
+        }
+        if closed && !batch.pending.is_empty() {
+            ready.push(batch.take());
+        }
+
+        if !ordered {
+            for pending in ready {
+                requests.spawn(send_batch(client.clone(), key.0.clone(), pending));
+            }
+            continue;
+        }
+        let mut ready = ready.into_iter();
+        while let Some(pending) = ready.next() {
+            if send_batch(client.clone(), key.0.clone(), pending)
+                .await
+                .is_err()
+            {
+                pause(&state, key, receiver, ready.flatten().chain(batch.take())).await;
+                return;
+            }
+        }
+    }
+    while requests.join_next().await.is_some() {}
+}
+
+/// Removes an idle batcher from `batchers`, unless a message arrived meanwhile, which
+/// is returned instead.
+fn evict(
+    state: &Weak<Mutex<State>>,
+    key: &BatchKey,
+    receiver: &mut mpsc::UnboundedReceiver<Pending>,
+) -> Option<Pending> {
+    let state = state.upgrade();
+    // Messages are only sent under this lock, so none arrives once the batcher is
+    // removed.
+    let mut state = state.as_ref().map(|state| state.lock().unwrap());
+    if let Ok(pending) = receiver.try_recv() {
+        return Some(pending);
+    }
+    if let Some(state) = &mut state {
+        state.batchers.remove(key);
+    }
+    None
+}
+
+/// Fails every message left for an ordering key, and those published until it is
+/// resumed.
+async fn pause(
+    state: &Weak<Mutex<State>>,
+    key: BatchKey,
+    mut receiver: mpsc::UnboundedReceiver<Pending>,
+    unsent: impl Iterator<Item = Pending>,
+) {
+    if let Some(state) = state.upgrade() {
+        let mut state = state.lock().unwrap();
+        state.paused.insert(key.clone());
+        state.batchers.remove(&key);
+    }
+    // Nothing is sent once the batcher is removed, under the same lock.
+    receiver.close();
+    for pending in unsent {
+        pending.fail(PubSubError::OrderingKeyPaused(key.1.clone()));
+    }
+    while let Some(pending) = receiver.recv().await {
+        pending.fail(PubSubError::OrderingKeyPaused(key.1.clone()));
+    }
+}
+
+async fn send_batch(client: PubSubClient, topic: String, batch: Vec<Pending>) -> Result<(), ()> {
+    let messages = batch.iter().map(|p| p.message.clone()).collect();
+    let result = client.publish_messages(&topic, messages).await;
+    complete(batch, result.map(|response| response.message_ids))
+}
+
+/// Resolves the handles of a sent batch, failing all of them unless there is one
+/// message id per message.
+fn complete(batch: Vec<Pending>, message_ids: Result<Vec<String>, Error>) -> Result<(), ()> {
+    let error = match message_ids {
+        Ok(message_ids) if message_ids.len() == batch.len() => {
+            for (pending, message_id) in batch.into_iter().zip(message_ids) {
+                let _ = pending.result.send(Ok(message_id));
+            }
+            return Ok(());
+        }
+        Ok(message_ids) => PubSubError::MessageIdMismatch {
+            sent: batch.len(),
+            received: message_ids.len(),
+        }
+        .into(),
+        Err(error) => error,
+    };
+    let error = Arc::new(error);
+    for pending in batch {
+        pending.fail(PubSubError::Publish(error.clone()));
+    }
+    Err(())
+}
+
+/// Resolves to the id of a published message.
+#[must_use = "the message id or error is only known by awaiting the handle"]
+pub struct PublishHandle {
+    receiver: oneshot::Receiver<Result<String, Error>>,
+}
+
+impl Future for PublishHandle {
+    type Output = Result<String, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx).map(|result| {
+            // Every message is resolved once its batch is sent, so the sender is only
+            // dropped without a result if the batcher task is aborted, as when the
+            // runtime shuts down.
+            result.unwrap_or_else(|_| Err(PubSubError::PublisherClosed.into()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use futures::future::try_join_all;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        proto::google::pubsub::v1::PublishRequest,
+        pubsub::{fake, MessageBuilder},
+    };
+
+    const TOPIC: &str = "projects/test/topics/topic";
+    const SUBSCRIPTION: &str = "projects/test/subscriptions/subscription";
+
+    #[tokio::test]
+    async fn test_batching() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        fake.create_subscription(SUBSCRIPTION, TOPIC);
+        let publisher = Publisher::builder(client)
+            .with_max_messages(5)
+            .with_max_delay(Duration::from_secs(3600))
+            .build();
+
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            handles.push(publisher.publish(TOPIC, format!("message {}", i)).await);
+        }
+        let message_ids: HashSet<_> = try_join_all(handles).await?.into_iter().collect();
+
+        assert_eq!(message_ids.len(), 10);
+        assert_eq!(fake.request_count("Publish"), 2);
+        assert_eq!(fake.unacked(SUBSCRIPTION), 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_thresholds() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+
+        // Sent together once the delay passes.
+        let publisher = Publisher::new(client.clone());
+        let handles = vec![
+            publisher.publish(TOPIC, "a").await,
+            publisher.publish(TOPIC, "b").await,
+        ];
+        try_join_all(handles).await?;
+        assert_eq!(fake.request_count("Publish"), 1);
+
+        // Each message fills a batch on its own.
+        let publisher = Publisher::builder(client)
+            .with_max_bytes(100)
+            .with_max_delay(Duration::from_secs(3600))
+            .build();
+        let handles = vec![
+            publisher.publish(TOPIC, vec![0; 100]).await,
+            publisher.publish(TOPIC, vec![1; 100]).await,
+        ];
+        try_join_all(handles).await?;
+        assert_eq!(fake.request_count("Publish"), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_back_pressure_and_shutdown() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        let publisher = Publisher::builder(client)
+            .with_max_delay(Duration::from_secs(3600))
+            .with_max_outstanding_messages(2)
+            .build();
+
+        let handles = vec![
+            publisher.publish(TOPIC, "a").await,
+            publisher.publish(TOPIC, "b").await,
+        ];
+        let blocked =
+            tokio::time::timeout(Duration::from_millis(50), publisher.publish(TOPIC, "c")).await;
+        assert!(blocked.is_err());
+        assert_eq!(fake.request_count("Publish"), 0);
+
+        publisher.shutdown().await;
+        assert_eq!(try_join_all(handles).await?.len(), 2);
+        assert_eq!(fake.request_count("Publish"), 1);
+
+        let result = publisher.publish(TOPIC, "d").await.await;
+        assert!(matches!(
+            result,
+            Err(Error::PubSub(PubSubError::PublisherClosed))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drop_sends_buffered_messages() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        let publisher = Publisher::builder(client)
+            .with_max_delay(Duration::from_secs(3600))
+            .build();
+
+        let handle = publisher.publish(TOPIC, "a").await;
+        drop(publisher);
+        handle.await?;
+        assert_eq!(fake.request_count("Publish"), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_idle_batchers_stop() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        let mut builder = Publisher::builder(client).with_message_ordering(true);
+        builder.settings.idle_timeout = Duration::from_millis(500);
+        let publisher = builder.build();
+
+        let handles = futures::future::join_all(["", "a", "b"].map(|key| {
+            let message = MessageBuilder::new(key).with_ordering_key(key).build();
+            publisher.publish_message(TOPIC, message)
+        }))
+        .await;
+        assert_eq!(publisher.inner.state.lock().unwrap().batchers.len(), 3);
+        try_join_all(handles).await?;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !publisher.inner.state.lock().unwrap().batchers.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await?;
+
+        let message = MessageBuilder::new("again").with_ordering_key("a").build();
+        publisher.publish_message(TOPIC, message).await.await?;
+        assert_eq!(fake.request_count("Publish"), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_publish_error() -> anyhow::Result<()> {
+        let (client, _fake) = fake::start().await?;
+        let publisher = Publisher::new(client);
+        let topic = "projects/test/topics/missing";
+
+        let handles = vec![
+            publisher.publish(topic, "a").await,
+            publisher.publish(topic, "b").await,
+        ];
+        for handle in handles {
+            assert!(matches!(
+                handle.await,
+                Err(Error::PubSub(PubSubError::Publish(error))) if error.is_not_found()
+            ));
+        }
+        Ok(())
+    }
+
+    /// A message as queued by [`Publisher::publish_message`], and its handle.
+    fn pending(message: PubsubMessage) -> (Pending, PublishHandle) {
+        let semaphore = Arc::new(Semaphore::new(2));
+        let (result, receiver) = oneshot::channel();
+        let permits = (
+            semaphore.clone().try_acquire_owned().unwrap(),
+            semaphore.try_acquire_owned().unwrap(),
+        );
+        let pending = Pending {
+            message,
+            result,
+            _permits: permits,
+        };
+        (pending, PublishHandle { receiver })
+    }
+
+    #[test]
+    fn test_batch_counts_request_size() {
+        let settings = BatchSettings {
+            max_messages: 10,
+            max_bytes: 204,
+            max_delay: Duration::from_secs(1),
+            idle_timeout: Duration::from_secs(1),
+        };
+        let message = PubsubMessage {
+            data: vec![0; 100],
+            ..Default::default()
+        };
+        let mut batch = Batch::new(TOPIC);
+        let mut ready = Vec::new();
+        // Two messages of 102 bytes do not fit once the request is accounted for.
+        for _ in 0..2 {
+            batch.push(pending(message.clone()).0, &settings, &mut ready);
+        }
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].len(), 1);
+
+        let request = PublishRequest {
+            topic: TOPIC.to_owned(),
+            messages: vec![message],
+        };
+        assert_eq!(batch.bytes, request.encoded_len());
+    }
+
+    #[tokio::test]
+    async fn test_missing_message_ids() -> anyhow::Result<()> {
+        let (batch, handles): (Vec<_>, Vec<_>) =
+            (0..2).map(|_| pending(PubsubMessage::default())).unzip();
+
+        assert!(complete(batch, Ok(vec!["1".to_owned()])).is_err());
+        for handle in handles {
+            assert!(matches!(
+                handle.await,
+                Err(Error::PubSub(PubSubError::Publish(error))) if matches!(
+                    *error,
+                    Error::PubSub(PubSubError::MessageIdMismatch { sent: 2, received: 1 })
+                )
+            ));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_message_ordering() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        fake.create_subscription(SUBSCRIPTION, TOPIC);
+        let publisher = Publisher::builder(client.clone())
+            .with_max_messages(3)
+            .with_message_ordering(true)
+            .build();
+
+        let mut handles = Vec::new();
+        for i in 0..20 {
+            let message = MessageBuilder::new(format!("{}", i))
+                .with_attribute("index", i.to_string())
+                .with_ordering_key(["a", "b"][i % 2])
+                .build();
+            handles.push(publisher.publish_message(TOPIC, message).await);
+        }
+        try_join_all(handles).await?;
+
+        let response = client.pull(SUBSCRIPTION).await?;
+        let mut received: HashMap<_, Vec<_>> = HashMap::new();
+        for received_message in response.received_messages {
+            let message = received_message.message.unwrap();
+            assert_eq!(message.attributes["index"].as_bytes(), message.data);
+            received
+                .entry(message.ordering_key)
+                .or_default()
+                .push(String::from_utf8(message.data)?.parse::<usize>()?);
+        }
+        assert_eq!(received["a"], (0..20).step_by(2).collect::<Vec<_>>());
+        assert_eq!(received["b"], (1..20).step_by(2).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ordering_key_paused() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        let publisher = Publisher::builder(client.clone())
+            .with_max_messages(1)
+            .with_message_ordering(true)
+            .build();
+        let message = |data: &str, ordering_key: &str| {
+            MessageBuilder::new(data)
+                .with_ordering_key(ordering_key)
+                .build()
+        };
+
+        // The topic does not exist yet.
+        let first = publisher.publish_message(TOPIC, message("1", "key")).await;
+        let second = publisher.publish_message(TOPIC, message("2", "key")).await;
+        assert!(matches!(
+            first.await,
+            Err(Error::PubSub(PubSubError::Publish(error))) if error.is_not_found()
+        ));
+        assert!(matches!(
+            second.await,
+            Err(Error::PubSub(PubSubError::OrderingKeyPaused(key))) if key == "key"
+        ));
+
+        fake.create_topic(TOPIC);
+        let paused = publisher.publish_message(TOPIC, message("3", "key")).await;
+        assert!(matches!(
+            paused.await,
+            Err(Error::PubSub(PubSubError::OrderingKeyPaused(_)))
+        ));
+        publisher
+            .publish_message(TOPIC, message("3", "other"))
+            .await
+            .await?;
+
+        publisher.resume_publish(TOPIC, "key");
+        publisher
+            .publish_message(TOPIC, message("4", "key"))
+            .await
+            .await?;
+
+        let unordered = Publisher::new(client);
+        let result = unordered
+            .publish_message(TOPIC, message("5", "key"))
+            .await
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::PubSub(PubSubError::OrderingNotEnabled))
+        ));
+        Ok(())
+    }
+}
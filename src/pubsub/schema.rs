@@ -0,0 +1,218 @@
+//! Schemas that messages published to a topic must conform to.
+//!
+//! [`SchemaValidator`] encodes and validates messages locally, following the Avro and
+//! protobuf specifications, so that mismatches surface before the service rejects
+//! them. [`SchemaPublisher`] does so for every message published to a topic.
+
+mod avro;
+mod protobuf;
+
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use self::{avro::AvroSchema, protobuf::ProtoSchema};
+pub use crate::proto::google::pubsub::v1::{schema::Type as SchemaType, Encoding, Schema};
+use crate::{
+    error::Error,
+    proto::google::pubsub::v1::PubsubMessage,
+    pubsub::{client::PubSubError, PublishHandle, Publisher},
+};
+
+/// How deeply binary data may nest values, so that crafted messages cannot overflow
+/// the stack while being validated.
+const MAX_DEPTH: usize = 100;
+
+/// Encodes and validates messages for a schema and encoding.
+pub struct SchemaValidator {
+    definition: Definition,
+    json: bool,
+}
+
+enum Definition {
+    Avro(AvroSchema),
+    Protobuf(ProtoSchema),
+}
+
+impl SchemaValidator {
+    /// Fails with [`PubSubError::InvalidSchema`] if the definition cannot be parsed.
+    ///
+    /// # Arguments
+    /// * `schema`   - its `type` and `definition`, see
+    ///   [`PubSubClient::get_schema`](super::PubSubClient::get_schema).
+    /// * `encoding` - JSON if unspecified, as for topics.
+    pub fn new(schema: &Schema, encoding: Encoding) -> Result<Self, Error> {
+        let definition = match SchemaType::from_i32(schema.r#type) {
+            Some(SchemaType::Avro) => AvroSchema::parse(&schema.definition).map(Definition::Avro),
+            Some(SchemaType::ProtocolBuffer) => {
+                ProtoSchema::parse(&schema.definition).map(Definition::Protobuf)
+            }
+            _ => Err(format!("unsupported schema type {}", schema.r#type)),
+        }
+        .map_err(PubSubError::InvalidSchema)?;
+        Ok(Self {
+            definition,
+            json: encoding != Encoding::Binary,
+        })
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        if self.json {
+            Encoding::Json
+        } else {
+            Encoding::Binary
+        }
+    }
+
+    /// Encodes `value` according to the schema, failing with
+    /// [`PubSubError::SchemaMismatch`] if it does not conform.
+    ///
+    /// `value` is first serialized to JSON: Avro records, enums and unions follow the
+    /// JSON format of the Avro specification, though union values need not be
+    /// wrapped; protobuf messages follow the proto3 JSON mapping, though fields may
+    /// also be named as in the definition.
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Error> {
+        let value =
+            serde_json::to_value(value).map_err(|e| PubSubError::SchemaMismatch(e.to_string()))?;
+        let data = match &self.definition {
+            Definition::Avro(schema) => schema.encode(&value, self.json),
+            Definition::Protobuf(schema) => schema.encode(&value, self.json),
+        };
+        Ok(data.map_err(PubSubError::SchemaMismatch)?)
+    }
+
+    /// Fails with [`PubSubError::SchemaMismatch`] if `data` is not a message of the
+    /// schema in the encoding.
+    pub fn validate(&self, data: &[u8]) -> Result<(), Error> {
+        let result = match &self.definition {
+            Definition::Avro(schema) => schema.validate(data, self.json),
+            Definition::Protobuf(schema) => schema.validate(data, self.json),
+        };
+        Ok(result.map_err(PubSubError::SchemaMismatch)?)
+    }
+}
+
+/// Publishes messages to a topic with a schema, encoding and validating them first.
+#[derive(Clone)]
+pub struct SchemaPublisher {
+    publisher: Publisher,
+    topic: String,
+    validator: Arc<SchemaValidator>,
+}
+
+impl SchemaPublisher {
+    /// Fetches the schema of the topic, failing with [`PubSubError::InvalidSchema`] if
+    /// the topic has none.
+    ///
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub async fn new(publisher: Publisher, topic: &str) -> Result<Self, Error> {
+        let client = publisher.client();
+        let settings = client
+            .get_topic(topic)
+            .await?
+            .schema_settings
+            .ok_or_else(|| PubSubError::InvalidSchema(format!("{} has no schema", topic)))?;
+        let schema = client.get_schema(&settings.schema).await?;
+        let encoding = Encoding::from_i32(settings.encoding).unwrap_or(Encoding::Unspecified);
+        Ok(Self {
+            validator: Arc::new(SchemaValidator::new(&schema, encoding)?),
+            publisher,
+            topic: topic.to_owned(),
+        })
+    }
+
+    pub fn validator(&self) -> &SchemaValidator {
+        &self.validator
+    }
+
+    /// Encodes `value` and publishes it, see [`SchemaValidator::encode`] and
+    /// [`Publisher::publish`].
+    pub async fn publish<T: Serialize + ?Sized>(&self, value: &T) -> Result<PublishHandle, Error> {
+        let data = self.validator.encode(value)?;
+        Ok(self.publisher.publish(&self.topic, data).await)
+    }
+
+    /// Publishes a message whose data is already encoded, once validated.
+    pub async fn publish_message(&self, message: PubsubMessage) -> Result<PublishHandle, Error> {
+        self.validator.validate(&message.data)?;
+        Ok(self.publisher.publish_message(&self.topic, message).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::pubsub::{fake, topic::TopicBuilder};
+
+    const PROJECT: &str = "projects/test";
+    const TOPIC: &str = "projects/test/topics/topic";
+    const SUBSCRIPTION: &str = "projects/test/subscriptions/subscription";
+
+    const AVRO: &str = r#"{
+        "type": "record",
+        "name": "Order",
+        "fields": [
+            {"name": "id", "type": "string"},
+            {"name": "amount", "type": "long"}
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn test_schema_publisher() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        let schema = client
+            .create_schema(
+                PROJECT,
+                "order",
+                Schema {
+                    r#type: SchemaType::Avro as i32,
+                    definition: AVRO.to_owned(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(schema.name, "projects/test/schemas/order");
+        client
+            .create_topic(
+                TopicBuilder::new(TOPIC)
+                    .with_schema(&schema.name, Encoding::Binary)
+                    .build(),
+            )
+            .await?;
+        fake.create_subscription(SUBSCRIPTION, TOPIC);
+
+        let publisher = SchemaPublisher::new(Publisher::new(client.clone()), TOPIC).await?;
+        publisher
+            .publish(&json!({"id": "o-1", "amount": 3}))
+            .await?
+            .await?;
+        let mut received = client.pull(SUBSCRIPTION).await?.received_messages;
+        let data = received.remove(0).message.unwrap_or_default().data;
+        assert_eq!(data, b"\x06o-1\x06");
+        client
+            .validate_message(PROJECT, &schema.name, data, Encoding::Binary)
+            .await?;
+
+        let error = publisher
+            .publish(&json!({"id": "o-2"}))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            Error::PubSub(PubSubError::SchemaMismatch(_))
+        ));
+        let error = client
+            .publish(TOPIC, b"\x06o-2".to_vec())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::Status(status) if status.code() == tonic::Code::InvalidArgument)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,785 @@
+//! Avro schemas, encoded in the binary or JSON format of the Avro specification.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::MAX_DEPTH;
+
+/// The most items taking no bytes, e.g. nulls, that an array may hold; the size of
+/// the data bounds the others.
+const MAX_ZERO_WIDTH_ITEMS: usize = 100_000;
+
+pub(super) struct AvroSchema {
+    root: Node,
+    named: Vec<Named>,
+}
+
+#[derive(Clone)]
+enum Node {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Array(Box<Node>),
+    Map(Box<Node>),
+    Union(Vec<Node>),
+    /// A record, enum or fixed, by index in `AvroSchema::named`.
+    Named(usize),
+}
+
+struct Named {
+    /// Including the namespace.
+    name: String,
+    kind: NamedKind,
+}
+
+enum NamedKind {
+    Record(Vec<Field>),
+    Enum(Vec<String>),
+    Fixed(usize),
+}
+
+struct Field {
+    name: String,
+    node: Node,
+    /// In the Avro JSON format.
+    default: Option<Value>,
+}
+
+/// A value checked against the schema.
+#[derive(Debug, PartialEq)]
+enum AvroValue {
+    Null,
+    Boolean(bool),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<AvroValue>),
+    Map(Vec<(String, AvroValue)>),
+    Union(usize, Box<AvroValue>),
+    Record(Vec<AvroValue>),
+    Enum(usize),
+    Fixed(Vec<u8>),
+}
+
+impl AvroSchema {
+    pub(super) fn parse(definition: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(definition).map_err(|e| e.to_string())?;
+        let mut parser = Parser::default();
+        let root = parser.parse(&json, "")?;
+        Ok(Self {
+            root,
+            named: parser.named,
+        })
+    }
+
+    /// Encodes a value as serialized by serde, where unions are plain values and bytes
+    /// are arrays of numbers or strings of code points up to U+00FF.
+    pub(super) fn encode(&self, value: &Value, json: bool) -> Result<Vec<u8>, String> {
+        let value = self.resolve(&self.root, value, false, "$")?;
+        if json {
+            Ok(self.to_json(&self.root, &value).to_string().into_bytes())
+        } else {
+            let mut buf = Vec::new();
+            write(&value, &mut buf);
+            Ok(buf)
+        }
+    }
+
+    pub(super) fn validate(&self, data: &[u8], json: bool) -> Result<(), String> {
+        if json {
+            let value: Value = serde_json::from_slice(data).map_err(|e| e.to_string())?;
+            self.resolve(&self.root, &value, true, "$")?;
+        } else {
+            let mut data = data;
+            self.read(&self.root, &mut data, 0)?;
+            if !data.is_empty() {
+                return Err(format!("{} bytes left after the value", data.len()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks a JSON value. `strict` expects the Avro JSON format, in which non-null
+    /// union values are wrapped in an object keyed by the branch type.
+    fn resolve(
+        &self,
+        node: &Node,
+        value: &Value,
+        strict: bool,
+        path: &str,
+    ) -> Result<AvroValue, String> {
+        let mismatch = || format!("{}: expected {}, got {}", path, self.type_name(node), value);
+        Ok(match node {
+            Node::Null => match value {
+                Value::Null => AvroValue::Null,
+                _ => return Err(mismatch()),
+            },
+            Node::Boolean => AvroValue::Boolean(value.as_bool().ok_or_else(mismatch)?),
+            Node::Int => AvroValue::Int(
+                value
+                    .as_i64()
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or_else(mismatch)?,
+            ),
+            Node::Long => AvroValue::Long(value.as_i64().ok_or_else(mismatch)?),
+            Node::Float => AvroValue::Float(value.as_f64().ok_or_else(mismatch)? as f32),
+            Node::Double => AvroValue::Double(value.as_f64().ok_or_else(mismatch)?),
+            Node::Bytes => AvroValue::Bytes(bytes(value, strict).ok_or_else(mismatch)?),
+            Node::String => AvroValue::String(value.as_str().ok_or_else(mismatch)?.to_owned()),
+            Node::Array(items) => AvroValue::Array(
+                value
+                    .as_array()
+                    .ok_or_else(mismatch)?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| self.resolve(items, item, strict, &format!("{}[{}]", path, i)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Node::Map(values) => AvroValue::Map(
+                value
+                    .as_object()
+                    .ok_or_else(mismatch)?
+                    .iter()
+                    .map(|(key, item)| {
+                        let item =
+                            self.resolve(values, item, strict, &format!("{}.{}", path, key))?;
+                        Ok((key.clone(), item))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+            Node::Union(branches) => self
+                .resolve_union(branches, value, strict, path)
+                .ok_or_else(|| {
+                    format!(
+                        "{}: {} matches no branch of {}",
+                        path,
+                        value,
+                        self.type_name(node)
+                    )
+                })?,
+            Node::Named(index) => match &self.named[*index].kind {
+                NamedKind::Record(fields) => {
+                    let object = value.as_object().ok_or_else(mismatch)?;
+                    if let Some(key) = object
+                        .keys()
+                        .find(|key| fields.iter().all(|f| &f.name != *key))
+                    {
+                        return Err(format!("{}: unknown field {}", path, key));
+                    }
+                    AvroValue::Record(
+                        fields
+                            .iter()
+                            .map(|field| self.resolve_field(field, object, strict, path))
+                            .collect::<Result<_, _>>()?,
+                    )
+                }
+                NamedKind::Enum(symbols) => AvroValue::Enum(
+                    value
+                        .as_str()
+                        .and_then(|symbol| symbols.iter().position(|s| s == symbol))
+                        .ok_or_else(mismatch)?,
+                ),
+                NamedKind::Fixed(size) => AvroValue::Fixed(
+                    bytes(value, strict)
+                        .filter(|bytes| bytes.len() == *size)
+                        .ok_or_else(mismatch)?,
+                ),
+            },
+        })
+    }
+
+    fn resolve_field(
+        &self,
+        field: &Field,
+        object: &Map<String, Value>,
+        strict: bool,
+        path: &str,
+    ) -> Result<AvroValue, String> {
+        let path = format!("{}.{}", path, field.name);
+        match (object.get(&field.name), &field.default) {
+            (Some(value), _) => self.resolve(&field.node, value, strict, &path),
+            // Defaults of unions are values of their first branch, not wrapped.
+            (None, Some(default)) => match &field.node {
+                Node::Union(branches) => Ok(AvroValue::Union(
+                    0,
+                    Box::new(self.resolve(&branches[0], default, true, &path)?),
+                )),
+                node => self.resolve(node, default, true, &path),
+            },
+            (None, None) => Err(format!("{}: missing", path)),
+        }
+    }
+
+    fn resolve_union(
+        &self,
+        branches: &[Node],
+        value: &Value,
+        strict: bool,
+        path: &str,
+    ) -> Option<AvroValue> {
+        let wrapped = value
+            .as_object()
+            .filter(|object| object.len() == 1)
+            .and_then(|object| object.iter().next());
+        if let Some((name, inner)) = wrapped {
+            let branch = branches.iter().position(|b| &self.type_name(b) == name);
+            if let Some(branch) = branch {
+                if let Ok(inner) = self.resolve(&branches[branch], inner, strict, path) {
+                    return Some(AvroValue::Union(branch, Box::new(inner)));
+                }
+            }
+        }
+        branches.iter().enumerate().find_map(|(branch, node)| {
+            if strict && !matches!(node, Node::Null) {
+                return None;
+            }
+            let inner = self.resolve(node, value, strict, path).ok()?;
+            Some(AvroValue::Union(branch, Box::new(inner)))
+        })
+    }
+
+    fn to_json(&self, node: &Node, value: &AvroValue) -> Value {
+        match (node, value) {
+            (_, AvroValue::Null) => Value::Null,
+            (_, AvroValue::Boolean(b)) => Value::from(*b),
+            (_, AvroValue::Int(n)) => Value::from(*n),
+            (_, AvroValue::Long(n)) => Value::from(*n),
+            (_, AvroValue::Float(n)) => Value::from(*n),
+            (_, AvroValue::Double(n)) => Value::from(*n),
+            (_, AvroValue::Bytes(bytes) | AvroValue::Fixed(bytes)) => {
+                Value::String(bytes.iter().map(|&b| char::from(b)).collect())
+            }
+            (_, AvroValue::String(s)) => Value::from(s.as_str()),
+            (Node::Array(items), AvroValue::Array(values)) => {
+                values.iter().map(|v| self.to_json(items, v)).collect()
+            }
+            (Node::Map(node), AvroValue::Map(entries)) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, v)| (key.clone(), self.to_json(node, v)))
+                    .collect(),
+            ),
+            (Node::Union(branches), AvroValue::Union(branch, value)) => {
+                let node = &branches[*branch];
+                match node {
+                    Node::Null => Value::Null,
+                    _ => Value::Object(
+                        [(self.type_name(node), self.to_json(node, value))]
+                            .into_iter()
+                            .collect(),
+                    ),
+                }
+            }
+            (Node::Named(index), value) => match (&self.named[*index].kind, value) {
+                (NamedKind::Record(fields), AvroValue::Record(values)) => Value::Object(
+                    fields
+                        .iter()
+                        .zip(values)
+                        .map(|(field, v)| (field.name.clone(), self.to_json(&field.node, v)))
+                        .collect(),
+                ),
+                (NamedKind::Enum(symbols), AvroValue::Enum(i)) => Value::from(symbols[*i].as_str()),
+                _ => unreachable!("resolved against the same schema"),
+            },
+            _ => unreachable!("resolved against the same schema"),
+        }
+    }
+
+    /// Reads a value nested `depth` levels deep.
+    fn read(&self, node: &Node, data: &mut &[u8], depth: usize) -> Result<AvroValue, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("values nested more than {} levels deep", MAX_DEPTH));
+        }
+        Ok(match node {
+            Node::Null => AvroValue::Null,
+            Node::Boolean => match take(data, 1)? {
+                [0] => AvroValue::Boolean(false),
+                [1] => AvroValue::Boolean(true),
+                _ => return Err("invalid boolean".to_owned()),
+            },
+            Node::Int => AvroValue::Int(
+                i32::try_from(read_long(data)?).map_err(|_| "int out of range".to_owned())?,
+            ),
+            Node::Long => AvroValue::Long(read_long(data)?),
+            Node::Float => AvroValue::Float(f32::from_le_bytes(take(data, 4)?.try_into().unwrap())),
+            Node::Double => {
+                AvroValue::Double(f64::from_le_bytes(take(data, 8)?.try_into().unwrap()))
+            }
+            Node::Bytes => AvroValue::Bytes(read_bytes(data)?.to_vec()),
+            Node::String => AvroValue::String(
+                String::from_utf8(read_bytes(data)?.to_vec())
+                    .map_err(|_| "invalid UTF-8 in string".to_owned())?,
+            ),
+            Node::Array(items) => {
+                let mut values = Vec::new();
+                while let Some(count) = read_block(data)? {
+                    let limit = match self.is_zero_width(items, depth) {
+                        true => MAX_ZERO_WIDTH_ITEMS - values.len(),
+                        false => data.len(),
+                    };
+                    for _ in 0..block_len(count, limit)? {
+                        values.push(self.read(items, data, depth + 1)?);
+                    }
+                }
+                AvroValue::Array(values)
+            }
+            Node::Map(node) => {
+                let mut entries = Vec::new();
+                while let Some(count) = read_block(data)? {
+                    // Every entry takes at least the length of its key.
+                    for _ in 0..block_len(count, data.len())? {
+                        let key = String::from_utf8(read_bytes(data)?.to_vec())
+                            .map_err(|_| "invalid UTF-8 in map key".to_owned())?;
+                        entries.push((key, self.read(node, data, depth + 1)?));
+                    }
+                }
+                AvroValue::Map(entries)
+            }
+            Node::Union(branches) => {
+                let branch = usize::try_from(read_long(data)?)
+                    .ok()
+                    .filter(|&branch| branch < branches.len())
+                    .ok_or_else(|| "invalid union branch".to_owned())?;
+                AvroValue::Union(
+                    branch,
+                    Box::new(self.read(&branches[branch], data, depth + 1)?),
+                )
+            }
+            Node::Named(index) => match &self.named[*index].kind {
+                NamedKind::Record(fields) => AvroValue::Record(
+                    fields
+                        .iter()
+                        .map(|field| self.read(&field.node, data, depth + 1))
+                        .collect::<Result<_, _>>()?,
+                ),
+                NamedKind::Enum(symbols) => AvroValue::Enum(
+                    usize::try_from(read_long(data)?)
+                        .ok()
+                        .filter(|&i| i < symbols.len())
+                        .ok_or_else(|| "invalid enum symbol".to_owned())?,
+                ),
+                NamedKind::Fixed(size) => AvroValue::Fixed(take(data, *size)?.to_vec()),
+            },
+        })
+    }
+
+    /// Whether values of `node` are encoded as no bytes at all.
+    fn is_zero_width(&self, node: &Node, depth: usize) -> bool {
+        match node {
+            Node::Null => true,
+            Node::Named(index) => match &self.named[*index].kind {
+                NamedKind::Fixed(size) => *size == 0,
+                // A record containing itself cannot be read anyway.
+                NamedKind::Record(fields) => {
+                    depth <= MAX_DEPTH
+                        && fields
+                            .iter()
+                            .all(|field| self.is_zero_width(&field.node, depth + 1))
+                }
+                NamedKind::Enum(_) => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// The name identifying a union branch in the Avro JSON format.
+    fn type_name(&self, node: &Node) -> String {
+        match node {
+            Node::Null => "null".to_owned(),
+            Node::Boolean => "boolean".to_owned(),
+            Node::Int => "int".to_owned(),
+            Node::Long => "long".to_owned(),
+            Node::Float => "float".to_owned(),
+            Node::Double => "double".to_owned(),
+            Node::Bytes => "bytes".to_owned(),
+            Node::String => "string".to_owned(),
+            Node::Array(_) => "array".to_owned(),
+            Node::Map(_) => "map".to_owned(),
+            Node::Union(branches) => {
+                let names: Vec<_> = branches.iter().map(|b| self.type_name(b)).collect();
+                format!("[{}]", names.join(", "))
+            }
+            Node::Named(index) => self.named[*index].name.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    named: Vec<Named>,
+    by_name: HashMap<String, usize>,
+}
+
+impl Parser {
+    fn parse(&mut self, json: &Value, namespace: &str) -> Result<Node, String> {
+        match json {
+            Value::String(name) => self.reference(name, namespace),
+            Value::Array(branches) => Ok(Node::Union(
+                branches
+                    .iter()
+                    .map(|branch| self.parse(branch, namespace))
+                    .collect::<Result<_, _>>()?,
+            )),
+            Value::Object(object) => {
+                let kind = match object.get("type") {
+                    Some(Value::String(kind)) => kind.as_str(),
+                    Some(other) => return self.parse(other, namespace),
+                    None => return Err(format!("missing type in {}", json)),
+                };
+                match kind {
+                    "record" | "error" | "enum" | "fixed" => {
+                        self.parse_named(object, kind, namespace)
+                    }
+                    "array" => Ok(Node::Array(Box::new(
+                        self.parse(required(object, "items")?, namespace)?,
+                    ))),
+                    "map" => Ok(Node::Map(Box::new(
+                        self.parse(required(object, "values")?, namespace)?,
+                    ))),
+                    // Logical types are encoded as their underlying type.
+                    kind => self.reference(kind, namespace),
+                }
+            }
+            _ => Err(format!("invalid schema {}", json)),
+        }
+    }
+
+    fn parse_named(
+        &mut self,
+        object: &Map<String, Value>,
+        kind: &str,
+        namespace: &str,
+    ) -> Result<Node, String> {
+        let name = required(object, "name")?
+            .as_str()
+            .ok_or("name must be a string")?;
+        let namespace = object
+            .get("namespace")
+            .and_then(Value::as_str)
+            .unwrap_or(namespace);
+        let name = full_name(name, namespace);
+        if self.by_name.contains_key(&name) {
+            return Err(format!("{} is defined twice", name));
+        }
+        let index = self.named.len();
+        self.named.push(Named {
+            name: name.clone(),
+            kind: NamedKind::Fixed(0),
+        });
+        self.by_name.insert(name.clone(), index);
+        // Names inside are relative to the namespace of this type.
+        let namespace = name.rsplit_once('.').map_or("", |(namespace, _)| namespace);
+
+        let kind = match kind {
+            "enum" => NamedKind::Enum(
+                required(object, "symbols")?
+                    .as_array()
+                    .and_then(|symbols| {
+                        symbols
+                            .iter()
+                            .map(|symbol| symbol.as_str().map(str::to_owned))
+                            .collect()
+                    })
+                    .ok_or("symbols must be an array of strings")?,
+            ),
+            "fixed" => NamedKind::Fixed(
+                required(object, "size")?
+                    .as_u64()
+                    .ok_or("size must be a positive integer")? as usize,
+            ),
+            _ => NamedKind::Record(
+                required(object, "fields")?
+                    .as_array()
+                    .ok_or("fields must be an array")?
+                    .iter()
+                    .map(|field| {
+                        let field = field.as_object().ok_or("fields must be objects")?;
+                        Ok(Field {
+                            name: required(field, "name")?
+                                .as_str()
+                                .ok_or("field names must be strings")?
+                                .to_owned(),
+                            node: self.parse(required(field, "type")?, namespace)?,
+                            default: field.get("default").cloned(),
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+        };
+        self.named[index].kind = kind;
+        Ok(Node::Named(index))
+    }
+
+    fn reference(&self, name: &str, namespace: &str) -> Result<Node, String> {
+        Ok(match name {
+            "null" => Node::Null,
+            "boolean" => Node::Boolean,
+            "int" => Node::Int,
+            "long" => Node::Long,
+            "float" => Node::Float,
+            "double" => Node::Double,
+            "bytes" => Node::Bytes,
+            "string" => Node::String,
+            name => Node::Named(
+                *self
+                    .by_name
+                    .get(&full_name(name, namespace))
+                    .or_else(|| self.by_name.get(name))
+                    .ok_or_else(|| format!("unknown type {}", name))?,
+            ),
+        })
+    }
+}
+
+fn required<'a>(object: &'a Map<String, Value>, key: &str) -> Result<&'a Value, String> {
+    object
+        .get(key)
+        .ok_or_else(|| format!("missing {} in {}", key, Value::Object(object.clone())))
+}
+
+fn full_name(name: &str, namespace: &str) -> String {
+    if name.contains('.') || namespace.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
+/// Strings of code points up to U+00FF, and outside of `strict` arrays of numbers.
+fn bytes(value: &Value, strict: bool) -> Option<Vec<u8>> {
+    match value {
+        Value::String(s) => s.chars().map(|c| u8::try_from(c).ok()).collect(),
+        Value::Array(items) if !strict => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn write(value: &AvroValue, buf: &mut Vec<u8>) {
+    match value {
+        AvroValue::Null => {}
+        AvroValue::Boolean(b) => buf.push(*b as u8),
+        AvroValue::Int(n) => write_long(*n as i64, buf),
+        AvroValue::Long(n) => write_long(*n, buf),
+        AvroValue::Float(n) => buf.extend_from_slice(&n.to_le_bytes()),
+        AvroValue::Double(n) => buf.extend_from_slice(&n.to_le_bytes()),
+        AvroValue::Bytes(bytes) => {
+            write_long(bytes.len() as i64, buf);
+            buf.extend_from_slice(bytes);
+        }
+        AvroValue::String(s) => {
+            write_long(s.len() as i64, buf);
+            buf.extend_from_slice(s.as_bytes());
+        }
+        AvroValue::Array(values) => {
+            if !values.is_empty() {
+                write_long(values.len() as i64, buf);
+                values.iter().for_each(|value| write(value, buf));
+            }
+            buf.push(0);
+        }
+        AvroValue::Map(entries) => {
+            if !entries.is_empty() {
+                write_long(entries.len() as i64, buf);
+                for (key, value) in entries {
+                    write_long(key.len() as i64, buf);
+                    buf.extend_from_slice(key.as_bytes());
+                    write(value, buf);
+                }
+            }
+            buf.push(0);
+        }
+        AvroValue::Union(branch, value) => {
+            write_long(*branch as i64, buf);
+            write(value, buf);
+        }
+        AvroValue::Record(values) => values.iter().for_each(|value| write(value, buf)),
+        AvroValue::Enum(index) => write_long(*index as i64, buf),
+        AvroValue::Fixed(bytes) => buf.extend_from_slice(bytes),
+    }
+}
+
+/// Zig-zag encoded variable-length integer.
+fn write_long(n: i64, buf: &mut Vec<u8>) {
+    let mut n = ((n << 1) ^ (n >> 63)) as u64;
+    while n >= 0x80 {
+        buf.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn read_long(data: &mut &[u8]) -> Result<i64, String> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take(data, 1)?[0];
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte < 0x80 {
+            return Ok((n >> 1) as i64 ^ -((n & 1) as i64));
+        }
+    }
+    Err("invalid variable-length integer".to_owned())
+}
+
+fn read_bytes<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let len = usize::try_from(read_long(data)?).map_err(|_| "negative length".to_owned())?;
+    take(data, len)
+}
+
+/// The item count of the next block of an array or map, `None` after the last one.
+fn read_block(data: &mut &[u8]) -> Result<Option<u64>, String> {
+    match read_long(data)? {
+        0 => Ok(None),
+        // A negative count is followed by the size of the block in bytes.
+        count if count < 0 => {
+            read_long(data)?;
+            Ok(Some(count.unsigned_abs()))
+        }
+        count => Ok(Some(count as u64)),
+    }
+}
+
+/// The item count of a block, rejected if larger than `limit`.
+fn block_len(count: u64, limit: usize) -> Result<usize, String> {
+    usize::try_from(count)
+        .ok()
+        .filter(|&count| count <= limit)
+        .ok_or_else(|| format!("block of {} items exceeds the data", count))
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if data.len() < len {
+        return Err("unexpected end of data".to_owned());
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "User",
+        "namespace": "com.example",
+        "fields": [
+            {"name": "name", "type": "string"},
+            {"name": "age", "type": "int"},
+            {"name": "email", "type": ["null", "string"], "default": null},
+            {"name": "role", "type": {"type": "enum", "name": "Role", "symbols": ["ADMIN", "USER"]}},
+            {"name": "tags", "type": {"type": "array", "items": "string"}},
+            {"name": "manager", "type": ["null", "User"], "default": null}
+        ]
+    }"#;
+
+    #[test]
+    fn test_encode() -> anyhow::Result<()> {
+        let schema = AvroSchema::parse(SCHEMA).map_err(anyhow::Error::msg)?;
+        let value = json!({
+            "name": "ann",
+            "age": 33,
+            "email": "ann@example.com",
+            "role": "USER",
+            "tags": ["a"],
+        });
+
+        let binary = schema.encode(&value, false).map_err(anyhow::Error::msg)?;
+        let mut expected = vec![6];
+        expected.extend_from_slice(b"ann");
+        expected.extend_from_slice(&[66, 2, 30]);
+        expected.extend_from_slice(b"ann@example.com");
+        expected.extend_from_slice(&[2, 2, 2, b'a', 0, 0]);
+        assert_eq!(binary, expected);
+        schema
+            .validate(&binary, false)
+            .map_err(anyhow::Error::msg)?;
+
+        let json = schema.encode(&value, true).map_err(anyhow::Error::msg)?;
+        assert_eq!(
+            serde_json::from_slice::<Value>(&json)?,
+            json!({
+                "name": "ann",
+                "age": 33,
+                "email": {"string": "ann@example.com"},
+                "role": "USER",
+                "tags": ["a"],
+                "manager": null,
+            })
+        );
+        schema.validate(&json, true).map_err(anyhow::Error::msg)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatch() -> anyhow::Result<()> {
+        let schema = AvroSchema::parse(SCHEMA).map_err(anyhow::Error::msg)?;
+        let valid = json!({"name": "ann", "age": 33, "role": "USER", "tags": []});
+        assert!(schema.encode(&valid, false).is_ok());
+
+        for (key, value) in [
+            ("age", json!("33")),
+            ("role", json!("OWNER")),
+            ("email", json!(1)),
+            ("manager", json!({"name": "bob"})),
+            ("unknown", json!(1)),
+        ] {
+            let mut invalid = valid.clone();
+            invalid[key] = value;
+            assert!(schema.encode(&invalid, false).is_err(), "{}", invalid);
+        }
+
+        // Unions must be wrapped in the Avro JSON format.
+        let json = json!({"name": "ann", "age": 33, "email": "a@example.com", "role": "USER", "tags": [], "manager": null});
+        assert!(schema.validate(json.to_string().as_bytes(), true).is_err());
+        assert!(schema.validate(&[6, b'a', b'n'], false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_untrusted_data() -> anyhow::Result<()> {
+        let mut huge = Vec::new();
+        write_long(1 << 62, &mut huge);
+        huge.push(0);
+        for items in [
+            r#""null""#,
+            r#""int""#,
+            r#"{"type": "fixed", "name": "Empty", "size": 0}"#,
+        ] {
+            let schema = AvroSchema::parse(&format!(r#"{{"type": "array", "items": {}}}"#, items))
+                .map_err(anyhow::Error::msg)?;
+            assert!(schema.validate(&huge, false).is_err(), "{}", items);
+        }
+        let schema = AvroSchema::parse(r#"{"type": "map", "values": "null"}"#)
+            .map_err(anyhow::Error::msg)?;
+        assert!(schema.validate(&huge, false).is_err());
+
+        let schema = AvroSchema::parse(
+            r#"{"type": "record", "name": "Node", "fields": [{"name": "next", "type": ["null", "Node"]}]}"#,
+        )
+        .map_err(anyhow::Error::msg)?;
+        let nested = |levels: usize| {
+            let mut data = vec![2; levels];
+            data.push(0);
+            data
+        };
+        assert!(schema.validate(&nested(10), false).is_ok());
+        assert!(schema.validate(&nested(1_000_000), false).is_err());
+        Ok(())
+    }
+}
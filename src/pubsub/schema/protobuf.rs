@@ -0,0 +1,1172 @@
+//! Protocol buffer schemas: a `.proto` definition whose first message is the type of
+//! the messages, encoded in the binary wire format or the proto3 JSON mapping.
+//!
+//! Imports, groups and extensions are not supported; neither are they by Pub/Sub.
+
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose, Engine};
+use serde_json::{Map, Value};
+
+use super::MAX_DEPTH;
+
+pub(super) struct ProtoSchema {
+    messages: Vec<MessageType>,
+    enums: Vec<EnumType>,
+    proto3: bool,
+}
+
+struct MessageType {
+    fields: Vec<Field>,
+}
+
+struct EnumType {
+    values: Vec<(String, i32)>,
+}
+
+struct Field {
+    name: String,
+    json_name: String,
+    number: u32,
+    label: Label,
+    kind: Kind,
+    /// A map field, whose values are entries of a message with `key` and `value`.
+    map: bool,
+    packed: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Label {
+    Optional,
+    Required,
+    Repeated,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+    Message(usize),
+    Enum(usize),
+}
+
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const START_GROUP: u64 = 3;
+const END_GROUP: u64 = 4;
+const FIXED32: u64 = 5;
+
+impl Kind {
+    fn scalar(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Self::Double,
+            "float" => Self::Float,
+            "int32" => Self::Int32,
+            "int64" => Self::Int64,
+            "uint32" => Self::Uint32,
+            "uint64" => Self::Uint64,
+            "sint32" => Self::Sint32,
+            "sint64" => Self::Sint64,
+            "fixed32" => Self::Fixed32,
+            "fixed64" => Self::Fixed64,
+            "sfixed32" => Self::Sfixed32,
+            "sfixed64" => Self::Sfixed64,
+            "bool" => Self::Bool,
+            "string" => Self::String,
+            "bytes" => Self::Bytes,
+            _ => return None,
+        })
+    }
+
+    fn wire_type(self) -> u64 {
+        match self {
+            Self::Double | Self::Fixed64 | Self::Sfixed64 => FIXED64,
+            Self::Float | Self::Fixed32 | Self::Sfixed32 => FIXED32,
+            Self::String | Self::Bytes | Self::Message(_) => LENGTH_DELIMITED,
+            _ => VARINT,
+        }
+    }
+}
+
+impl ProtoSchema {
+    pub(super) fn parse(definition: &str) -> Result<Self, String> {
+        let tokens = tokenize(definition)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            package: String::new(),
+            proto3: false,
+            messages: Vec::new(),
+            enums: Vec::new(),
+        };
+        parser.parse_file()?;
+        if parser.messages.is_empty() {
+            return Err("the definition contains no message".to_owned());
+        }
+
+        let names: HashMap<_, _> = parser
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.name.clone(), Kind::Message(i)))
+            .chain(
+                parser
+                    .enums
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (e.name.clone(), Kind::Enum(i))),
+            )
+            .collect();
+        let proto3 = parser.proto3;
+        let messages = parser
+            .messages
+            .into_iter()
+            .map(|message| {
+                let fields = message
+                    .fields
+                    .into_iter()
+                    .map(|field| {
+                        let kind = match Kind::scalar(&field.type_name) {
+                            Some(kind) => kind,
+                            None => resolve(&names, &field.type_name, &message.name)?,
+                        };
+                        let packed = field.label == Label::Repeated
+                            && kind.wire_type() != LENGTH_DELIMITED
+                            && field.packed.unwrap_or(proto3);
+                        Ok(Field {
+                            json_name: json_name(&field.name),
+                            name: field.name,
+                            number: field.number,
+                            label: field.label,
+                            kind,
+                            map: field.map,
+                            packed,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?;
+                Ok(MessageType { fields })
+            })
+            .collect::<Result<_, String>>()?;
+        let enums = parser
+            .enums
+            .into_iter()
+            .map(|e| EnumType { values: e.values })
+            .collect();
+        Ok(Self {
+            messages,
+            enums,
+            proto3,
+        })
+    }
+
+    /// Encodes a JSON value following the proto3 JSON mapping; fields may also be named
+    /// as in the definition and bytes given as arrays of numbers, as serialized by serde.
+    pub(super) fn encode(&self, value: &Value, json: bool) -> Result<Vec<u8>, String> {
+        let mut buf = Vec::new();
+        self.encode_message(0, value, "$", &mut buf)?;
+        if json {
+            // Normalizes the value to the proto3 JSON mapping.
+            let value = self.decode_message(0, &buf, 0)?;
+            return Ok(value.to_string().into_bytes());
+        }
+        Ok(buf)
+    }
+
+    /// Unknown fields are rejected, whereas the service keeps them.
+    pub(super) fn validate(&self, data: &[u8], json: bool) -> Result<(), String> {
+        if json {
+            let value: Value = serde_json::from_slice(data).map_err(|e| e.to_string())?;
+            self.encode_message(0, &value, "$", &mut Vec::new())
+        } else {
+            self.decode_message(0, data, 0).map(drop)
+        }
+    }
+
+    fn encode_message(
+        &self,
+        index: usize,
+        value: &Value,
+        path: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), String> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| format!("{}: expected an object, got {}", path, value))?;
+        let fields = &self.messages[index].fields;
+        let mut present = vec![None; fields.len()];
+        for (key, value) in object {
+            let i = fields
+                .iter()
+                .position(|f| &f.name == key || &f.json_name == key)
+                .ok_or_else(|| format!("{}: unknown field {}", path, key))?;
+            if !value.is_null() {
+                present[i] = Some(value);
+            }
+        }
+
+        for (field, value) in fields.iter().zip(present) {
+            let path = format!("{}.{}", path, field.name);
+            let value = match value {
+                Some(value) => value,
+                None if field.label == Label::Required => {
+                    return Err(format!("{}: missing required field", path))
+                }
+                None => continue,
+            };
+            if field.map {
+                let entries = value
+                    .as_object()
+                    .ok_or_else(|| format!("{}: expected an object, got {}", path, value))?;
+                let Kind::Message(entry) = field.kind else {
+                    unreachable!("map fields are messages")
+                };
+                let [key_field, value_field] = &self.messages[entry].fields[..] else {
+                    unreachable!("map entries have a key and a value")
+                };
+                for (key, value) in entries {
+                    let mut entry = Vec::new();
+                    let key = map_key(key_field.kind, key)
+                        .ok_or_else(|| format!("{}: invalid key {}", path, key))?;
+                    self.encode_field(key_field, &key, &path, &mut entry)?;
+                    self.encode_field(
+                        value_field,
+                        value,
+                        &format!("{}.{}", path, key),
+                        &mut entry,
+                    )?;
+                    write_tag(field.number, LENGTH_DELIMITED, buf);
+                    write_bytes(&entry, buf);
+                }
+            } else if field.label == Label::Repeated {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| format!("{}: expected an array, got {}", path, value))?;
+                if field.packed {
+                    let mut packed = Vec::new();
+                    for (i, item) in items.iter().enumerate() {
+                        self.encode_value(
+                            field.kind,
+                            item,
+                            &format!("{}[{}]", path, i),
+                            &mut packed,
+                        )?;
+                    }
+                    write_tag(field.number, LENGTH_DELIMITED, buf);
+                    write_bytes(&packed, buf);
+                } else {
+                    for (i, item) in items.iter().enumerate() {
+                        self.encode_field(field, item, &format!("{}[{}]", path, i), buf)?;
+                    }
+                }
+            } else {
+                self.encode_field(field, value, &path, buf)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        field: &Field,
+        value: &Value,
+        path: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), String> {
+        write_tag(field.number, field.kind.wire_type(), buf);
+        self.encode_value(field.kind, value, path, buf)
+    }
+
+    fn encode_value(
+        &self,
+        kind: Kind,
+        value: &Value,
+        path: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), String> {
+        let mismatch = || format!("{}: {} is not a valid {}", path, value, kind_name(kind));
+        match kind {
+            Kind::Double => {
+                buf.extend_from_slice(&float(value).ok_or_else(mismatch)?.to_le_bytes())
+            }
+            Kind::Float => {
+                buf.extend_from_slice(&(float(value).ok_or_else(mismatch)? as f32).to_le_bytes())
+            }
+            Kind::Int32 => write_varint(
+                integer::<i32>(value).ok_or_else(mismatch)? as i64 as u64,
+                buf,
+            ),
+            Kind::Int64 => write_varint(integer::<i64>(value).ok_or_else(mismatch)? as u64, buf),
+            Kind::Uint32 => write_varint(integer::<u32>(value).ok_or_else(mismatch)? as u64, buf),
+            Kind::Uint64 => write_varint(integer::<u64>(value).ok_or_else(mismatch)?, buf),
+            Kind::Sint32 => {
+                let n = integer::<i32>(value).ok_or_else(mismatch)?;
+                write_varint(((n << 1) ^ (n >> 31)) as u32 as u64, buf)
+            }
+            Kind::Sint64 => {
+                let n = integer::<i64>(value).ok_or_else(mismatch)?;
+                write_varint(((n << 1) ^ (n >> 63)) as u64, buf)
+            }
+            Kind::Fixed32 => {
+                buf.extend_from_slice(&integer::<u32>(value).ok_or_else(mismatch)?.to_le_bytes())
+            }
+            Kind::Fixed64 => {
+                buf.extend_from_slice(&integer::<u64>(value).ok_or_else(mismatch)?.to_le_bytes())
+            }
+            Kind::Sfixed32 => {
+                buf.extend_from_slice(&integer::<i32>(value).ok_or_else(mismatch)?.to_le_bytes())
+            }
+            Kind::Sfixed64 => {
+                buf.extend_from_slice(&integer::<i64>(value).ok_or_else(mismatch)?.to_le_bytes())
+            }
+            Kind::Bool => write_varint(value.as_bool().ok_or_else(mismatch)? as u64, buf),
+            Kind::String => write_bytes(value.as_str().ok_or_else(mismatch)?.as_bytes(), buf),
+            Kind::Bytes => write_bytes(&bytes(value).ok_or_else(mismatch)?, buf),
+            Kind::Enum(index) => {
+                let values = &self.enums[index].values;
+                let number = match value {
+                    Value::String(name) => values
+                        .iter()
+                        .find(|(n, _)| n == name)
+                        .map(|(_, number)| *number),
+                    value => integer::<i32>(value),
+                };
+                write_varint(number.ok_or_else(mismatch)? as i64 as u64, buf)
+            }
+            Kind::Message(index) => {
+                let mut message = Vec::new();
+                self.encode_message(index, value, path, &mut message)?;
+                write_bytes(&message, buf);
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes a message, nested `depth` levels deep, to the proto3 JSON mapping.
+    fn decode_message(&self, index: usize, mut data: &[u8], depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!(
+                "messages nested more than {} levels deep",
+                MAX_DEPTH
+            ));
+        }
+        let fields = &self.messages[index].fields;
+        let mut object = Map::new();
+        while !data.is_empty() {
+            let tag = read_varint(&mut data)?;
+            let (number, wire_type) = (tag >> 3, tag & 7);
+            // Unknown fields, e.g. from a newer revision of the schema, are skipped.
+            let Some(field) = fields.iter().find(|f| f.number as u64 == number) else {
+                skip_value(&mut data, wire_type)?;
+                continue;
+            };
+
+            if field.map {
+                let Kind::Message(entry) = field.kind else {
+                    unreachable!("map fields are messages")
+                };
+                let entry =
+                    self.decode_message(entry, read_bytes(&mut data, wire_type)?, depth + 1)?;
+                let key = match entry.get("key") {
+                    Some(Value::String(key)) => key.clone(),
+                    Some(key) => key.to_string(),
+                    None => String::new(),
+                };
+                let value = entry.get("value").cloned().unwrap_or(Value::Null);
+                object
+                    .entry(&field.json_name)
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .unwrap()
+                    .insert(key, value);
+            } else if field.label == Label::Repeated {
+                let items = object
+                    .entry(&field.json_name)
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                    .as_array_mut()
+                    .unwrap();
+                let packable = field.kind.wire_type() != LENGTH_DELIMITED;
+                if packable && wire_type == LENGTH_DELIMITED {
+                    let mut packed = read_bytes(&mut data, wire_type)?;
+                    while !packed.is_empty() {
+                        items.push(self.decode_value(
+                            field.kind,
+                            field.kind.wire_type(),
+                            &mut packed,
+                            depth,
+                        )?);
+                    }
+                } else {
+                    items.push(self.decode_value(field.kind, wire_type, &mut data, depth)?);
+                }
+            } else {
+                let value = self.decode_value(field.kind, wire_type, &mut data, depth)?;
+                object.insert(field.json_name.clone(), value);
+            }
+        }
+
+        if let Some(field) = fields
+            .iter()
+            .find(|f| f.label == Label::Required && !object.contains_key(&f.json_name))
+        {
+            return Err(format!("missing required field {}", field.name));
+        }
+        Ok(Value::Object(object))
+    }
+
+    /// Decodes a field of a message nested `depth` levels deep.
+    fn decode_value(
+        &self,
+        kind: Kind,
+        wire_type: u64,
+        data: &mut &[u8],
+        depth: usize,
+    ) -> Result<Value, String> {
+        if wire_type != kind.wire_type() {
+            return Err(format!(
+                "wire type {} does not match {}",
+                wire_type,
+                kind_name(kind)
+            ));
+        }
+        Ok(match kind {
+            Kind::Double => float_json(f64::from_le_bytes(take(data, 8)?.try_into().unwrap())),
+            Kind::Float => {
+                float_json(f32::from_le_bytes(take(data, 4)?.try_into().unwrap()) as f64)
+            }
+            Kind::Int32 => Value::from(read_varint(data)? as i32),
+            Kind::Int64 => Value::from((read_varint(data)? as i64).to_string()),
+            Kind::Uint32 => Value::from(read_varint(data)? as u32),
+            Kind::Uint64 => Value::from(read_varint(data)?.to_string()),
+            Kind::Sint32 => {
+                let n = read_varint(data)? as u32;
+                Value::from((n >> 1) as i32 ^ -((n & 1) as i32))
+            }
+            Kind::Sint64 => {
+                let n = read_varint(data)?;
+                Value::from(((n >> 1) as i64 ^ -((n & 1) as i64)).to_string())
+            }
+            Kind::Fixed32 => Value::from(u32::from_le_bytes(take(data, 4)?.try_into().unwrap())),
+            Kind::Fixed64 => {
+                Value::from(u64::from_le_bytes(take(data, 8)?.try_into().unwrap()).to_string())
+            }
+            Kind::Sfixed32 => Value::from(i32::from_le_bytes(take(data, 4)?.try_into().unwrap())),
+            Kind::Sfixed64 => {
+                Value::from(i64::from_le_bytes(take(data, 8)?.try_into().unwrap()).to_string())
+            }
+            Kind::Bool => Value::from(read_varint(data)? != 0),
+            Kind::String => Value::from(
+                std::str::from_utf8(read_bytes(data, wire_type)?)
+                    .map_err(|_| "invalid UTF-8 in string".to_owned())?,
+            ),
+            Kind::Bytes => {
+                Value::from(general_purpose::STANDARD.encode(read_bytes(data, wire_type)?))
+            }
+            Kind::Enum(index) => {
+                let number = read_varint(data)? as i32;
+                let values = &self.enums[index].values;
+                match values.iter().find(|(_, n)| *n == number) {
+                    Some((name, _)) => Value::from(name.as_str()),
+                    None if self.proto3 => Value::from(number),
+                    None => return Err(format!("unknown enum value {}", number)),
+                }
+            }
+            Kind::Message(index) => {
+                self.decode_message(index, read_bytes(data, wire_type)?, depth + 1)?
+            }
+        })
+    }
+}
+
+/// Resolves a type name the way protoc does, from the innermost scope outwards.
+fn resolve(names: &HashMap<String, Kind>, name: &str, scope: &str) -> Result<Kind, String> {
+    if let Some(name) = name.strip_prefix('.') {
+        return names
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("unknown type {}", name));
+    }
+    let mut scope = Some(scope);
+    while let Some(current) = scope {
+        let candidate = if current.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}.{}", current, name)
+        };
+        if let Some(kind) = names.get(&candidate) {
+            return Ok(*kind);
+        }
+        scope = match current {
+            "" => None,
+            current => Some(current.rsplit_once('.').map_or("", |(outer, _)| outer)),
+        };
+    }
+    Err(format!("unknown type {}", name))
+}
+
+fn json_name(name: &str) -> String {
+    let mut json_name = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' => upper = true,
+            c if upper => {
+                json_name.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => json_name.push(c),
+        }
+    }
+    json_name
+}
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Double => "double",
+        Kind::Float => "float",
+        Kind::Int32 => "int32",
+        Kind::Int64 => "int64",
+        Kind::Uint32 => "uint32",
+        Kind::Uint64 => "uint64",
+        Kind::Sint32 => "sint32",
+        Kind::Sint64 => "sint64",
+        Kind::Fixed32 => "fixed32",
+        Kind::Fixed64 => "fixed64",
+        Kind::Sfixed32 => "sfixed32",
+        Kind::Sfixed64 => "sfixed64",
+        Kind::Bool => "bool",
+        Kind::String => "string",
+        Kind::Bytes => "bytes",
+        Kind::Message(_) => "message",
+        Kind::Enum(_) => "enum",
+    }
+}
+
+/// Map keys are strings in JSON; they keep their type on the wire.
+fn map_key(kind: Kind, key: &str) -> Option<Value> {
+    match kind {
+        Kind::String => Some(Value::from(key)),
+        Kind::Bool => key.parse::<bool>().ok().map(Value::from),
+        _ => Some(Value::from(key))
+            .filter(|key| integer::<i64>(key).is_some() || integer::<u64>(key).is_some()),
+    }
+}
+
+/// Numbers, or strings as 64-bit integers are in the proto3 JSON mapping.
+fn integer<T: TryFrom<i64> + TryFrom<u64> + std::str::FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (_, Some(n)) => T::try_from(n).ok(),
+            (Some(n), None) => T::try_from(n).ok(),
+            _ => None,
+        },
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => match s.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            s => s.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+fn float_json(n: f64) -> Value {
+    match n {
+        n if n.is_nan() => Value::from("NaN"),
+        f64::INFINITY => Value::from("Infinity"),
+        f64::NEG_INFINITY => Value::from("-Infinity"),
+        n => Value::from(n),
+    }
+}
+
+/// Base64 strings as in the proto3 JSON mapping, or arrays of numbers.
+fn bytes(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::String(s) => general_purpose::STANDARD
+            .decode(s)
+            .or_else(|_| general_purpose::URL_SAFE.decode(s))
+            .ok(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn write_tag(number: u32, wire_type: u64, buf: &mut Vec<u8>) {
+    write_varint((number as u64) << 3 | wire_type, buf);
+}
+
+fn write_varint(mut n: u64, buf: &mut Vec<u8>) {
+    while n >= 0x80 {
+        buf.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn write_bytes(bytes: &[u8], buf: &mut Vec<u8>) {
+    write_varint(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
+fn read_varint(data: &mut &[u8]) -> Result<u64, String> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take(data, 1)?[0];
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte < 0x80 {
+            return Ok(n);
+        }
+    }
+    Err("invalid varint".to_owned())
+}
+
+fn read_bytes<'a>(data: &mut &'a [u8], wire_type: u64) -> Result<&'a [u8], String> {
+    if wire_type != LENGTH_DELIMITED {
+        return Err(format!("wire type {} is not length-delimited", wire_type));
+    }
+    let len = read_varint(data)?;
+    take(
+        data,
+        usize::try_from(len).map_err(|_| "invalid length".to_owned())?,
+    )
+}
+
+/// Skips the value of a field that is not in the schema.
+fn skip_value(data: &mut &[u8], wire_type: u64) -> Result<(), String> {
+    match wire_type {
+        VARINT => {
+            read_varint(data)?;
+        }
+        FIXED64 => {
+            take(data, 8)?;
+        }
+        LENGTH_DELIMITED => {
+            read_bytes(data, wire_type)?;
+        }
+        FIXED32 => {
+            take(data, 4)?;
+        }
+        START_GROUP => {
+            // Skips nested groups up to the end of this one, without recursing.
+            let mut groups = 1;
+            while groups > 0 {
+                match read_varint(data)? & 7 {
+                    START_GROUP => groups += 1,
+                    END_GROUP => groups -= 1,
+                    wire_type => skip_value(data, wire_type)?,
+                }
+            }
+        }
+        wire_type => return Err(format!("invalid wire type {}", wire_type)),
+    }
+    Ok(())
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if data.len() < len {
+        return Err("unexpected end of data".to_owned());
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifiers, keywords and numbers.
+    Word(String),
+    Str(String),
+    Symbol(char),
+}
+
+fn tokenize(definition: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = definition.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                chars
+                    .by_ref()
+                    .find(|&c| std::mem::replace(&mut previous, c) == '*' && c == '/')
+                    .ok_or("unterminated comment")?;
+            }
+            '"' | '\'' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next().ok_or("unterminated string")? {
+                        '\\' => s.extend(chars.next()),
+                        end if end == c => break,
+                        c => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+            c => tokens.push(Token::Symbol(c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct ParsedMessage {
+    /// Including the package and enclosing messages.
+    name: String,
+    fields: Vec<ParsedField>,
+}
+
+struct ParsedField {
+    name: String,
+    type_name: String,
+    number: u32,
+    label: Label,
+    map: bool,
+    packed: Option<bool>,
+}
+
+struct ParsedEnum {
+    name: String,
+    values: Vec<(String, i32)>,
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    package: String,
+    proto3: bool,
+    messages: Vec<ParsedMessage>,
+    enums: Vec<ParsedEnum>,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or("unexpected end of definition")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn word(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            token => Err(format!("expected a name, got {:?}", token)),
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        match self.next()? {
+            Token::Symbol(c) if c == symbol => Ok(()),
+            token => Err(format!("expected {:?}, got {:?}", symbol, token)),
+        }
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn number(&mut self) -> Result<i64, String> {
+        let negative = self.eat('-');
+        let word = self.word()?;
+        let n = match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+            Some(hex) => i64::from_str_radix(hex, 16),
+            None => word.parse(),
+        }
+        .map_err(|_| format!("invalid number {}", word))?;
+        Ok(if negative { -n } else { n })
+    }
+
+    /// Skips to the end of a statement, over any nested braces.
+    fn skip_statement(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+        loop {
+            match self.next()? {
+                Token::Symbol(';') if depth == 0 => return Ok(()),
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.eat(';');
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_file(&mut self) -> Result<(), String> {
+        while self.peek().is_some() {
+            if self.eat(';') {
+                continue;
+            }
+            match self.word()?.as_str() {
+                "syntax" => {
+                    self.expect('=')?;
+                    match self.next()? {
+                        Token::Str(syntax) => self.proto3 = syntax == "proto3",
+                        token => return Err(format!("invalid syntax {:?}", token)),
+                    }
+                    self.expect(';')?;
+                }
+                "package" => {
+                    self.package = self.word()?;
+                    self.expect(';')?;
+                }
+                "option" => self.skip_statement()?,
+                "message" => {
+                    let scope = self.package.clone();
+                    self.parse_message(&scope)?;
+                }
+                "enum" => {
+                    let scope = self.package.clone();
+                    self.parse_enum(&scope)?;
+                }
+                word => return Err(format!("{} is not supported", word)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_message(&mut self, scope: &str) -> Result<(), String> {
+        let name = qualify(scope, &self.word()?);
+        let index = self.messages.len();
+        self.messages.push(ParsedMessage {
+            name: name.clone(),
+            fields: Vec::new(),
+        });
+        self.expect('{')?;
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+            match self.peek() {
+                Some(Token::Word(word)) => match word.as_str() {
+                    "message" => {
+                        self.pos += 1;
+                        self.parse_message(&name)?;
+                    }
+                    "enum" => {
+                        self.pos += 1;
+                        self.parse_enum(&name)?;
+                    }
+                    "option" | "reserved" | "extensions" => self.skip_statement()?,
+                    "extend" | "group" => return Err(format!("{} is not supported", word)),
+                    "oneof" => {
+                        self.pos += 1;
+                        self.word()?;
+                        self.expect('{')?;
+                        while !self.eat('}') {
+                            if self.peek() == Some(&Token::Word("option".to_owned())) {
+                                self.skip_statement()?;
+                                continue;
+                            }
+                            let field = self.parse_field(Label::Optional, &name)?;
+                            self.messages[index].fields.push(field);
+                        }
+                    }
+                    "map" if self.tokens.get(self.pos + 1) == Some(&Token::Symbol('<')) => {
+                        self.pos += 2;
+                        let key = self.word()?;
+                        self.expect(',')?;
+                        let value = self.word()?;
+                        self.expect('>')?;
+                        let mut field = self.parse_field(Label::Repeated, &name)?;
+                        let entry = format!("{}Entry", upper_camel(&field.name));
+                        self.messages.push(ParsedMessage {
+                            name: qualify(&name, &entry),
+                            fields: vec![
+                                entry_field("key", key, 1),
+                                entry_field("value", value, 2),
+                            ],
+                        });
+                        field.type_name = entry;
+                        field.map = true;
+                        self.messages[index].fields.push(field);
+                    }
+                    _ => {
+                        let label = match word.as_str() {
+                            "optional" => Some(Label::Optional),
+                            "required" => Some(Label::Required),
+                            "repeated" => Some(Label::Repeated),
+                            _ => None,
+                        };
+                        if label.is_some() {
+                            self.pos += 1;
+                        }
+                        let field = self.parse_field(label.unwrap_or(Label::Optional), &name)?;
+                        self.messages[index].fields.push(field);
+                    }
+                },
+                token => return Err(format!("unexpected {:?} in message {}", token, name)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses `[type] name = number [options];`, the type having been read for maps.
+    fn parse_field(&mut self, label: Label, message: &str) -> Result<ParsedField, String> {
+        let type_name = match self.tokens.get(self.pos + 1) {
+            Some(Token::Symbol('=')) => String::new(),
+            _ => self.word()?,
+        };
+        let name = self.word()?;
+        self.expect('=')?;
+        let number = u32::try_from(self.number()?)
+            .ok()
+            .filter(|n| (1..1 << 29).contains(n))
+            .ok_or_else(|| format!("invalid number of {}.{}", message, name))?;
+        let mut packed = None;
+        if self.eat('[') {
+            while !self.eat(']') {
+                if let Token::Word(option) = self.next()? {
+                    if option == "packed" {
+                        self.expect('=')?;
+                        packed = Some(self.word()? == "true");
+                    }
+                }
+            }
+        }
+        self.expect(';')?;
+        Ok(ParsedField {
+            name,
+            type_name,
+            number,
+            label,
+            map: false,
+            packed,
+        })
+    }
+
+    fn parse_enum(&mut self, scope: &str) -> Result<(), String> {
+        let name = qualify(scope, &self.word()?);
+        let mut values = Vec::new();
+        self.expect('{')?;
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+            let value = self.word()?;
+            if value == "option" || value == "reserved" {
+                self.skip_statement()?;
+                continue;
+            }
+            self.expect('=')?;
+            let number = i32::try_from(self.number()?)
+                .map_err(|_| format!("invalid value of {}.{}", name, value))?;
+            if self.eat('[') {
+                while !self.eat(']') {
+                    self.next()?;
+                }
+            }
+            self.expect(';')?;
+            values.push((value, number));
+        }
+        self.enums.push(ParsedEnum { name, values });
+        Ok(())
+    }
+}
+
+fn entry_field(name: &str, type_name: String, number: u32) -> ParsedField {
+    ParsedField {
+        name: name.to_owned(),
+        type_name,
+        number,
+        label: Label::Optional,
+        map: false,
+        packed: None,
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+fn upper_camel(name: &str) -> String {
+    let json_name = json_name(name);
+    let mut chars = json_name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    const DEFINITION: &str = r#"
+        syntax = "proto3";
+        package example;
+
+        // The first message is the type of the messages.
+        message Order {
+            string order_id = 1;
+            int64 amount_cents = 2;
+            repeated int32 quantities = 3;
+            Status status = 4;
+            map<string, Item> items = 5;
+            oneof payment {
+                string card = 6;
+                bytes token = 7 [deprecated = true];
+            }
+
+            message Item { sint32 delta = 1; }
+        }
+
+        /* Enums may follow. */
+        enum Status {
+            STATUS_UNSPECIFIED = 0;
+            PAID = 1;
+        }
+    "#;
+
+    #[test]
+    fn test_encode() -> anyhow::Result<()> {
+        let schema = ProtoSchema::parse(DEFINITION).map_err(anyhow::Error::msg)?;
+        let value = json!({
+            "order_id": "o-1",
+            "amountCents": "1500",
+            "quantities": [1, 2],
+            "status": "PAID",
+            "items": {"apple": {"delta": -1}},
+            "token": [1, 2],
+        });
+
+        let binary = schema.encode(&value, false).map_err(anyhow::Error::msg)?;
+        assert_eq!(
+            binary,
+            [
+                &[0x0a, 3][..],
+                b"o-1",
+                &[0x10, 0xdc, 0x0b],
+                &[0x1a, 2, 1, 2],
+                &[0x20, 1],
+                &[0x2a, 11, 0x0a, 5],
+                b"apple",
+                &[0x12, 2, 0x08, 1],
+                &[0x3a, 2, 1, 2],
+            ]
+            .concat()
+        );
+        schema
+            .validate(&binary, false)
+            .map_err(anyhow::Error::msg)?;
+
+        let json = schema.encode(&value, true).map_err(anyhow::Error::msg)?;
+        assert_eq!(
+            serde_json::from_slice::<Value>(&json)?,
+            json!({
+                "orderId": "o-1",
+                "amountCents": "1500",
+                "quantities": [1, 2],
+                "status": "PAID",
+                "items": {"apple": {"delta": -1}},
+                "token": "AQI=",
+            })
+        );
+        schema.validate(&json, true).map_err(anyhow::Error::msg)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatch() -> anyhow::Result<()> {
+        let schema = ProtoSchema::parse(DEFINITION).map_err(anyhow::Error::msg)?;
+        for value in [
+            json!({"orderId": 1}),
+            json!({"status": "REFUNDED"}),
+            json!({"quantities": 1}),
+            json!({"items": {"apple": {"delta": "x"}}}),
+            json!({"unknown": 1}),
+        ] {
+            assert!(schema.encode(&value, false).is_err(), "{}", value);
+        }
+
+        // Field 1 as a varint instead of a string.
+        assert!(schema.validate(&[0x08, 1], false).is_err());
+        // Unknown field 9 as a varint, a string and a group are skipped, unless truncated.
+        assert!(schema.validate(&[0x48, 1], false).is_ok());
+        assert!(schema.validate(&[0x4a, 2, b'a', b'b'], false).is_ok());
+        assert!(schema.validate(&[0x4b, 0x08, 1, 0x4c], false).is_ok());
+        assert!(schema.validate(&[0x4a, 2, b'a'], false).is_err());
+        assert!(schema.validate(&[0x4b, 0x08, 1], false).is_err());
+        assert!(ProtoSchema::parse("import \"other.proto\"; message A {}").is_err());
+        assert!(ProtoSchema::parse("message A { Missing b = 1; }").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_nesting_limit() -> anyhow::Result<()> {
+        let schema = ProtoSchema::parse(r#"syntax = "proto3"; message Node { Node next = 1; }"#)
+            .map_err(anyhow::Error::msg)?;
+        let nested = |levels: usize| {
+            let mut data = Vec::new();
+            for _ in 0..levels {
+                let mut outer = vec![0x0a];
+                write_varint(data.len() as u64, &mut outer);
+                outer.extend(data);
+                data = outer;
+            }
+            data
+        };
+        assert!(schema.validate(&nested(MAX_DEPTH), false).is_ok());
+        assert!(schema.validate(&nested(100_000), false).is_err());
+        Ok(())
+    }
+}
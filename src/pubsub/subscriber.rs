@@ -0,0 +1,1104 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::{self, Future},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use rand::Rng;
+use tokio::{
+    sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore},
+    task::{JoinError, JoinSet},
+};
+use tonic::Code;
+
+use crate::{
+    error::Error,
+    proto::google::pubsub::v1::{PubsubMessage, ReceivedMessage, StreamingPullRequest},
+    pubsub::{AckStatus, PubSubClient},
+    retry::RetryPolicy,
+};
+
+const DEFAULT_MAX_OUTSTANDING_MESSAGES: usize = 1000;
+const DEFAULT_MAX_OUTSTANDING_BYTES: usize = 1_000_000_000;
+const DEFAULT_ACK_DEADLINE: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_EXTENSION: Duration = Duration::from_secs(60 * 60);
+const MIN_ACK_DEADLINE: Duration = Duration::from_secs(10);
+const MAX_ACK_DEADLINE: Duration = Duration::from_secs(600);
+/// The most ack ids a single `Acknowledge` or `ModifyAckDeadline` request may carry.
+const MAX_ACK_IDS_PER_REQUEST: usize = 2500;
+const ACK_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+// Attributes added to messages forwarded to the dead-letter topic of a `Subscriber`.
+/// The subscription the message was received from.
+pub const DEAD_LETTER_SUBSCRIPTION_ATTRIBUTE: &str = "dead_letter_source_subscription";
+/// The id of the message in the original topic.
+pub const DEAD_LETTER_MESSAGE_ID_ATTRIBUTE: &str = "dead_letter_source_message_id";
+/// How many times the message was delivered before being forwarded.
+pub const DEAD_LETTER_DELIVERY_ATTEMPTS_ATTRIBUTE: &str = "dead_letter_delivery_attempts";
+/// Set when the message was nacked with [`Message::nack_with_error`].
+pub const DEAD_LETTER_ERROR_ATTRIBUTE: &str = "dead_letter_error";
+
+// cf. https://github.com/googleapis/google-cloud-go/blob/main/pubsub/service.go
+const STREAMING_PULL_RETRY_CODES: &[Code] = &[
+    Code::DeadlineExceeded,
+    Code::ResourceExhausted,
+    Code::Aborted,
+    Code::Internal,
+    Code::Unavailable,
+];
+
+/// Receives messages of a subscription over a streaming pull and hands each one to
+/// an async handler.
+///
+/// While a handler runs, the ack deadline of its message is extended, up to
+/// `max_extension`. Acknowledgements are batched. The stream is reopened after
+/// transient errors.
+#[derive(Clone)]
+pub struct Subscriber {
+    client: PubSubClient,
+    subscription: String,
+    max_outstanding_messages: usize,
+    max_outstanding_bytes: usize,
+    ack_deadline: Duration,
+    max_extension: Duration,
+    /// How often leases are extended.
+    lease_interval: Duration,
+    reconnect_policy: RetryPolicy,
+    dead_letter: Option<DeadLetter>,
+}
+
+#[derive(Clone)]
+struct DeadLetter {
+    topic: String,
+    max_delivery_attempts: i32,
+}
+
+impl Subscriber {
+    /// # Arguments
+    /// * `client`       - used for every call.
+    /// * `subscription` - in the format `projects/{project}/subscriptions/{subscription}`
+    pub fn new(client: PubSubClient, subscription: impl Into<String>) -> Self {
+        Self {
+            client,
+            subscription: subscription.into(),
+            max_outstanding_messages: DEFAULT_MAX_OUTSTANDING_MESSAGES,
+            max_outstanding_bytes: DEFAULT_MAX_OUTSTANDING_BYTES,
+            ack_deadline: DEFAULT_ACK_DEADLINE,
+            max_extension: DEFAULT_MAX_EXTENSION,
+            lease_interval: DEFAULT_ACK_DEADLINE / 2,
+            reconnect_policy: RetryPolicy::default()
+                .with_retryable_codes(STREAMING_PULL_RETRY_CODES),
+            dead_letter: None,
+        }
+    }
+
+    /// Messages handed to handlers and not yet acked or nacked. Defaults to 1000.
+    pub fn with_max_outstanding_messages(mut self, max_outstanding_messages: usize) -> Self {
+        self.max_outstanding_messages = max_outstanding_messages.max(1);
+        self
+    }
+
+    /// Total size of outstanding message data. Defaults to 1 GB.
+    pub fn with_max_outstanding_bytes(mut self, max_outstanding_bytes: usize) -> Self {
+        self.max_outstanding_bytes = max_outstanding_bytes.clamp(1, u32::MAX as usize);
+        self
+    }
+
+    /// The deadline leases are extended by, between 10 and 600 seconds. Defaults to
+    /// 60 seconds.
+    pub fn with_ack_deadline(mut self, ack_deadline: Duration) -> Self {
+        self.ack_deadline = ack_deadline.clamp(MIN_ACK_DEADLINE, MAX_ACK_DEADLINE);
+        self.lease_interval = self.ack_deadline / 2;
+        self
+    }
+
+    /// How long a message is kept leased while its handler runs, after which it may
+    /// be redelivered. Defaults to 60 minutes.
+    pub fn with_max_extension(mut self, max_extension: Duration) -> Self {
+        self.max_extension = max_extension;
+        self
+    }
+
+    /// Backoff between reconnections and which errors are transient. Reconnection is
+    /// attempted indefinitely, `max_attempts` and `total_timeout` are ignored.
+    pub fn with_reconnect_policy(mut self, reconnect_policy: RetryPolicy) -> Self {
+        self.reconnect_policy = reconnect_policy;
+        self
+    }
+
+    /// Republishes messages nacked on their `max_delivery_attempts`-th delivery to
+    /// `dead_letter_topic`, then acknowledges them, so that poison messages are not
+    /// redelivered forever. The copies carry the original attributes, the ordering key
+    /// and the `DEAD_LETTER_*_ATTRIBUTE`s; a copy that fails to publish is nacked
+    /// instead.
+    ///
+    /// Attempts are counted by the service for subscriptions with a dead-letter policy,
+    /// otherwise by the subscriber, starting over when it is restarted or a message is
+    /// not redelivered within the maximum lease extension plus 10 minutes.
+    ///
+    /// # Arguments
+    /// * `dead_letter_topic`     - in the format `projects/{project}/topics/{topic}`
+    /// * `max_delivery_attempts` - at least 1.
+    pub fn with_dead_letter_policy(
+        mut self,
+        dead_letter_topic: impl Into<String>,
+        max_delivery_attempts: i32,
+    ) -> Self {
+        self.dead_letter = Some(DeadLetter {
+            topic: dead_letter_topic.into(),
+            max_delivery_attempts: max_delivery_attempts.max(1),
+        });
+        self
+    }
+
+    pub fn subscription(&self) -> &str {
+        &self.subscription
+    }
+
+    /// Runs until the stream fails with a non-retryable error.
+    ///
+    /// Handlers run concurrently on the tokio runtime. Messages must be settled with
+    /// [`Message::ack`] or [`Message::nack`]; a message dropped unsettled is nacked.
+    pub async fn run<F, Fut>(&self, handler: F) -> Result<(), Error>
+    where
+        F: Fn(Message) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.run_until(handler, future::pending()).await
+    }
+
+    /// Like [`Subscriber::run`], stopping once `shutdown` completes.
+    ///
+    /// After the stream is closed, running handlers are awaited and pending
+    /// acknowledgements are sent before returning.
+    pub async fn run_until<F, Fut>(
+        &self,
+        handler: F,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), Error>
+    where
+        F: Fn(Message) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            commands,
+            leases: Mutex::new(HashMap::new()),
+            max_delivery_attempts: self.dead_letter.as_ref().map(|d| d.max_delivery_attempts),
+            attempts: Mutex::new(HashMap::new()),
+        });
+        let batcher = tokio::spawn(flush_acks(
+            self.client.clone(),
+            self.subscription.clone(),
+            self.dead_letter.as_ref().map(|d| d.topic.clone()),
+            receiver,
+        ));
+        let extender = tokio::spawn(extend_leases(
+            self.client.clone(),
+            self.subscription.clone(),
+            shared.clone(),
+            self.ack_deadline,
+            self.max_extension,
+            self.lease_interval,
+        ));
+
+        let mut handlers = JoinSet::new();
+        let result = tokio::select! {
+            result = self.receive(&handler, &shared, &mut handlers) => result,
+            _ = shutdown => Ok(()),
+        };
+
+        while handlers.join_next().await.is_some() {}
+        extender.abort();
+        let _ = shared.commands.send(Command::Flush);
+        let _ = batcher.await;
+        result
+    }
+
+    /// Reopens the stream until it fails with a non-retryable error.
+    async fn receive<F, Fut>(
+        &self,
+        handler: &F,
+        shared: &Arc<Shared>,
+        handlers: &mut JoinSet<()>,
+    ) -> Result<(), Error>
+    where
+        F: Fn(Message) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let flow_control = FlowControl {
+            messages: Arc::new(Semaphore::new(self.max_outstanding_messages)),
+            bytes: Arc::new(Semaphore::new(self.max_outstanding_bytes)),
+            max_bytes: self.max_outstanding_bytes,
+        };
+        let client_id = format!("{:016x}", rand::thread_rng().gen::<u64>());
+        let mut attempt = 1;
+        loop {
+            let mut received = false;
+            let result = self
+                .stream(
+                    &client_id,
+                    handler,
+                    shared,
+                    &flow_control,
+                    handlers,
+                    &mut received,
+                )
+                .await;
+            // A stream that delivered messages was healthy; back off from the start.
+            if received {
+                attempt = 1;
+            }
+            if let Err(error) = result {
+                if !self.reconnect_policy.is_retryable(&error) {
+                    return Err(error);
+                }
+            }
+            // Jittered, so that subscribers disconnected together do not reconnect
+            // together. The backoff stays at its maximum however long this goes on.
+            tokio::time::sleep(self.reconnect_policy.delay(attempt)).await;
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    /// Dispatches messages until the stream ends.
+    async fn stream<F, Fut>(
+        &self,
+        client_id: &str,
+        handler: &F,
+        shared: &Arc<Shared>,
+        flow_control: &FlowControl,
+        handlers: &mut JoinSet<()>,
+        received: &mut bool,
+    ) -> Result<(), Error>
+    where
+        F: Fn(Message) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        // Kept open for the lifetime of the stream, which would end with it.
+        let (requests, receiver) = futures::channel::mpsc::unbounded();
+        let _ = requests.unbounded_send(StreamingPullRequest {
+            subscription: self.subscription.clone(),
+            stream_ack_deadline_seconds: self.ack_deadline.as_secs() as i32,
+            client_id: client_id.to_owned(),
+            max_outstanding_messages: self.max_outstanding_messages as i64,
+            max_outstanding_bytes: self.max_outstanding_bytes as i64,
+            ..Default::default()
+        });
+        let mut stream = self
+            .client
+            .streaming_pull(&self.subscription, receiver)
+            .await?;
+
+        while let Some(response) = stream.message().await? {
+            *received = true;
+            for mut received_message in response.received_messages {
+                shared.count_attempt(&mut received_message);
+                shared
+                    .leases
+                    .lock()
+                    .unwrap()
+                    .insert(received_message.ack_id.clone(), Instant::now());
+                let permits = flow_control.acquire(&received_message).await;
+                let message = Message::new(received_message, shared.clone(), permits);
+                handlers.spawn(handler(message));
+            }
+            // Reap finished handlers so the set does not grow without bound.
+            while handlers.try_join_next().is_some() {}
+        }
+        Ok(())
+    }
+}
+
+struct FlowControl {
+    messages: Arc<Semaphore>,
+    bytes: Arc<Semaphore>,
+    max_bytes: usize,
+}
+
+impl FlowControl {
+    /// Waits until the message fits within the limits. A message larger than the byte
+    /// limit waits until nothing else is outstanding.
+    async fn acquire(&self, message: &ReceivedMessage) -> Permits {
+        let size = message.message.as_ref().map_or(0, |m| m.data.len());
+        // The semaphores are never closed.
+        let message = self.messages.clone().acquire_owned().await.unwrap();
+        let bytes = self
+            .bytes
+            .clone()
+            .acquire_many_owned(size.min(self.max_bytes) as u32)
+            .await
+            .unwrap();
+        Permits {
+            _message: message,
+            _bytes: bytes,
+        }
+    }
+}
+
+struct Permits {
+    _message: OwnedSemaphorePermit,
+    _bytes: OwnedSemaphorePermit,
+}
+
+/// State shared between the subscriber and its messages.
+struct Shared {
+    commands: mpsc::UnboundedSender<Command>,
+    /// Ack ids being extended, with the time they were received.
+    leases: Mutex<HashMap<String, Instant>>,
+    /// Of the dead-letter policy, if any.
+    max_delivery_attempts: Option<i32>,
+    /// Deliveries by message id with the time of the last one, counted when the
+    /// service does not.
+    attempts: Mutex<HashMap<String, (i32, Instant)>>,
+}
+
+impl Shared {
+    fn count_attempt(&self, received: &mut ReceivedMessage) {
+        if self.max_delivery_attempts.is_none() || received.delivery_attempt > 0 {
+            return;
+        }
+        let message_id = received
+            .message
+            .as_ref()
+            .map_or_else(String::new, |m| m.message_id.clone());
+        let mut attempts = self.attempts.lock().unwrap();
+        let (attempt, delivered) = attempts.entry(message_id).or_insert((0, Instant::now()));
+        *attempt += 1;
+        *delivered = Instant::now();
+        received.delivery_attempt = *attempt;
+    }
+}
+
+enum Command {
+    /// Reports the outcome to the sender, if any.
+    Ack(String, Option<oneshot::Sender<AckStatus>>),
+    /// Makes the message available for redelivery after the delay.
+    Nack(String, Duration),
+    /// Republishes the message to the dead-letter topic, then acknowledges it.
+    DeadLetter(String, PubsubMessage),
+    Flush,
+}
+
+/// A message received by a [`Subscriber`].
+pub struct Message {
+    message: PubsubMessage,
+    ack_id: String,
+    delivery_attempt: i32,
+    lease: Option<(Arc<Shared>, Permits)>,
+}
+
+impl fmt::Debug for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Message")
+            .field("message", &self.message)
+            .field("ack_id", &self.ack_id)
+            .field("delivery_attempt", &self.delivery_attempt)
+            .finish()
+    }
+}
+
+impl Message {
+    fn new(received: ReceivedMessage, shared: Arc<Shared>, permits: Permits) -> Self {
+        Self {
+            message: received.message.unwrap_or_default(),
+            ack_id: received.ack_id,
+            delivery_attempt: received.delivery_attempt,
+            lease: Some((shared, permits)),
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.message.data
+    }
+
+    pub fn attributes(&self) -> &HashMap<String, String> {
+        &self.message.attributes
+    }
+
+    pub fn message_id(&self) -> &str {
+        &self.message.message_id
+    }
+
+    pub fn publish_time(&self) -> Option<SystemTime> {
+        self.message
+            .publish_time
+            .clone()
+            .and_then(|time| time.try_into().ok())
+    }
+
+    pub fn ordering_key(&self) -> &str {
+        &self.message.ordering_key
+    }
+
+    pub fn ack_id(&self) -> &str {
+        &self.ack_id
+    }
+
+    /// How many times the message was delivered, including this time. Only known for
+    /// subscriptions with a dead-letter policy, or subscribers with one, see
+    /// [`Subscriber::with_dead_letter_policy`].
+    pub fn delivery_attempt(&self) -> Option<i32> {
+        (self.delivery_attempt > 0).then_some(self.delivery_attempt)
+    }
+
+    pub fn message(&self) -> &PubsubMessage {
+        &self.message
+    }
+
+    /// Acknowledges the message, so that it is not delivered again.
+    pub fn ack(mut self) {
+        self.settle_ack(None);
+    }
+
+    /// Like [`Message::ack`], waiting for the outcome. On subscriptions with
+    /// exactly-once delivery, the message is guaranteed not to be redelivered only if
+    /// it is [`AckStatus::Success`]; otherwise, processing should be considered not
+    /// committed.
+    pub async fn ack_confirmed(mut self) -> AckStatus {
+        let (sender, receiver) = oneshot::channel();
+        self.settle_ack(Some(sender));
+        receiver
+            .await
+            .unwrap_or_else(|_| AckStatus::Other("the subscriber stopped".to_owned()))
+    }
+
+    fn settle_ack(&mut self, result: Option<oneshot::Sender<AckStatus>>) {
+        if let Some((shared, _permits)) = self.lease.take() {
+            shared.leases.lock().unwrap().remove(&self.ack_id);
+            shared.attempts.lock().unwrap().remove(self.message_id());
+            // Fails only after the subscriber stopped, the lease then simply expires.
+            let _ = shared
+                .commands
+                .send(Command::Ack(self.ack_id.clone(), result));
+        }
+    }
+
+    /// Makes the message available for redelivery right away.
+    pub fn nack(mut self) {
+        self.settle(Duration::ZERO, None, false);
+    }
+
+    /// Makes the message available for redelivery after `delay`, at most 600 seconds
+    /// and truncated to whole seconds. The delay does not apply to messages forwarded
+    /// to the dead-letter topic.
+    pub fn nack_with_delay(mut self, delay: Duration) {
+        self.settle(delay.min(MAX_ACK_DEADLINE), None, false);
+    }
+
+    /// Like [`Message::nack`], recording `error` in the
+    /// [`DEAD_LETTER_ERROR_ATTRIBUTE`] if the message is forwarded to the dead-letter
+    /// topic.
+    pub fn nack_with_error(mut self, error: impl fmt::Display) {
+        self.settle(Duration::ZERO, Some(error.to_string()), false);
+    }
+
+    /// Forwards the message to the dead-letter topic right away, whatever its delivery
+    /// attempt, e.g. when it cannot be decoded. Without a dead-letter policy, see
+    /// [`Subscriber::with_dead_letter_policy`], this is [`Message::nack_with_error`].
+    pub fn dead_letter(mut self, error: impl fmt::Display) {
+        self.settle(Duration::ZERO, Some(error.to_string()), true);
+    }
+
+    fn settle(&mut self, delay: Duration, error: Option<String>, dead_letter: bool) {
+        let (shared, _permits) = match self.lease.take() {
+            Some(lease) => lease,
+            None => return,
+        };
+        shared.leases.lock().unwrap().remove(&self.ack_id);
+        let ack_id = self.ack_id.clone();
+        let command = match shared.max_delivery_attempts {
+            Some(max) if dead_letter || self.delivery_attempt >= max => {
+                shared.attempts.lock().unwrap().remove(self.message_id());
+                Command::DeadLetter(ack_id, self.dead_letter_message(error))
+            }
+            _ => Command::Nack(ack_id, delay),
+        };
+        // Fails only after the subscriber stopped, the lease then simply expires.
+        let _ = shared.commands.send(command);
+    }
+
+    fn dead_letter_message(&mut self, error: Option<String>) -> PubsubMessage {
+        let mut attributes = std::mem::take(&mut self.message.attributes);
+        attributes.insert(
+            DEAD_LETTER_MESSAGE_ID_ATTRIBUTE.to_owned(),
+            self.message.message_id.clone(),
+        );
+        attributes.insert(
+            DEAD_LETTER_DELIVERY_ATTEMPTS_ATTRIBUTE.to_owned(),
+            self.delivery_attempt.to_string(),
+        );
+        if let Some(error) = error {
+            attributes.insert(DEAD_LETTER_ERROR_ATTRIBUTE.to_owned(), error);
+        }
+        PubsubMessage {
+            data: std::mem::take(&mut self.message.data),
+            attributes,
+            ordering_key: std::mem::take(&mut self.message.ordering_key),
+            ..Default::default()
+        }
+    }
+}
+
+impl Drop for Message {
+    fn drop(&mut self) {
+        self.settle(Duration::ZERO, None, false);
+    }
+}
+
+/// Sends acks and nacks in batches until told to flush for the last time.
+///
+/// Messages to dead-letter are republished concurrently, then acked once published or
+/// nacked if that failed.
+async fn flush_acks(
+    client: PubSubClient,
+    subscription: String,
+    dead_letter_topic: Option<String>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+) {
+    let mut acks = Vec::new();
+    // By delay, in whole seconds as sent to the service.
+    let mut nacks: HashMap<u64, Vec<String>> = HashMap::new();
+    let mut dead_letters = JoinSet::new();
+    let mut interval = tokio::time::interval(ACK_FLUSH_INTERVAL);
+    loop {
+        let done = tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Ack(ack_id, result)) => {
+                    acks.push((ack_id, result));
+                    false
+                }
+                Some(Command::Nack(ack_id, delay)) => {
+                    nacks.entry(delay.as_secs()).or_default().push(ack_id);
+                    false
+                }
+                Some(Command::DeadLetter(ack_id, mut message)) => {
+                    message
+                        .attributes
+                        .insert(DEAD_LETTER_SUBSCRIPTION_ATTRIBUTE.to_owned(), subscription.clone());
+                    let (client, topic) = (client.clone(), dead_letter_topic.clone());
+                    dead_letters.spawn(async move {
+                        let topic = topic.unwrap_or_default();
+                        let published = client.publish_message(&topic, message).await.is_ok();
+                        (ack_id, published)
+                    });
+                    false
+                }
+                Some(Command::Flush) | None => true,
+            },
+            Some(result) = dead_letters.join_next() => {
+                settle_dead_letter(result, &mut acks, &mut nacks);
+                false
+            }
+            _ = interval.tick() => {
+                flush(&client, &subscription, &mut acks, &mut nacks).await;
+                false
+            }
+        };
+        if done {
+            while let Some(result) = dead_letters.join_next().await {
+                settle_dead_letter(result, &mut acks, &mut nacks);
+            }
+            flush(&client, &subscription, &mut acks, &mut nacks).await;
+            return;
+        }
+        if acks.len() >= MAX_ACK_IDS_PER_REQUEST
+            || nacks
+                .values()
+                .any(|ack_ids| ack_ids.len() >= MAX_ACK_IDS_PER_REQUEST)
+        {
+            flush(&client, &subscription, &mut acks, &mut nacks).await;
+        }
+    }
+}
+
+fn settle_dead_letter(
+    result: Result<(String, bool), JoinError>,
+    acks: &mut Vec<PendingAck>,
+    nacks: &mut HashMap<u64, Vec<String>>,
+) {
+    match result {
+        Ok((ack_id, true)) => acks.push((ack_id, None)),
+        Ok((ack_id, false)) => nacks.entry(0).or_default().push(ack_id),
+        // The lease expires.
+        Err(_) => {}
+    }
+}
+
+/// An ack id, with where to report the outcome of acknowledging it.
+type PendingAck = (String, Option<oneshot::Sender<AckStatus>>);
+
+/// Failures are only reported to [`Message::ack_confirmed`]: the messages are then
+/// redelivered, as they would be if the subscriber had crashed.
+async fn flush(
+    client: &PubSubClient,
+    subscription: &str,
+    acks: &mut Vec<PendingAck>,
+    nacks: &mut HashMap<u64, Vec<String>>,
+) {
+    while !acks.is_empty() {
+        let chunk: Vec<_> = acks
+            .drain(..acks.len().min(MAX_ACK_IDS_PER_REQUEST))
+            .collect();
+        let ack_ids = chunk.iter().map(|(ack_id, _)| ack_id.clone()).collect();
+        let mut statuses = client.acknowledge_confirmed(subscription, ack_ids).await;
+        for (ack_id, result) in chunk {
+            let status = match &mut statuses {
+                Ok(statuses) => statuses.remove(&ack_id).unwrap_or(AckStatus::Success),
+                Err(error) => AckStatus::Other(error.to_string()),
+            };
+            if let Some(result) = result {
+                let _ = result.send(status);
+            }
+        }
+    }
+    for (delay, ack_ids) in nacks.iter() {
+        for ack_ids in ack_ids.chunks(MAX_ACK_IDS_PER_REQUEST) {
+            let _ = client
+                .modify_ack_deadline(subscription, ack_ids.to_vec(), Duration::from_secs(*delay))
+                .await;
+        }
+    }
+    nacks.clear();
+}
+
+/// Extends the deadline of outstanding messages every `interval`, until they were
+/// outstanding for `max_extension`.
+///
+/// Also forgets the delivery attempts of messages that were not redelivered within
+/// `max_extension` and the longest ack deadline, e.g. because another subscriber
+/// acked them.
+async fn extend_leases(
+    client: PubSubClient,
+    subscription: String,
+    shared: Arc<Shared>,
+    ack_deadline: Duration,
+    max_extension: Duration,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let ack_ids: Vec<_> = {
+            let mut leases = shared.leases.lock().unwrap();
+            leases.retain(|_, received| received.elapsed() < max_extension);
+            leases.keys().cloned().collect()
+        };
+        shared
+            .attempts
+            .lock()
+            .unwrap()
+            .retain(|_, (_, delivered)| delivered.elapsed() < max_extension + MAX_ACK_DEADLINE);
+        for ack_ids in ack_ids.chunks(MAX_ACK_IDS_PER_REQUEST) {
+            let statuses = client
+                .modify_ack_deadline_confirmed(&subscription, ack_ids.to_vec(), ack_deadline)
+                .await
+                .unwrap_or_default();
+            // Leases that cannot be extended any more, e.g. expired on a subscription
+            // with exactly-once delivery.
+            let mut leases = shared.leases.lock().unwrap();
+            for (ack_id, status) in statuses {
+                if !status.is_success() {
+                    leases.remove(&ack_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use pretty_assertions::assert_eq;
+    use tokio::sync::Notify;
+
+    use super::*;
+    use crate::pubsub::{
+        fake::{self, FakePubSub},
+        SubscriptionBuilder,
+    };
+
+    const TOPIC: &str = "projects/test/topics/topic";
+    const SUBSCRIPTION: &str = "projects/test/subscriptions/subscription";
+
+    async fn setup() -> anyhow::Result<(PubSubClient, FakePubSub)> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        fake.create_subscription(SUBSCRIPTION, TOPIC);
+        Ok((client, fake))
+    }
+
+    /// Completes once `count` reaches `expected`.
+    async fn reached(count: &AtomicUsize, notify: &Notify, expected: usize) {
+        while count.load(Ordering::SeqCst) < expected {
+            notify.notified().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run() -> anyhow::Result<()> {
+        let (client, fake) = setup().await?;
+        for i in 0..10 {
+            client.publish(TOPIC, format!("message {}", i)).await?;
+        }
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let count = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(Notify::new());
+        let subscriber = Subscriber::new(client, SUBSCRIPTION);
+        subscriber
+            .run_until(
+                |message| {
+                    let (received, count, notify) =
+                        (received.clone(), count.clone(), notify.clone());
+                    async move {
+                        received
+                            .lock()
+                            .unwrap()
+                            .push(String::from_utf8(message.data().to_vec()).unwrap());
+                        message.ack();
+                        count.fetch_add(1, Ordering::SeqCst);
+                        notify.notify_one();
+                    }
+                },
+                reached(&count, &notify, 10),
+            )
+            .await?;
+
+        let mut received = received.lock().unwrap().clone();
+        received.sort();
+        assert_eq!(
+            received,
+            (0..10)
+                .map(|i| format!("message {}", i))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(fake.unacked(SUBSCRIPTION), 0);
+        assert!(fake.request_count("Acknowledge") < 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flow_control() -> anyhow::Result<()> {
+        let (client, _fake) = setup().await?;
+        for i in 0..6 {
+            client.publish(TOPIC, format!("message {}", i)).await?;
+        }
+
+        let outstanding = Arc::new(AtomicUsize::new(0));
+        let max_outstanding = Arc::new(AtomicUsize::new(0));
+        let count = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(Notify::new());
+        let subscriber = Subscriber::new(client, SUBSCRIPTION).with_max_outstanding_messages(2);
+        subscriber
+            .run_until(
+                |message| {
+                    let (outstanding, max_outstanding, count, notify) = (
+                        outstanding.clone(),
+                        max_outstanding.clone(),
+                        count.clone(),
+                        notify.clone(),
+                    );
+                    async move {
+                        let current = outstanding.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_outstanding.fetch_max(current, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        outstanding.fetch_sub(1, Ordering::SeqCst);
+                        message.ack();
+                        count.fetch_add(1, Ordering::SeqCst);
+                        notify.notify_one();
+                    }
+                },
+                reached(&count, &notify, 6),
+            )
+            .await?;
+
+        assert_eq!(max_outstanding.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flow_control_per_stream() -> anyhow::Result<()> {
+        use futures::{stream, StreamExt};
+
+        let (client, fake) = setup().await?;
+        for i in 0..2 {
+            client.publish(TOPIC, format!("message {}", i)).await?;
+        }
+        // Messages leased by another puller do not count against the stream.
+        assert_eq!(client.pull(SUBSCRIPTION).await?.received_messages.len(), 2);
+        assert_eq!(fake.request_count("Pull"), 1);
+        for i in 2..4 {
+            client.publish(TOPIC, format!("message {}", i)).await?;
+        }
+
+        let request = StreamingPullRequest {
+            subscription: SUBSCRIPTION.to_owned(),
+            stream_ack_deadline_seconds: 60,
+            max_outstanding_messages: 1,
+            ..Default::default()
+        };
+        let requests = stream::iter(vec![request]).chain(stream::pending());
+        let mut responses = client.streaming_pull(SUBSCRIPTION, requests).await?;
+        let response = tokio::time::timeout(Duration::from_secs(5), responses.next())
+            .await?
+            .unwrap()?;
+        assert_eq!(response.received_messages.len(), 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), responses.next())
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nack_and_lease_extension() -> anyhow::Result<()> {
+        let (client, fake) = setup().await?;
+        client.publish(TOPIC, "message").await?;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(Notify::new());
+        let mut subscriber = Subscriber::new(client, SUBSCRIPTION);
+        subscriber.lease_interval = Duration::from_millis(20);
+        subscriber
+            .run_until(
+                |message| {
+                    let (count, notify) = (count.clone(), notify.clone());
+                    async move {
+                        if count.fetch_add(1, Ordering::SeqCst) == 0 {
+                            // Dropped without being settled, so nacked.
+                            return notify.notify_one();
+                        }
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        message.ack();
+                        notify.notify_one();
+                    }
+                },
+                async {
+                    reached(&count, &notify, 2).await;
+                    while fake.unacked(SUBSCRIPTION) > 0 {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                },
+            )
+            .await?;
+
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        assert!(fake.request_count("ModifyAckDeadline") >= 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reconnect() -> anyhow::Result<()> {
+        let (client, fake) = setup().await?;
+        client.publish(TOPIC, "before").await?;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(Notify::new());
+        let subscriber = Subscriber::new(client.clone(), SUBSCRIPTION).with_reconnect_policy(
+            RetryPolicy::default()
+                .with_initial_backoff(Duration::from_millis(1))
+                .with_retryable_codes(STREAMING_PULL_RETRY_CODES),
+        );
+        subscriber
+            .run_until(
+                |message| {
+                    let (count, notify) = (count.clone(), notify.clone());
+                    async move {
+                        message.ack();
+                        count.fetch_add(1, Ordering::SeqCst);
+                        notify.notify_one();
+                    }
+                },
+                async {
+                    reached(&count, &notify, 1).await;
+                    fake.disconnect_streams();
+                    client.publish(TOPIC, "after").await.unwrap();
+                    reached(&count, &notify, 2).await;
+                },
+            )
+            .await?;
+
+        assert!(fake.request_count("StreamingPull") >= 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nack_with_delay() -> anyhow::Result<()> {
+        let (client, _fake) = setup().await?;
+        client.publish(TOPIC, "message").await?;
+
+        let deliveries = Arc::new(Mutex::new(Vec::new()));
+        let count = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(Notify::new());
+        Subscriber::new(client, SUBSCRIPTION)
+            .run_until(
+                |message| {
+                    let (deliveries, count, notify) =
+                        (deliveries.clone(), count.clone(), notify.clone());
+                    async move {
+                        deliveries.lock().unwrap().push(Instant::now());
+                        match count.fetch_add(1, Ordering::SeqCst) {
+                            0 => message.nack_with_delay(Duration::from_secs(1)),
+                            _ => message.ack(),
+                        }
+                        notify.notify_one();
+                    }
+                },
+                reached(&count, &notify, 2),
+            )
+            .await?;
+
+        let deliveries = deliveries.lock().unwrap();
+        assert!(deliveries[1] - deliveries[0] >= Duration::from_secs(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_attempts_expire() -> anyhow::Result<()> {
+        let (client, _fake) = setup().await?;
+        let (commands, _receiver) = mpsc::unbounded_channel();
+        let expired = Instant::now()
+            .checked_sub(MAX_ACK_DEADLINE + Duration::from_secs(1))
+            .unwrap();
+        let shared = Arc::new(Shared {
+            commands,
+            leases: Mutex::new(HashMap::new()),
+            max_delivery_attempts: Some(5),
+            attempts: Mutex::new(HashMap::from([
+                ("expired".to_owned(), (1, expired)),
+                ("recent".to_owned(), (1, Instant::now())),
+            ])),
+        });
+        let extender = tokio::spawn(extend_leases(
+            client,
+            SUBSCRIPTION.to_owned(),
+            shared.clone(),
+            Duration::from_secs(10),
+            Duration::ZERO,
+            Duration::from_millis(10),
+        ));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        extender.abort();
+
+        let attempts: Vec<_> = shared.attempts.lock().unwrap().keys().cloned().collect();
+        assert_eq!(attempts, ["recent"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter() -> anyhow::Result<()> {
+        const DEAD_LETTER_TOPIC: &str = "projects/test/topics/dead-letter";
+        const DEAD_LETTER_SUBSCRIPTION: &str = "projects/test/subscriptions/dead-letter";
+        let (client, fake) = setup().await?;
+        fake.create_topic(DEAD_LETTER_TOPIC);
+        fake.create_subscription(DEAD_LETTER_SUBSCRIPTION, DEAD_LETTER_TOPIC);
+        client
+            .publish_message(
+                TOPIC,
+                PubsubMessage {
+                    data: b"poison".to_vec(),
+                    attributes: [("type".to_owned(), "order".to_owned())].into(),
+                    ordering_key: "customer-1".to_owned(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        Subscriber::new(client.clone(), SUBSCRIPTION)
+            .with_dead_letter_policy(DEAD_LETTER_TOPIC, 3)
+            .run_until(
+                |message| {
+                    let attempts = attempts.clone();
+                    async move {
+                        attempts.lock().unwrap().push(message.delivery_attempt());
+                        message.nack_with_error("cannot parse");
+                    }
+                },
+                async {
+                    while fake.unacked(DEAD_LETTER_SUBSCRIPTION) == 0 {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                },
+            )
+            .await?;
+
+        assert_eq!(*attempts.lock().unwrap(), [Some(1), Some(2), Some(3)]);
+        assert_eq!(fake.unacked(SUBSCRIPTION), 0);
+        let received = client
+            .pull(DEAD_LETTER_SUBSCRIPTION)
+            .await?
+            .received_messages;
+        let message = received[0].message.clone().unwrap_or_default();
+        assert_eq!(message.data, b"poison");
+        assert_eq!(message.ordering_key, "customer-1");
+        let mut attributes: Vec<_> = message.attributes.into_iter().collect();
+        attributes.sort();
+        assert_eq!(
+            attributes,
+            [
+                (DEAD_LETTER_DELIVERY_ATTEMPTS_ATTRIBUTE, "3"),
+                (DEAD_LETTER_ERROR_ATTRIBUTE, "cannot parse"),
+                (DEAD_LETTER_MESSAGE_ID_ATTRIBUTE, "1"),
+                (DEAD_LETTER_SUBSCRIPTION_ATTRIBUTE, SUBSCRIPTION),
+                ("type", "order"),
+            ]
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ack_confirmed() -> anyhow::Result<()> {
+        let (client, fake) = setup().await?;
+        client
+            .update_subscription(
+                SubscriptionBuilder::new(SUBSCRIPTION, TOPIC)
+                    .with_exactly_once_delivery(true)
+                    .build(),
+                &["enable_exactly_once_delivery"],
+            )
+            .await?;
+        client.publish(TOPIC, "message").await?;
+        fake.fail_acks_transiently(1);
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let count = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(Notify::new());
+        Subscriber::new(client, SUBSCRIPTION)
+            .run_until(
+                |message| {
+                    let (statuses, count, notify) =
+                        (statuses.clone(), count.clone(), notify.clone());
+                    async move {
+                        let status = message.ack_confirmed().await;
+                        statuses.lock().unwrap().push(status);
+                        count.fetch_add(1, Ordering::SeqCst);
+                        notify.notify_one();
+                    }
+                },
+                reached(&count, &notify, 1),
+            )
+            .await?;
+
+        assert_eq!(*statuses.lock().unwrap(), [AckStatus::Success]);
+        assert_eq!(fake.unacked(SUBSCRIPTION), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_not_found() -> anyhow::Result<()> {
+        let (client, _fake) = setup().await?;
+        let result = Subscriber::new(client, "projects/test/subscriptions/missing")
+            .run(|message| async move { message.ack() })
+            .await;
+        assert!(matches!(result, Err(error) if error.is_not_found()));
+        Ok(())
+    }
+}
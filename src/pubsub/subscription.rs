@@ -0,0 +1,214 @@
+use std::{collections::HashMap, time::Duration};
+
+pub use crate::proto::google::pubsub::v1::PushConfig;
+use crate::proto::google::pubsub::v1::{
+    push_config::{AuthenticationMethod, OidcToken},
+    DeadLetterPolicy, ExpirationPolicy, RetryPolicy, Subscription,
+};
+
+/// Builds the [`Subscription`] passed to
+/// [`PubSubClient::create_subscription`](super::PubSubClient::create_subscription).
+#[derive(Debug, Clone)]
+pub struct SubscriptionBuilder {
+    subscription: Subscription,
+}
+
+impl SubscriptionBuilder {
+    /// # Arguments
+    /// * `name`  - in the format `projects/{project}/subscriptions/{subscription}`
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub fn new(name: impl Into<String>, topic: impl Into<String>) -> Self {
+        Self {
+            subscription: Subscription {
+                name: name.into(),
+                topic: topic.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// How long a pulled message may stay unacknowledged before it is redelivered,
+    /// between 10 and 600 seconds. Defaults to 10 seconds.
+    pub fn with_ack_deadline(mut self, ack_deadline: Duration) -> Self {
+        self.subscription.ack_deadline_seconds = ack_deadline.as_secs() as i32;
+        self
+    }
+
+    /// How long unacknowledged messages are kept, between 10 minutes and 7 days.
+    /// Defaults to 7 days.
+    pub fn with_message_retention_duration(mut self, retention: Duration) -> Self {
+        self.subscription.message_retention_duration = Some(duration(retention));
+        self
+    }
+
+    /// Keeps acknowledged messages for the retention duration too, so that
+    /// [`PubSubClient::seek_to_time`](super::PubSubClient::seek_to_time) can replay them.
+    pub fn with_retain_acked_messages(mut self, retain_acked_messages: bool) -> Self {
+        self.subscription.retain_acked_messages = retain_acked_messages;
+        self
+    }
+
+    /// Forwards messages delivered `max_delivery_attempts` times, between 5 and 100, to
+    /// `dead_letter_topic`. The Pub/Sub service account needs permission to publish to
+    /// that topic and to subscribe to this subscription.
+    pub fn with_dead_letter_policy(
+        mut self,
+        dead_letter_topic: impl Into<String>,
+        max_delivery_attempts: i32,
+    ) -> Self {
+        self.subscription.dead_letter_policy = Some(DeadLetterPolicy {
+            dead_letter_topic: dead_letter_topic.into(),
+            max_delivery_attempts,
+        });
+        self
+    }
+
+    /// Delays the redelivery of nacked or expired messages by an exponential backoff
+    /// between `minimum_backoff` and `maximum_backoff`, both at most 600 seconds.
+    /// Without it, messages are redelivered right away.
+    pub fn with_retry_policy(
+        mut self,
+        minimum_backoff: Duration,
+        maximum_backoff: Duration,
+    ) -> Self {
+        self.subscription.retry_policy = Some(RetryPolicy {
+            minimum_backoff: Some(duration(minimum_backoff)),
+            maximum_backoff: Some(duration(maximum_backoff)),
+        });
+        self
+    }
+
+    /// Only delivers messages whose attributes match `filter`, e.g.
+    /// `attributes.type = "order"`. Cannot be changed later.
+    pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+        self.subscription.filter = filter.into();
+        self
+    }
+
+    /// Guarantees that acknowledged messages are not redelivered, as long as they were
+    /// acknowledged before their deadline.
+    pub fn with_exactly_once_delivery(mut self, exactly_once_delivery: bool) -> Self {
+        self.subscription.enable_exactly_once_delivery = exactly_once_delivery;
+        self
+    }
+
+    /// Delivers messages sharing an ordering key in the order they were published.
+    pub fn with_message_ordering(mut self, message_ordering: bool) -> Self {
+        self.subscription.enable_message_ordering = message_ordering;
+        self
+    }
+
+    /// Pushes messages to `push_endpoint` instead of waiting for them to be pulled.
+    pub fn with_push_config(mut self, push_config: PushConfig) -> Self {
+        self.subscription.push_config = Some(push_config);
+        self
+    }
+
+    /// Shorthand for [`SubscriptionBuilder::with_push_config`], authenticating pushes
+    /// with an OIDC token of `service_account_email` when given.
+    pub fn with_push_endpoint(
+        self,
+        push_endpoint: impl Into<String>,
+        service_account_email: Option<&str>,
+    ) -> Self {
+        self.with_push_config(PushConfig {
+            push_endpoint: push_endpoint.into(),
+            authentication_method: service_account_email.map(|email| {
+                AuthenticationMethod::OidcToken(OidcToken {
+                    service_account_email: email.to_owned(),
+                    audience: Default::default(),
+                })
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Deletes the subscription after `ttl` without activity, at least 1 day. `None`
+    /// keeps it forever. Defaults to 31 days.
+    pub fn with_expiration(mut self, ttl: Option<Duration>) -> Self {
+        self.subscription.expiration_policy = Some(ExpirationPolicy {
+            ttl: ttl.map(duration),
+        });
+        self
+    }
+
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.subscription.labels.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.subscription.labels.extend(labels);
+        self
+    }
+
+    pub fn build(self) -> Subscription {
+        self.subscription
+    }
+}
+
+fn duration(duration: Duration) -> prost_types::Duration {
+    prost_types::Duration {
+        seconds: duration.as_secs() as i64,
+        nanos: duration.subsec_nanos() as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let subscription = SubscriptionBuilder::new(
+            "projects/test/subscriptions/subscription",
+            "projects/test/topics/topic",
+        )
+        .with_ack_deadline(Duration::from_secs(30))
+        .with_dead_letter_policy("projects/test/topics/dead-letter", 5)
+        .with_retry_policy(Duration::from_secs(1), Duration::from_millis(60_500))
+        .with_push_endpoint(
+            "https://example.com/push",
+            Some("push@test.iam.gserviceaccount.com"),
+        )
+        .with_expiration(None)
+        .with_label("team", "infra")
+        .build();
+
+        assert_eq!(
+            subscription,
+            Subscription {
+                name: "projects/test/subscriptions/subscription".to_owned(),
+                topic: "projects/test/topics/topic".to_owned(),
+                ack_deadline_seconds: 30,
+                dead_letter_policy: Some(DeadLetterPolicy {
+                    dead_letter_topic: "projects/test/topics/dead-letter".to_owned(),
+                    max_delivery_attempts: 5,
+                }),
+                retry_policy: Some(RetryPolicy {
+                    minimum_backoff: Some(prost_types::Duration {
+                        seconds: 1,
+                        nanos: 0
+                    }),
+                    maximum_backoff: Some(prost_types::Duration {
+                        seconds: 60,
+                        nanos: 500_000_000
+                    }),
+                }),
+                push_config: Some(PushConfig {
+                    push_endpoint: "https://example.com/push".to_owned(),
+                    authentication_method: Some(AuthenticationMethod::OidcToken(OidcToken {
+                        service_account_email: "push@test.iam.gserviceaccount.com".to_owned(),
+                        audience: String::new(),
+                    })),
+                    ..Default::default()
+                }),
+                expiration_policy: Some(ExpirationPolicy { ttl: None }),
+                labels: [("team".to_owned(), "infra".to_owned())].into(),
+                ..Default::default()
+            }
+        );
+    }
+}
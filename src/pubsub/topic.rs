@@ -0,0 +1,76 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::proto::google::pubsub::v1::{Encoding, MessageStoragePolicy, SchemaSettings, Topic};
+
+/// Builds the [`Topic`] passed to
+/// [`PubSubClient::create_topic`](super::PubSubClient::create_topic).
+#[derive(Debug, Clone)]
+pub struct TopicBuilder {
+    topic: Topic,
+}
+
+impl TopicBuilder {
+    /// # Arguments
+    /// * `name` - in the format `projects/{project}/topics/{topic}`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            topic: Topic {
+                name: name.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Rejects published messages that do not conform to `schema`, in the format
+    /// `projects/{project}/schemas/{schema}`, when encoded with `encoding`.
+    pub fn with_schema(mut self, schema: impl Into<String>, encoding: Encoding) -> Self {
+        self.topic.schema_settings = Some(SchemaSettings {
+            schema: schema.into(),
+            encoding: encoding as i32,
+        });
+        self
+    }
+
+    /// Keeps published messages for `retention`, between 10 minutes and 31 days, so
+    /// that subscriptions can seek back to them.
+    pub fn with_message_retention_duration(mut self, retention: Duration) -> Self {
+        self.topic.message_retention_duration = Some(prost_types::Duration {
+            seconds: retention.as_secs() as i64,
+            nanos: retention.subsec_nanos() as i32,
+        });
+        self
+    }
+
+    /// Only stores messages in these regions, e.g. `europe-west1`.
+    pub fn with_allowed_persistence_regions<I, S>(mut self, regions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.topic.message_storage_policy = Some(MessageStoragePolicy {
+            allowed_persistence_regions: regions.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Encrypts messages with a Cloud KMS key, in the format
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*`.
+    pub fn with_kms_key_name(mut self, kms_key_name: impl Into<String>) -> Self {
+        self.topic.kms_key_name = kms_key_name.into();
+        self
+    }
+
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.topic.labels.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.topic.labels.extend(labels);
+        self
+    }
+
+    pub fn build(self) -> Topic {
+        self.topic
+    }
+}
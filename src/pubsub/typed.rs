@@ -0,0 +1,269 @@
+use std::{future::Future, marker::PhantomData, sync::Arc};
+
+use futures::future::{self, Either};
+
+use crate::{
+    error::Error,
+    proto::google::pubsub::v1::PubsubMessage,
+    pubsub::{
+        client::PubSubError,
+        codec::{JsonCodec, MessageCodec, CONTENT_TYPE_ATTRIBUTE},
+        AckStatus, Message, MessageBuilder, PublishHandle, Publisher, Subscriber,
+    },
+};
+
+/// Publishes values of `T` to a topic, encoded by a [`MessageCodec`].
+pub struct TypedPublisher<T, C = JsonCodec> {
+    publisher: Publisher,
+    topic: String,
+    codec: Arc<C>,
+    _type: PhantomData<fn(&T)>,
+}
+
+impl<T, C> Clone for TypedPublisher<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            publisher: self.publisher.clone(),
+            topic: self.topic.clone(),
+            codec: self.codec.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, C: MessageCodec<T>> TypedPublisher<T, C> {
+    /// # Arguments
+    /// * `topic` - in the format `projects/{project}/topics/{topic}`
+    pub fn new(publisher: Publisher, topic: impl Into<String>, codec: C) -> Self {
+        Self {
+            publisher,
+            topic: topic.into(),
+            codec: Arc::new(codec),
+            _type: PhantomData,
+        }
+    }
+
+    /// Encodes `value`, with the [`CONTENT_TYPE_ATTRIBUTE`] of the codec. Attributes or
+    /// an ordering key may be added before publishing it with
+    /// [`TypedPublisher::publish_message`].
+    pub fn message(&self, value: &T) -> Result<MessageBuilder, Error> {
+        let data = self.codec.encode(value)?;
+        Ok(MessageBuilder::new(data)
+            .with_attribute(CONTENT_TYPE_ATTRIBUTE, self.codec.content_type()))
+    }
+
+    /// Encodes `value` and publishes it, see [`Publisher::publish`].
+    pub async fn publish(&self, value: &T) -> Result<PublishHandle, Error> {
+        let message = self.message(value)?.build();
+        Ok(self.publish_message(message).await)
+    }
+
+    /// Publishes an already encoded message, see [`Publisher::publish_message`]. The
+    /// [`CONTENT_TYPE_ATTRIBUTE`] is not added, unlike with [`TypedPublisher::message`].
+    pub async fn publish_message(&self, message: PubsubMessage) -> PublishHandle {
+        self.publisher.publish_message(&self.topic, message).await
+    }
+}
+
+/// Receives values of `T` from a subscription, decoded by a [`MessageCodec`].
+pub struct TypedSubscriber<T, C = JsonCodec> {
+    subscriber: Subscriber,
+    codec: Arc<C>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T, C> Clone for TypedSubscriber<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            subscriber: self.subscriber.clone(),
+            codec: self.codec.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, C> TypedSubscriber<T, C>
+where
+    T: Send + 'static,
+    C: MessageCodec<T>,
+{
+    pub fn new(subscriber: Subscriber, codec: C) -> Self {
+        Self {
+            subscriber,
+            codec: Arc::new(codec),
+            _type: PhantomData,
+        }
+    }
+
+    /// Like [`Subscriber::run`], handing decoded values to `handler`.
+    ///
+    /// Messages that cannot be decoded are not handed to `handler` but dead-lettered
+    /// with the [`PubSubError::Decode`] error, see [`Message::dead_letter`].
+    pub async fn run<F, Fut>(&self, handler: F) -> Result<(), Error>
+    where
+        F: Fn(TypedMessage<T>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.run_until(handler, future::pending()).await
+    }
+
+    /// Like [`TypedSubscriber::run`], stopping once `shutdown` completes.
+    pub async fn run_until<F, Fut>(
+        &self,
+        handler: F,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), Error>
+    where
+        F: Fn(TypedMessage<T>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.subscriber
+            .run_until(
+                |message| match self.decode(&message) {
+                    Ok(value) => Either::Left(handler(TypedMessage { value, message })),
+                    Err(error) => {
+                        message.dead_letter(error);
+                        Either::Right(future::ready(()))
+                    }
+                },
+                shutdown,
+            )
+            .await
+    }
+
+    /// Fails with [`PubSubError::Decode`] if the data cannot be decoded, or if the
+    /// [`CONTENT_TYPE_ATTRIBUTE`] is set to another type than the codec's.
+    pub fn decode(&self, message: &Message) -> Result<T, Error> {
+        match message.attributes().get(CONTENT_TYPE_ATTRIBUTE) {
+            Some(content_type) if content_type != self.codec.content_type() => {
+                Err(PubSubError::Decode(format!("unexpected content type {}", content_type)).into())
+            }
+            _ => self.codec.decode(message.data()),
+        }
+    }
+}
+
+/// A decoded message received by a [`TypedSubscriber`].
+#[derive(Debug)]
+pub struct TypedMessage<T> {
+    value: T,
+    message: Message,
+}
+
+impl<T> TypedMessage<T> {
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    /// The value and the message, which must still be settled.
+    pub fn into_parts(self) -> (T, Message) {
+        (self.value, self.message)
+    }
+
+    /// See [`Message::ack`].
+    pub fn ack(self) {
+        self.message.ack();
+    }
+
+    /// See [`Message::ack_confirmed`].
+    pub async fn ack_confirmed(self) -> AckStatus {
+        self.message.ack_confirmed().await
+    }
+
+    /// See [`Message::nack`].
+    pub fn nack(self) {
+        self.message.nack();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        time::Duration,
+    };
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::pubsub::{fake, subscriber::DEAD_LETTER_ERROR_ATTRIBUTE};
+
+    const TOPIC: &str = "projects/test/topics/topic";
+    const SUBSCRIPTION: &str = "projects/test/subscriptions/subscription";
+    const DEAD_LETTER_TOPIC: &str = "projects/test/topics/dead-letter";
+    const DEAD_LETTER_SUBSCRIPTION: &str = "projects/test/subscriptions/dead-letter";
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        id: String,
+        amount: i64,
+    }
+
+    #[tokio::test]
+    async fn test_typed() -> anyhow::Result<()> {
+        let (client, fake) = fake::start().await?;
+        fake.create_topic(TOPIC);
+        fake.create_subscription(SUBSCRIPTION, TOPIC);
+        fake.create_topic(DEAD_LETTER_TOPIC);
+        fake.create_subscription(DEAD_LETTER_SUBSCRIPTION, DEAD_LETTER_TOPIC);
+
+        let order = Order {
+            id: "o-1".to_owned(),
+            amount: 3,
+        };
+        let publisher = TypedPublisher::new(Publisher::new(client.clone()), TOPIC, JsonCodec);
+        publisher.publish(&order).await?.await?;
+        client.publish(TOPIC, "not json").await?;
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let count = Arc::new(AtomicUsize::new(0));
+        let subscriber = Subscriber::new(client.clone(), SUBSCRIPTION)
+            .with_dead_letter_policy(DEAD_LETTER_TOPIC, 5);
+        TypedSubscriber::new(subscriber, JsonCodec)
+            .run_until(
+                |message: TypedMessage<Order>| {
+                    let (received, count) = (received.clone(), count.clone());
+                    async move {
+                        let content_type =
+                            message.message().attributes()[CONTENT_TYPE_ATTRIBUTE].clone();
+                        received
+                            .lock()
+                            .unwrap()
+                            .push((message.value().clone(), content_type));
+                        message.ack();
+                        count.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+                async {
+                    while count.load(Ordering::SeqCst) < 1
+                        || fake.unacked(DEAD_LETTER_SUBSCRIPTION) == 0
+                    {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                },
+            )
+            .await?;
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [(order, "application/json".to_owned())]
+        );
+        let dead_letters = client
+            .pull(DEAD_LETTER_SUBSCRIPTION)
+            .await?
+            .received_messages;
+        let dead_letter = dead_letters[0].message.clone().unwrap_or_default();
+        assert_eq!(dead_letter.data, b"not json");
+        assert!(dead_letter.attributes[DEAD_LETTER_ERROR_ATTRIBUTE]
+            .starts_with("pubsub error: cannot decode message"));
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use tonic::Code;
+
+use crate::error::Error;
+
+type Predicate = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+/// How failed calls are retried.
+///
+/// The delay before attempt `n + 1` is `initial_backoff * multiplier^(n - 1)`, capped at
+/// `max_backoff`. With jitter enabled the actual delay is drawn uniformly from
+/// `[0, delay]`. Retrying stops after `max_attempts` attempts, when the next attempt
+/// would start after `total_timeout`, or when the error is not retryable.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    total_timeout: Option<Duration>,
+    retryable: Predicate,
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("total_timeout", &self.total_timeout)
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    /// The backoff Google's client libraries use for most APIs: 100ms initial,
+    /// 60s maximum, multiplier 1.3 and a 60s overall deadline. Retries transient
+    /// gRPC codes and HTTP statuses, see [`Error::is_retryable`].
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(60),
+            multiplier: 1.3,
+            jitter: true,
+            total_timeout: Some(Duration::from_secs(60)),
+            retryable: Arc::new(Error::is_retryable),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::default().with_max_attempts(1)
+    }
+
+    /// Retries gRPC calls failing with one of `codes`.
+    pub fn with_retryable_codes(self, codes: &'static [Code]) -> Self {
+        self.with_retryable(move |error| match error {
+            Error::Status(status) => codes.contains(&status.code()),
+            _ => error.is_transport_failure(),
+        })
+    }
+
+    /// Includes the first attempt; `1` disables retries.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// `None` retries until `max_attempts` is reached.
+    pub fn with_total_timeout(mut self, total_timeout: Option<Duration>) -> Self {
+        self.total_timeout = total_timeout;
+        self
+    }
+
+    pub fn with_retryable(
+        mut self,
+        retryable: impl Fn(&Error) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        (self.retryable)(error)
+    }
+
+    /// The delay before the attempt following attempt number `attempt` (starting at 1),
+    /// before jitter is applied.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let seconds = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        // Past `Duration::MAX`, or non-finite, once the multiplier has grown enough.
+        Duration::try_from_secs_f64(seconds)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// Calls `f` until it succeeds or the policy gives up, returning the last error.
+    ///
+    /// When the server asks for a longer delay through `google.rpc.RetryInfo`, that
+    /// delay is used instead of the backoff.
+    pub async fn run<T, F, Fut>(&self, mut f: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let error = match f().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            if attempt >= self.max_attempts || !self.is_retryable(&error) {
+                return Err(error);
+            }
+
+            let delay = self
+                .delay(attempt)
+                .max(error.retry_delay().unwrap_or_default());
+            if let Some(total_timeout) = self.total_timeout {
+                match started.elapsed().checked_add(delay) {
+                    Some(next) if next <= total_timeout => {}
+                    _ => return Err(error),
+                }
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// [`RetryPolicy::backoff`] with jitter applied, if enabled.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.backoff(attempt);
+        if self.jitter && !backoff.is_zero() {
+            rand::thread_rng().gen_range(Duration::ZERO..=backoff)
+        } else {
+            backoff
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_attempts(3)
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_multiplier(2.0)
+            .with_max_backoff(Duration::from_millis(300));
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(300));
+        assert_eq!(policy.backoff(2000), Duration::from_millis(300));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_transient_errors() {
+        let calls = AtomicU32::new(0);
+        let result = fast_policy()
+            .run(|| async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(tonic::Status::unavailable("try again").into()),
+                    n => Ok(n),
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_gives_up() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = fast_policy()
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(tonic::Status::unavailable("try again").into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = fast_policy()
+            .with_retryable_codes(&[Code::Unavailable])
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(tonic::Status::invalid_argument("bad").into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_server_delay_past_deadline() {
+        use prost::Message;
+
+        use crate::proto::google::rpc;
+
+        let retry_info = rpc::RetryInfo {
+            retry_delay: Some(prost_types::Duration {
+                seconds: i64::MAX,
+                nanos: 0,
+            }),
+        };
+        let status = rpc::Status {
+            code: Code::Unavailable as i32,
+            message: "come back later".into(),
+            details: vec![prost_types::Any {
+                type_url: "type.googleapis.com/google.rpc.RetryInfo".into(),
+                value: retry_info.encode_to_vec(),
+            }],
+        };
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = fast_policy()
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(tonic::Status::with_details(
+                    Code::Unavailable,
+                    "come back later",
+                    status.encode_to_vec().into(),
+                )
+                .into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,4 +1,6 @@
+pub mod bucket;
 pub mod client;
+pub mod hmac_key;
 pub mod object;
 
 pub use client::Client;
@@ -0,0 +1,24 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// cf. https://cloud.google.com/storage/docs/json_api/v1/buckets#resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketResource {
+    // Value: "storage#bucket"
+    pub kind: String,
+    pub id: String,
+    pub self_link: String,
+    pub project_number: String,
+    pub name: String,
+    pub time_created: String,
+    pub updated: String,
+    pub metageneration: String,
+    pub location: String,
+    pub location_type: Option<String>,
+    pub storage_class: String,
+    pub etag: String,
+    pub default_event_based_hold: Option<bool>,
+    pub labels: Option<HashMap<String, String>>,
+}
@@ -1,14 +1,28 @@
+use std::sync::Arc;
+
 use reqwest::{header::HeaderMap, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize};
 
 use crate::{
-    auth::TokenManager,
-    error::{AuthError, Error},
+    auth::{DefaultCredentials, NoAuth, TokenManager, TokenProvider},
+    endpoint::{emulator_endpoint, Endpoint},
+    error::{ApiError, Error},
+    options::CallOptions,
+    pagination::{ListOptions, Page, PageStream},
+    retry::RetryPolicy,
 };
 
-use super::object::ObjectResource;
+use super::{
+    bucket::BucketResource,
+    hmac_key::HmacKeyMetadata,
+    object::{ObjectEntry, ObjectQuery, ObjectResource},
+};
 
-const ENDPOINT: &str = "https://storage.googleapis.com/storage/v1";
-const ENDPOINT_UPLOAD: &str = "https://storage.googleapis.com/upload/storage/v1";
+/// When set, clients connect to the emulator at this address without TLS or credentials.
+pub const EMULATOR_HOST_ENV: &str = "STORAGE_EMULATOR_HOST";
+pub const ENDPOINT: &str = "https://storage.googleapis.com";
+const API_PATH: &str = "storage/v1";
+const UPLOAD_PATH: &str = "upload/storage/v1";
 const SCOPES: [&str; 2] = [
     "https://www.googleapis.com/auth/cloud-platform",
     "https://www.googleapis.com/auth/devstorage.full_control",
@@ -20,95 +34,401 @@ pub enum CloudStorageError {
     ErrorResponse {
         status: StatusCode,
         response: String,
+        /// `response` parsed as a JSON error body, if it is one. Boxed to keep
+        /// [`Error`] small.
+        error: Option<Box<ApiError>>,
     },
 }
 
+impl CloudStorageError {
+    pub(crate) fn error_response(status: StatusCode, response: String) -> Self {
+        Self::ErrorResponse {
+            status,
+            error: ApiError::parse(&response).map(Box::new),
+            response,
+        }
+    }
+}
+
+/// The envelope of every JSON API list response.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListResponse<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+    /// Only set when listing objects with a delimiter.
+    #[serde(default = "Vec::new")]
+    prefixes: Vec<String>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Clone)]
 pub struct Client {
     token_manager: TokenManager,
     http: reqwest::Client,
+    endpoint: Url,
+    retry_policy: Option<RetryPolicy>,
 }
 
-impl Client {
-    pub async fn new() -> Result<Self, AuthError> {
-        Ok(Self {
-            token_manager: TokenManager::new(&SCOPES).await?,
-            http: reqwest::Client::new(),
+#[derive(Default)]
+pub struct ClientBuilder {
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    endpoint: Option<Endpoint>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl ClientBuilder {
+    pub fn with_token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.token_provider = Some(Arc::new(token_provider));
+        self
+    }
+
+    /// Defaults to the emulator in [`EMULATOR_HOST_ENV`] if set, otherwise [`ENDPOINT`].
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Replaces the per-method defaults. By default reads are retried on transient
+    /// errors and uploads, which are not idempotent without preconditions, are not.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub async fn build(self) -> Result<Client, Error> {
+        let emulator = match self.endpoint {
+            Some(_) => None,
+            None => emulator_endpoint(EMULATOR_HOST_ENV)?,
+        };
+        let token_provider: Arc<dyn TokenProvider> = match (self.token_provider, &emulator) {
+            (Some(token_provider), _) => token_provider,
+            (None, Some(_)) => Arc::new(NoAuth),
+            (None, None) => Arc::new(DefaultCredentials::new().await?),
+        };
+        let endpoint = match self.endpoint.or(emulator) {
+            Some(endpoint) => endpoint,
+            None => Endpoint::new(ENDPOINT)?,
+        };
+
+        Ok(Client {
+            token_manager: TokenManager::with_provider(token_provider, &SCOPES),
+            http: endpoint.http_client()?,
+            endpoint: endpoint.url().clone(),
+            retry_policy: self.retry_policy,
         })
     }
+}
+
+impl Client {
+    pub async fn new() -> Result<Self, Error> {
+        Self::builder().build().await
+    }
 
-    pub async fn object(&mut self, bucket: &str, object: &str) -> Result<Vec<u8>, Error> {
-        let url = Self::build_uri(bucket, Some(object))?;
-        let res = self
-            .http
-            .get(url)
-            .headers(self.headers().await?)
-            .query(&[("alt", "media")])
-            .send()
-            .await?;
-        if res.status().is_success() {
-            Ok(res.bytes().await?.to_vec())
-        } else {
-            Err(CloudStorageError::ErrorResponse {
-                status: res.status(),
-                response: res.text().await?,
-            }
-            .into())
-        }
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    pub async fn object(&self, bucket: &str, object: &str) -> Result<Vec<u8>, Error> {
+        self.object_with_options(bucket, object, &CallOptions::default())
+            .await
+    }
+
+    pub async fn object_with_options(
+        &self,
+        bucket: &str,
+        object: &str,
+        options: &CallOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let url = Self::build_uri(&self.endpoint, bucket, Some(object))?;
+
+        self.retry_policy(options, RetryPolicy::default)
+            .run(|| {
+                options.attempt(async {
+                    let res = self
+                        .http
+                        .get(url.clone())
+                        .headers(self.headers(options).await?)
+                        .query(&[("alt", "media")])
+                        .send()
+                        .await?;
+                    if res.status().is_success() {
+                        Ok(res.bytes().await?.to_vec())
+                    } else {
+                        Err(
+                            CloudStorageError::error_response(res.status(), res.text().await?)
+                                .into(),
+                        )
+                    }
+                })
+            })
+            .await
     }
 
     pub async fn create_object(
-        &mut self,
+        &self,
+        bucket: &str,
+        name: &str,
+        object: impl Into<Vec<u8>>,
+        mime_type: impl AsRef<str>,
+    ) -> Result<ObjectResource, Error> {
+        self.create_object_with_options(bucket, name, object, mime_type, &CallOptions::default())
+            .await
+    }
+
+    pub async fn create_object_with_options(
+        &self,
         bucket: &str,
         name: &str,
         object: impl Into<Vec<u8>>,
         mime_type: impl AsRef<str>,
+        options: &CallOptions,
     ) -> Result<ObjectResource, Error> {
-        let url = Self::build_upload_uri(bucket, Some(""))?;
+        let url = Self::build_upload_uri(&self.endpoint, bucket, Some(""))?;
         let data = object.into();
 
-        let res = self
-            .http
-            .post(url)
-            .query(&[
-                // cf. https://cloud.google.com/storage/docs/json_api/v1/objects/insert#parameters
-                ("uploadType", "media"),
-                ("name", name),
-            ])
-            .headers(self.headers().await?)
-            .header("content-type", mime_type.as_ref())
-            .header("content-length", data.len())
-            .body(data)
-            .send()
-            .await?;
-        if res.status().is_success() {
-            Ok(res.json().await?)
-        } else {
-            Err(CloudStorageError::ErrorResponse {
-                status: res.status(),
-                response: res.text().await?,
-            }
-            .into())
+        self.retry_policy(options, RetryPolicy::none)
+            .run(|| {
+                options.attempt(async {
+                    let res = self
+                        .http
+                        .post(url.clone())
+                        .query(&[
+                            // cf. https://cloud.google.com/storage/docs/json_api/v1/objects/insert#parameters
+                            ("uploadType", "media"),
+                            ("name", name),
+                        ])
+                        .headers(self.headers(options).await?)
+                        .header("content-type", mime_type.as_ref())
+                        .header("content-length", data.len())
+                        .body(data.clone())
+                        .send()
+                        .await?;
+                    if res.status().is_success() {
+                        Ok(res.json().await?)
+                    } else {
+                        Err(
+                            CloudStorageError::error_response(res.status(), res.text().await?)
+                                .into(),
+                        )
+                    }
+                })
+            })
+            .await
+    }
+
+    /// Streams all buckets of a project, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `project` - project id or number
+    pub fn stream_buckets(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, BucketResource> {
+        self.stream_buckets_with_options(project, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_buckets_with_options(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, BucketResource> {
+        self.stream(
+            Self::build_list_uri(&self.endpoint, &["b"]),
+            vec![("project", project.to_owned())],
+            list_options,
+            options,
+        )
+    }
+
+    /// Streams all objects of a bucket, fetching further pages as needed.
+    pub fn stream_objects(
+        &self,
+        bucket: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, ObjectResource> {
+        self.stream_objects_with_options(bucket, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_objects_with_options(
+        &self,
+        bucket: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, ObjectResource> {
+        self.list_objects_with_options(bucket, &ObjectQuery::new(), list_options, options)
+            .filter_map_items(|entry| match entry {
+                ObjectEntry::Object(object) => Some(*object),
+                ObjectEntry::Prefix(_) => None,
+            })
+    }
+
+    /// Streams the objects of a bucket matching `query`, fetching further pages as
+    /// needed. With a delimiter, each page ends with the prefixes that objects were
+    /// grouped under.
+    pub fn list_objects(
+        &self,
+        bucket: &str,
+        query: &ObjectQuery,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, ObjectEntry> {
+        self.list_objects_with_options(bucket, query, list_options, &CallOptions::default())
+    }
+
+    pub fn list_objects_with_options(
+        &self,
+        bucket: &str,
+        query: &ObjectQuery,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, ObjectEntry> {
+        self.stream_with(
+            Self::build_list_uri(&self.endpoint, &["b", bucket, "o"]),
+            query.to_query(),
+            list_options,
+            options,
+            |response: ListResponse<ObjectResource>| {
+                let objects = response
+                    .items
+                    .into_iter()
+                    .map(|object| ObjectEntry::Object(Box::new(object)));
+                let prefixes = response.prefixes.into_iter().map(ObjectEntry::Prefix);
+                objects.chain(prefixes).collect()
+            },
+        )
+    }
+
+    /// Streams the metadata of all HMAC keys of a project, fetching further pages as needed.
+    ///
+    /// # Arguments
+    /// * `project` - project id or number
+    pub fn stream_hmac_keys(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+    ) -> PageStream<'_, HmacKeyMetadata> {
+        self.stream_hmac_keys_with_options(project, list_options, &CallOptions::default())
+    }
+
+    pub fn stream_hmac_keys_with_options(
+        &self,
+        project: &str,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, HmacKeyMetadata> {
+        self.stream(
+            Self::build_list_uri(&self.endpoint, &["projects", project, "hmacKeys"]),
+            vec![],
+            list_options,
+            options,
+        )
+    }
+
+    /// Pages through a list endpoint of the JSON API.
+    fn stream<T: DeserializeOwned + Send + 'static>(
+        &self,
+        url: Result<Url, url::ParseError>,
+        query: Vec<(&'static str, String)>,
+        list_options: &ListOptions,
+        options: &CallOptions,
+    ) -> PageStream<'_, T> {
+        self.stream_with(url, query, list_options, options, |response| response.items)
+    }
+
+    /// Like [`Client::stream`], taking the items of each page from the response with
+    /// `items`.
+    fn stream_with<T: DeserializeOwned + Send + 'static, U: Send + 'static>(
+        &self,
+        url: Result<Url, url::ParseError>,
+        mut query: Vec<(&'static str, String)>,
+        list_options: &ListOptions,
+        options: &CallOptions,
+        items: fn(ListResponse<T>) -> Vec<U>,
+    ) -> PageStream<'_, U> {
+        if let Some(page_size) = list_options.page_size() {
+            query.push(("maxResults", page_size.to_string()));
         }
+        let options = options.clone();
+        PageStream::new(move |page_token| {
+            let url = url.clone();
+            let mut query = query.clone();
+            query.extend(page_token.map(|page_token| ("pageToken", page_token)));
+            let options = options.clone();
+            async move {
+                let url = url?;
+                let response: ListResponse<T> = self
+                    .retry_policy(&options, RetryPolicy::default)
+                    .run(|| {
+                        options.attempt(async {
+                            let res = self
+                                .http
+                                .get(url.clone())
+                                .headers(self.headers(&options).await?)
+                                .query(&query)
+                                .send()
+                                .await?;
+                            if res.status().is_success() {
+                                Ok(res.json().await?)
+                            } else {
+                                Err(CloudStorageError::error_response(
+                                    res.status(),
+                                    res.text().await?,
+                                )
+                                .into())
+                            }
+                        })
+                    })
+                    .await?;
+                let next_page_token = response.next_page_token.clone();
+                Ok(Page::new(items(response), next_page_token))
+            }
+        })
     }
 
-    fn build_uri<T: AsRef<str>>(bucket: &str, object: Option<T>) -> Result<Url, url::ParseError> {
-        Self::build_uri_by_endpoint(ENDPOINT, bucket, object)
+    /// The retry policy in `options`, the client's retry policy or `default`, in that order.
+    fn retry_policy(
+        &self,
+        options: &CallOptions,
+        default: impl FnOnce() -> RetryPolicy,
+    ) -> RetryPolicy {
+        options
+            .retry_policy()
+            .or(self.retry_policy.as_ref())
+            .cloned()
+            .unwrap_or_else(default)
+    }
+
+    fn build_uri<T: AsRef<str>>(
+        endpoint: &Url,
+        bucket: &str,
+        object: Option<T>,
+    ) -> Result<Url, url::ParseError> {
+        Self::build_uri_by_path(endpoint, API_PATH, bucket, object)
     }
 
     fn build_upload_uri<T: AsRef<str>>(
+        endpoint: &Url,
         bucket: &str,
         object: Option<T>,
     ) -> Result<Url, url::ParseError> {
-        Self::build_uri_by_endpoint(ENDPOINT_UPLOAD, bucket, object)
+        Self::build_uri_by_path(endpoint, UPLOAD_PATH, bucket, object)
     }
 
-    fn build_uri_by_endpoint<T: AsRef<str>>(
-        endpoint: &str,
+    fn build_uri_by_path<T: AsRef<str>>(
+        endpoint: &Url,
+        path: &str,
         bucket: &str,
         object: Option<T>,
     ) -> Result<Url, url::ParseError> {
-        let mut url = Url::parse(endpoint)?;
-        url.path_segments_mut().unwrap().push("b").push(bucket);
+        let mut url = endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+            .pop_if_empty()
+            .extend(path.split('/'))
+            .push("b")
+            .push(bucket);
 
         if let Some(object) = object {
             url.path_segments_mut()
@@ -120,14 +440,25 @@ impl Client {
         Ok(url)
     }
 
-    async fn headers(&mut self) -> Result<HeaderMap, AuthError> {
+    fn build_list_uri(endpoint: &Url, segments: &[&str]) -> Result<Url, url::ParseError> {
+        let mut url = endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+            .pop_if_empty()
+            .extend(API_PATH.split('/'))
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn headers(&self, options: &CallOptions) -> Result<HeaderMap, Error> {
         let mut header = HeaderMap::new();
-        header.insert(
-            reqwest::header::AUTHORIZATION,
-            format!("Bearer {}", self.token_manager.get_token().await?.as_str())
-                .parse()
-                .unwrap(),
-        );
+        options.apply_to_headers(&mut header)?;
+        if let Some(token) = self.token_manager.get_token().await? {
+            header.insert(
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {}", token.as_str()).parse().unwrap(),
+            );
+        }
         Ok(header)
     }
 }
@@ -135,6 +466,7 @@ impl Client {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::TryStreamExt;
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
@@ -166,9 +498,148 @@ mod tests {
         #[case] expected: &str,
     ) -> anyhow::Result<()> {
         assert_eq!(
-            Client::build_uri(bucket, object)?,
+            Client::build_uri(&Url::parse(ENDPOINT)?, bucket, object)?,
             Url::parse(expected).unwrap()
         );
         Ok(())
     }
+
+    #[test]
+    fn test_build_upload_uri() -> anyhow::Result<()> {
+        assert_eq!(
+            Client::build_upload_uri(
+                &Url::parse("http://localhost:4443")?,
+                "test-bucket",
+                Some("")
+            )?,
+            Url::parse("http://localhost:4443/upload/storage/v1/b/test-bucket/o/")?
+        );
+        Ok(())
+    }
+
+    fn object(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "kind": "storage#object",
+            "id": format!("list-bucket/{}/1", name),
+            "selfLink": "",
+            "name": name,
+            "bucket": "list-bucket",
+            "generation": "1",
+            "metageneration": "1",
+            "contentType": "text/plain",
+            "timeCreated": "2021-12-01T00:00:00.000Z",
+            "updated": "2021-12-01T00:00:00.000Z",
+            "storageClass": "STANDARD",
+            "size": "0",
+            "md5Hash": "",
+            "mediaLink": "",
+            "crc32c": "",
+            "etag": ""
+        })
+    }
+
+    #[tokio::test]
+    async fn test_stream_objects() -> anyhow::Result<()> {
+        let _first = mockito::mock("GET", "/storage/v1/b/list-bucket/o")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "maxResults".into(),
+                "2".into(),
+            ))
+            .with_body(
+                serde_json::json!({
+                    "kind": "storage#objects",
+                    "nextPageToken": "token",
+                    "items": [object("a"), object("b")]
+                })
+                .to_string(),
+            )
+            .create();
+        let _second = mockito::mock("GET", "/storage/v1/b/list-bucket/o")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("maxResults".into(), "2".into()),
+                mockito::Matcher::UrlEncoded("pageToken".into(), "token".into()),
+            ]))
+            .with_body(
+                serde_json::json!({"kind": "storage#objects", "items": [object("c")]}).to_string(),
+            )
+            .create();
+
+        let client = Client::builder()
+            .with_token_provider(NoAuth)
+            .with_endpoint(Endpoint::new(&mockito::server_url())?)
+            .build()
+            .await?;
+        let names: Vec<_> = client
+            .stream_objects("list-bucket", &ListOptions::new().with_page_size(2))
+            .items()
+            .map_ok(|object| object.name)
+            .try_collect()
+            .await?;
+
+        assert_eq!(names, vec!["a", "b", "c"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_objects() -> anyhow::Result<()> {
+        let query = |extra: Vec<mockito::Matcher>| {
+            let mut matchers = vec![
+                mockito::Matcher::UrlEncoded("prefix".into(), "logs/".into()),
+                mockito::Matcher::UrlEncoded("delimiter".into(), "/".into()),
+                mockito::Matcher::UrlEncoded("startOffset".into(), "logs/a".into()),
+                mockito::Matcher::UrlEncoded("endOffset".into(), "logs/z".into()),
+                mockito::Matcher::UrlEncoded("matchGlob".into(), "**.json".into()),
+                mockito::Matcher::UrlEncoded("versions".into(), "true".into()),
+            ];
+            matchers.extend(extra);
+            mockito::Matcher::AllOf(matchers)
+        };
+        let _first = mockito::mock("GET", "/storage/v1/b/tree-bucket/o")
+            .match_query(query(vec![]))
+            .with_body(
+                serde_json::json!({
+                    "kind": "storage#objects",
+                    "nextPageToken": "token",
+                    "prefixes": ["logs/2021/"],
+                    "items": [object("logs/a.json")]
+                })
+                .to_string(),
+            )
+            .create();
+        let _second = mockito::mock("GET", "/storage/v1/b/tree-bucket/o")
+            .match_query(query(vec![mockito::Matcher::UrlEncoded(
+                "pageToken".into(),
+                "token".into(),
+            )]))
+            .with_body(
+                serde_json::json!({"kind": "storage#objects", "prefixes": ["logs/2022/"]})
+                    .to_string(),
+            )
+            .create();
+
+        let client = Client::builder()
+            .with_token_provider(NoAuth)
+            .with_endpoint(Endpoint::new(&mockito::server_url())?)
+            .build()
+            .await?;
+        let query = ObjectQuery::new()
+            .with_prefix("logs/")
+            .with_delimiter("/")
+            .with_start_offset("logs/a")
+            .with_end_offset("logs/z")
+            .with_match_glob("**.json")
+            .with_versions(true);
+        let entries: Vec<_> = client
+            .list_objects("tree-bucket", &query, &ListOptions::new())
+            .items()
+            .map_ok(|entry| match entry {
+                ObjectEntry::Object(object) => object.name,
+                ObjectEntry::Prefix(prefix) => prefix,
+            })
+            .try_collect()
+            .await?;
+
+        assert_eq!(entries, vec!["logs/a.json", "logs/2021/", "logs/2022/"]);
+        Ok(())
+    }
 }
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// cf. https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys#resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacKeyMetadata {
+    // Value: "storage#hmacKeyMetadata"
+    pub kind: String,
+    pub id: String,
+    pub self_link: String,
+    pub access_id: String,
+    pub project_id: String,
+    pub service_account_email: String,
+    /// `ACTIVE`, `INACTIVE` or `DELETED`.
+    pub state: String,
+    pub time_created: String,
+    pub updated: String,
+    pub etag: String,
+}
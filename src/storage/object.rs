@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+/// cf. https://cloud.google.com/storage/docs/json_api/v1/objects#resource
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectResource {
@@ -72,3 +73,86 @@ pub struct ObjectAclProjectTeam {
     pub project_number: String,
     pub team: String,
 }
+
+/// An item of [`Client::list_objects`](super::Client::list_objects).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectEntry {
+    Object(Box<ObjectResource>),
+    /// A name prefix up to and including the delimiter, shared by the objects it
+    /// stands for, like a directory.
+    Prefix(String),
+}
+
+/// Restricts the objects listed by [`Client::list_objects`](super::Client::list_objects).
+///
+/// cf. https://cloud.google.com/storage/docs/json_api/v1/objects/list#parameters
+#[derive(Debug, Clone, Default)]
+pub struct ObjectQuery {
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    start_offset: Option<String>,
+    end_offset: Option<String>,
+    versions: bool,
+    match_glob: Option<String>,
+}
+
+impl ObjectQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only objects whose name starts with `prefix`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Objects whose name contains `delimiter` after the prefix are listed once, as an
+    /// [`ObjectEntry::Prefix`] up to the delimiter. With `/`, lists one level of a
+    /// hierarchy.
+    pub fn with_delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        self.delimiter = Some(delimiter.into());
+        self
+    }
+
+    /// Only objects whose name is at least `start_offset`, lexicographically.
+    pub fn with_start_offset(mut self, start_offset: impl Into<String>) -> Self {
+        self.start_offset = Some(start_offset.into());
+        self
+    }
+
+    /// Only objects whose name is less than `end_offset`, lexicographically.
+    pub fn with_end_offset(mut self, end_offset: impl Into<String>) -> Self {
+        self.end_offset = Some(end_offset.into());
+        self
+    }
+
+    /// Lists every generation of the objects, including noncurrent ones.
+    pub fn with_versions(mut self, versions: bool) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    /// Only objects whose name matches `glob`, e.g. `**/*.json`.
+    pub fn with_match_glob(mut self, glob: impl Into<String>) -> Self {
+        self.match_glob = Some(glob.into());
+        self
+    }
+
+    pub(crate) fn to_query(&self) -> Vec<(&'static str, String)> {
+        let mut query: Vec<_> = [
+            ("prefix", &self.prefix),
+            ("delimiter", &self.delimiter),
+            ("startOffset", &self.start_offset),
+            ("endOffset", &self.end_offset),
+            ("matchGlob", &self.match_glob),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.clone().map(|value| (key, value)))
+        .collect();
+        if self.versions {
+            query.push(("versions", "true".to_owned()));
+        }
+        query
+    }
+}
@@ -1,18 +1,25 @@
+use prost_types::FieldMask;
 use tonic::{IntoRequest, Request};
 
-use crate::error::Error;
+use crate::{error::Error, options::CallOptions};
 
 pub(crate) async fn construct_request<T: IntoRequest<T>>(
     request: T,
-    token: &str,
+    token: Option<&str>,
     headers: Vec<(&str, &str)>,
+    options: &CallOptions,
 ) -> Result<Request<T>, Error> {
     let mut request = request.into_request();
-    let metadata = request.metadata_mut();
-    metadata.insert(
-        "authorization",
-        format!("Bearer {}", token).parse().unwrap(),
-    );
+    if let Some(timeout) = options.timeout() {
+        request.set_timeout(timeout);
+    }
+    options.apply_to_metadata(request.metadata_mut())?;
+    if let Some(token) = token {
+        request.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+    }
 
     request.metadata_mut().insert(
         "x-goog-request-params",
@@ -26,6 +33,12 @@ pub(crate) async fn construct_request<T: IntoRequest<T>>(
     Ok(request)
 }
 
+pub(crate) fn field_mask(paths: &[&str]) -> FieldMask {
+    FieldMask {
+        paths: paths.iter().map(|path| path.to_string()).collect(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -49,8 +62,9 @@ mod tests {
 
         let actual = construct_request(
             req.clone(),
-            token,
+            Some(token),
             vec![("parent", parent), ("key", "value")],
+            &CallOptions::new().with_quota_project("quota-proj"),
         )
         .await?;
 
@@ -66,6 +80,10 @@ mod tests {
                     .unwrap()
             )
         );
+        assert_eq!(
+            actual.metadata().get("x-goog-user-project"),
+            Some(&"quota-proj".parse().unwrap())
+        );
         assert_eq!(actual.into_inner(), req);
 
         Ok(())